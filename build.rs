@@ -0,0 +1,23 @@
+extern crate alloc;
+
+#[allow(dead_code)]
+#[path = "src/table.rs"]
+mod table;
+
+fn main() {
+    println!("cargo::rerun-if-changed=build.rs");
+    println!("cargo::rerun-if-changed=src/table.rs");
+
+    if std::env::var_os("CARGO_FEATURE_PRECOMPUTED_TABLE").is_none() {
+        return;
+    }
+
+    let out_dir = std::env::var_os("OUT_DIR").unwrap();
+    let table = table::SuuhaiTable::new();
+
+    std::fs::write(
+        std::path::Path::new(&out_dir).join("suuhai_table.bin"),
+        table.as_bytes(),
+    )
+    .unwrap();
+}
@@ -0,0 +1,26 @@
+//! Built for a bare-metal target in CI to make sure the shanten core stays
+//! `no_std`.
+
+#![no_std]
+
+use shanten_count::shanten::{Hand, SuuhaiTable};
+
+pub fn shanten(tiles: &[usize]) -> i8 {
+    let mut hand = Hand::default();
+
+    for &t in tiles {
+        hand[t] += 1;
+    }
+
+    hand.shanten()
+}
+
+pub fn shanten_with(table: &SuuhaiTable, tiles: &[usize]) -> i8 {
+    let mut hand = Hand::default();
+
+    for &t in tiles {
+        hand[t] += 1;
+    }
+
+    hand.shanten_with(table)
+}
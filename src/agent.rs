@@ -0,0 +1,361 @@
+//! Decision-makers for the engine and a self-play harness.
+
+use crate::game::{Game, GameConfig};
+use crate::meld::Meld;
+use crate::rng::Rng;
+use crate::round::{Action, Discard, Phase, Round, RoundEnd, RoundError};
+use crate::tile::Tile;
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+use crate::shanten::Hand;
+
+/// What one player can see when asked to act.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Observation {
+    pub seat: usize,
+    pub round_wind: usize,
+    pub seat_wind: usize,
+    pub dealer: usize,
+    pub honba: u32,
+    pub riichi_sticks: u32,
+    pub scores: Vec<i32>,
+    /// Concealed tiles, including the drawn tile.
+    pub hand: Vec<Tile>,
+    pub drawn: Option<Tile>,
+    /// Melds of every player, by seat.
+    pub melds: Vec<Vec<Meld>>,
+    /// Discards of every player, by seat.
+    pub discards: Vec<Vec<Discard>>,
+    pub riichi: Vec<bool>,
+    pub dora_indicators: Vec<Tile>,
+    /// Tiles left in the live wall.
+    pub remaining: usize,
+    /// The seat and tile being responded to: a discard, or a tile added to a
+    /// kan.
+    pub target: Option<(usize, Tile)>,
+    pub legal_actions: Vec<Action>,
+}
+
+impl Observation {
+    pub fn new(round: &Round, seat: usize) -> Self {
+        let player = round.player(seat);
+        let target = match round.phase() {
+            Phase::Calls {
+                discarder, tile, ..
+            } => Some((*discarder, *tile)),
+            Phase::Chankan { seat, tile, .. } => Some((*seat, *tile)),
+            _ => None,
+        };
+
+        Self {
+            seat,
+            round_wind: round.round_wind(),
+            seat_wind: round.seat_wind(seat),
+            dealer: round.dealer(),
+            honba: round.honba(),
+            riichi_sticks: round.riichi_sticks(),
+            scores: round.scores().to_vec(),
+            hand: player.concealed.clone(),
+            drawn: player.drawn,
+            melds: round.players().iter().map(|p| p.melds.clone()).collect(),
+            discards: round.players().iter().map(|p| p.discards.clone()).collect(),
+            riichi: round.players().iter().map(|p| p.is_riichi()).collect(),
+            dora_indicators: round.wall().dora_indicators().to_vec(),
+            remaining: round.wall().remaining(),
+            target,
+            legal_actions: round.legal_actions(seat),
+        }
+    }
+
+    /// Copies of each tile kind seen outside the player's concealed hand.
+    pub fn visible_counts(&self) -> [u8; 34] {
+        let mut counts = [0; 34];
+
+        for discards in self.discards.iter() {
+            for discard in discards.iter().filter(|d| !d.called) {
+                counts[discard.tile.kind()] += 1;
+            }
+        }
+
+        for melds in self.melds.iter() {
+            for tile in melds.iter().flat_map(|m| m.tiles.iter()) {
+                counts[tile.kind()] += 1;
+            }
+        }
+
+        for tile in self.dora_indicators.iter() {
+            counts[tile.kind()] += 1;
+        }
+
+        return counts;
+    }
+}
+
+pub trait Agent {
+    /// Chooses one of `observation.legal_actions`.
+    fn act(&mut self, observation: &Observation) -> Action;
+
+    fn name(&self) -> &str {
+        "agent"
+    }
+}
+
+/// Picks a legal action uniformly at random.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RandomAgent {
+    rng: Rng,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn act(&mut self, observation: &Observation) -> Action {
+        let actions = &observation.legal_actions;
+
+        actions[self.rng.below(actions.len())]
+    }
+
+    fn name(&self) -> &str {
+        "random"
+    }
+}
+
+/// Always wins when it can, never calls, and otherwise discards the tile that
+/// leaves the lowest standard-form shanten and the most tiles that lower it.
+/// Declares riichi as soon as that discard allows it.
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct GreedyAgent;
+
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+impl GreedyAgent {
+    /// Shanten after discarding `tile` and the number of unseen tiles that
+    /// would lower it.
+    fn evaluate(hand: &Hand, tile: Tile, visible: &[u8; 34]) -> (i8, u32) {
+        let mut hand = hand.clone();
+        hand[tile.kind()] -= 1;
+
+        let shanten = hand.shanten_standard();
+        let mut ukeire = 0;
+
+        for t in 0..34 {
+            if hand[t] >= 4 {
+                continue;
+            }
+
+            // A tile with no neighbour in the hand cannot form a pair or a
+            // partial set, so it never lowers the shanten.
+            let neighbours = if t >= 27 {
+                t..t + 1
+            } else {
+                let suit = t / 9 * 9;
+                t.saturating_sub(2).max(suit)..(t + 3).min(suit + 9)
+            };
+            if neighbours.clone().all(|u| hand[u] == 0) {
+                continue;
+            }
+
+            hand[t] += 1;
+
+            if hand.shanten_standard() < shanten {
+                ukeire += 4u32.saturating_sub(hand[t] as u32 - 1 + visible[t] as u32);
+            }
+
+            hand[t] -= 1;
+        }
+
+        return (shanten, ukeire);
+    }
+}
+
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+impl Agent for GreedyAgent {
+    fn act(&mut self, observation: &Observation) -> Action {
+        let actions = &observation.legal_actions;
+
+        for action in [Action::Tsumo, Action::Ron] {
+            if actions.contains(&action) {
+                return action;
+            }
+        }
+
+        if actions.contains(&Action::Pass) {
+            return Action::Pass;
+        }
+
+        let hand: Hand = observation.hand.iter().copied().collect();
+        let visible = observation.visible_counts();
+
+        let best = actions
+            .iter()
+            .filter_map(|a| match a {
+                Action::Discard(tile) => Some(*tile),
+                _ => None,
+            })
+            .min_by_key(|&tile| {
+                let (shanten, ukeire) = Self::evaluate(&hand, tile, &visible);
+                (shanten, core::cmp::Reverse(ukeire))
+            });
+
+        let Some(tile) = best else {
+            return actions[0];
+        };
+
+        if actions.contains(&Action::Riichi(tile)) {
+            return Action::Riichi(tile);
+        }
+
+        return Action::Discard(tile);
+    }
+
+    fn name(&self) -> &str {
+        "greedy"
+    }
+}
+
+/// Plays one match, asking `agents[seat]` for every decision of that seat.
+pub fn play_game(
+    config: GameConfig,
+    agents: &mut [Box<dyn Agent>],
+    seed: u64,
+) -> Result<Game, RoundError> {
+    let mut game = Game::new(config, seed);
+
+    while !game.is_over() {
+        let seat = game.round().waiting_for()[0];
+        let observation = Observation::new(game.round(), seat);
+        let action = agents[seat].act(&observation);
+
+        game.apply(seat, action)?;
+    }
+
+    return Ok(game);
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AgentStats {
+    pub name: String,
+    pub games: usize,
+    pub rounds: usize,
+    pub wins: usize,
+    pub deal_ins: usize,
+    /// Sum of 1-based placements.
+    pub placement_sum: usize,
+}
+
+impl AgentStats {
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.rounds.max(1) as f64
+    }
+
+    pub fn deal_in_rate(&self) -> f64 {
+        self.deal_ins as f64 / self.rounds.max(1) as f64
+    }
+
+    pub fn average_placement(&self) -> f64 {
+        self.placement_sum as f64 / self.games.max(1) as f64
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfPlayReport {
+    /// One entry per agent, in the order they were passed.
+    pub agents: Vec<AgentStats>,
+}
+
+impl fmt::Display for SelfPlayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "agent\tname\tgames\trounds\twin\tdeal-in\tplacement")?;
+
+        for (i, stats) in self.agents.iter().enumerate() {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}\t{:.3}\t{:.3}\t{:.3}",
+                i,
+                stats.name,
+                stats.games,
+                stats.rounds,
+                stats.win_rate(),
+                stats.deal_in_rate(),
+                stats.average_placement()
+            )?;
+        }
+
+        return Ok(());
+    }
+}
+
+/// Plays `games` matches with seeds derived from `seed`. Agents change seats
+/// every game so that each spends the same time as dealer.
+pub fn self_play(
+    config: &GameConfig,
+    agents: &mut [Box<dyn Agent>],
+    games: usize,
+    seed: u64,
+) -> Result<SelfPlayReport, RoundError> {
+    let n = agents.len();
+    let mut rng = Rng::new(seed);
+    let mut stats = agents
+        .iter()
+        .map(|a| AgentStats {
+            name: a.name().into(),
+            ..AgentStats::default()
+        })
+        .collect::<Vec<_>>();
+
+    for g in 0..games {
+        let agent_of = |seat: usize| (seat + g) % n;
+
+        agents.rotate_left(g % n);
+        let game = play_game(config.clone(), agents, rng.next_u64());
+        agents.rotate_right(g % n);
+        let game = game?;
+
+        for record in game.history() {
+            for s in stats.iter_mut() {
+                s.rounds += 1;
+            }
+
+            match &record.end {
+                RoundEnd::Tsumo(win) => stats[agent_of(win.seat)].wins += 1,
+                RoundEnd::Ron(wins) => {
+                    for win in wins.iter() {
+                        stats[agent_of(win.seat)].wins += 1;
+                    }
+
+                    stats[agent_of(wins[0].from.unwrap())].deal_ins += 1;
+                }
+                _ => {}
+            }
+        }
+
+        for placement in game.placements().unwrap() {
+            let s = &mut stats[agent_of(placement.seat)];
+            s.games += 1;
+            s.placement_sum += placement.rank + 1;
+        }
+    }
+
+    return Ok(SelfPlayReport { agents: stats });
+}
+
+/// Builds an agent by name: `random` or `greedy`.
+pub fn agent_by_name(name: &str, seed: u64) -> Option<Box<dyn Agent>> {
+    match name {
+        "random" => Some(Box::new(RandomAgent::new(seed))),
+        #[cfg(any(feature = "std", feature = "precomputed-table"))]
+        "greedy" => Some(Box::new(GreedyAgent)),
+        _ => None,
+    }
+}
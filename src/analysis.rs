@@ -0,0 +1,131 @@
+//! Tile efficiency of one hand: shanten per winning form, ukeire and waits of
+//! a 3n+1 hand, and every discard of a 3n+2 hand ranked by the shanten and
+//! ukeire it leaves.
+//!
+//! Melds only take part as visible tiles; the shanten of the concealed part
+//! already accounts for them since the standard form is counted against
+//! `count / 3 * 3 + 2` tiles.
+
+use crate::meld::Meld;
+use crate::shanten::{Hand, SuuhaiTable, Ukeire};
+use alloc::{vec, vec::Vec};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiscardOption {
+    pub tile: usize,
+    /// Shanten and ukeire of the hand left after the discard.
+    pub shanten: i8,
+    pub ukeire: u32,
+    pub tiles: Vec<usize>,
+    /// Waits of the hand left, if it is tenpai.
+    pub waits: Vec<usize>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Analysis {
+    /// Number of concealed tiles.
+    pub count: u8,
+    pub shanten: i8,
+    pub standard: i8,
+    /// `None` when the form is impossible: fewer than 13 concealed tiles or
+    /// any meld.
+    pub chiitoitsu: Option<i8>,
+    pub kokushimusou: Option<i8>,
+    /// Ukeire of a 3n+1 hand.
+    pub ukeire: Option<Ukeire>,
+    /// Waits of a 3n+1 hand, empty unless it is tenpai.
+    pub waits: Vec<usize>,
+    /// Every distinct discard of a 3n+2 hand, best first: lowest shanten,
+    /// then most ukeire, then tile order.
+    pub discards: Vec<DiscardOption>,
+}
+
+impl Analysis {
+    /// Discards that tie with the best one.
+    pub fn best_discards(&self) -> &[DiscardOption] {
+        let Some(best) = self.discards.first() else {
+            return &[];
+        };
+        let n = self
+            .discards
+            .iter()
+            .take_while(|d| (d.shanten, d.ukeire) == (best.shanten, best.ukeire))
+            .count();
+
+        return &self.discards[..n];
+    }
+}
+
+/// Analyzes `hand` with `melds` beside it. `visible` counts copies seen
+/// elsewhere (ponds, dora indicators); the melds are added to it here.
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+pub fn analyze(hand: &Hand, melds: &[Meld], visible: &[u8; 34]) -> Analysis {
+    analyze_with(crate::shanten::suuhai_table(), hand, melds, visible)
+}
+
+pub fn analyze_with(
+    table: &SuuhaiTable,
+    hand: &Hand,
+    melds: &[Meld],
+    visible: &[u8; 34],
+) -> Analysis {
+    let mut visible = *visible;
+
+    for t in melds.iter().flat_map(|m| m.tiles.iter()) {
+        visible[t.kind()] += 1;
+    }
+
+    let count = hand.count();
+    let closed_forms = melds.is_empty() && count >= 13;
+    let standard = hand.shanten_standard_with(table);
+    let chiitoitsu = closed_forms.then(|| hand.shanten_chiitoitsu());
+    let kokushimusou = closed_forms.then(|| hand.shanten_kokushimusou());
+    let shanten = standard
+        .min(chiitoitsu.unwrap_or(i8::MAX))
+        .min(kokushimusou.unwrap_or(i8::MAX));
+
+    let mut analysis = Analysis {
+        count,
+        shanten,
+        standard,
+        chiitoitsu,
+        kokushimusou,
+        ukeire: None,
+        waits: vec![],
+        discards: vec![],
+    };
+
+    match count % 3 {
+        1 => {
+            analysis.ukeire = Some(hand.ukeire_with(table, &visible));
+            analysis.waits = hand.waits();
+        }
+        2 => {
+            for tile in (0..34).filter(|&t| hand[t] > 0) {
+                let mut after = hand.clone();
+                after[tile] -= 1;
+                // The discarded copy lies in the pond and cannot be drawn.
+                let mut visible = visible;
+                visible[tile] += 1;
+                let ukeire = after.ukeire_with(table, &visible);
+
+                analysis.discards.push(DiscardOption {
+                    tile,
+                    shanten: ukeire.shanten,
+                    ukeire: ukeire.count,
+                    tiles: ukeire.tiles,
+                    waits: after.waits(),
+                });
+            }
+
+            analysis
+                .discards
+                .sort_by_key(|d| (d.shanten, core::cmp::Reverse(d.ukeire), d.tile));
+        }
+        _ => {}
+    }
+
+    return analysis;
+}
@@ -0,0 +1,261 @@
+//! Parallel evaluation of a stream of hands.
+//!
+//! [`Batch`] pulls items from an iterator in chunks, hands the chunks to a
+//! pool of worker threads and yields the results in input order. At most
+//! `max_chunks` chunks are read ahead of the consumer, so memory stays
+//! bounded however long the input is. Every worker shares the one table
+//! returned by [`suuhai_table`], which is built before the workers start.
+
+use crate::shanten::{Hand, Ukeire, suuhai_table};
+use alloc::collections::BTreeMap;
+use alloc::{sync::Arc, vec::Vec};
+use core::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct BatchConfig {
+    /// Number of worker threads.
+    pub threads: usize,
+    /// Items per chunk sent to a worker.
+    pub chunk_size: usize,
+    /// Chunks read ahead of the consumer, queued or being evaluated.
+    pub max_chunks: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+        Self {
+            threads,
+            chunk_size: 1024,
+            max_chunks: threads * 4,
+        }
+    }
+}
+
+/// Shanten per form and the ukeire of one hand, as counted with no tiles
+/// visible outside it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Evaluation {
+    pub standard: i8,
+    pub chiitoitsu: i8,
+    pub kokushimusou: i8,
+    pub shanten: i8,
+    /// Ukeire of a 3n+1 hand.
+    pub ukeire: Option<Ukeire>,
+}
+
+impl Evaluation {
+    pub fn new(hand: &Hand) -> Self {
+        let table = suuhai_table();
+
+        Self {
+            standard: hand.shanten_standard_with(table),
+            chiitoitsu: hand.shanten_chiitoitsu(),
+            kokushimusou: hand.shanten_kokushimusou(),
+            shanten: hand.shanten_with(table),
+            ukeire: (hand.count() % 3 == 1).then(|| hand.ukeire_with(table, &[0; 34])),
+        }
+    }
+}
+
+/// Items evaluated so far and the time since the batch started.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Throughput {
+    pub items: u64,
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    pub fn per_second(&self) -> f64 {
+        self.items as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hands in {:.2}s ({:.0} hands/s)",
+            self.items,
+            self.elapsed.as_secs_f64(),
+            self.per_second()
+        )
+    }
+}
+
+type Job<T> = (u64, Vec<T>);
+
+/// The results of a chunk, or the payload of a panic in `f`.
+type Done<R> = (u64, thread::Result<Vec<R>>);
+
+/// An iterator over `f` applied to every item of `input`, evaluated on
+/// worker threads and yielded in input order. A panic in `f` is resumed on
+/// the consumer's thread when the iterator reaches its chunk.
+pub struct Batch<I: Iterator, R> {
+    input: I,
+    chunk_size: usize,
+    max_chunks: usize,
+    jobs: Option<mpsc::Sender<Job<I::Item>>>,
+    results: mpsc::Receiver<Done<R>>,
+    workers: Vec<JoinHandle<()>>,
+    /// Chunks that finished ahead of the one the consumer waits for.
+    pending: BTreeMap<u64, thread::Result<Vec<R>>>,
+    current: alloc::vec::IntoIter<R>,
+    sent: u64,
+    next: u64,
+    exhausted: bool,
+    items: u64,
+    started: Instant,
+}
+
+impl<I, R> Batch<I, R>
+where
+    I: Iterator,
+    I::Item: Send + 'static,
+    R: Send + 'static,
+{
+    pub fn new<F>(input: I, config: BatchConfig, f: F) -> Self
+    where
+        F: Fn(I::Item) -> R + Send + Sync + 'static,
+    {
+        assert!(config.threads > 0 && config.chunk_size > 0 && config.max_chunks > 0);
+
+        suuhai_table();
+
+        let f = Arc::new(f);
+        let (jobs, job_receiver) = mpsc::channel::<Job<I::Item>>();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let workers = (0..config.threads)
+            .map(|_| {
+                let f = f.clone();
+                let job_receiver = job_receiver.clone();
+                let result_sender = result_sender.clone();
+
+                thread::spawn(move || {
+                    loop {
+                        let job = job_receiver.lock().unwrap().recv();
+                        let Ok((seq, items)) = job else {
+                            return;
+                        };
+                        let results = panic::catch_unwind(AssertUnwindSafe(|| {
+                            items.into_iter().map(|item| f(item)).collect()
+                        }));
+
+                        if result_sender.send((seq, results)).is_err() {
+                            return;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        return Self {
+            input,
+            chunk_size: config.chunk_size,
+            max_chunks: config.max_chunks,
+            jobs: Some(jobs),
+            results,
+            workers,
+            pending: BTreeMap::new(),
+            current: Vec::new().into_iter(),
+            sent: 0,
+            next: 0,
+            exhausted: false,
+            items: 0,
+            started: Instant::now(),
+        };
+    }
+
+    pub fn throughput(&self) -> Throughput {
+        Throughput {
+            items: self.items,
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    /// Reads chunks until `max_chunks` are in flight or the input ends.
+    fn fill(&mut self) {
+        while !self.exhausted && self.sent - self.next < self.max_chunks as u64 {
+            let chunk = self
+                .input
+                .by_ref()
+                .take(self.chunk_size)
+                .collect::<Vec<_>>();
+
+            if chunk.is_empty() {
+                self.exhausted = true;
+                break;
+            }
+
+            if let Some(jobs) = &self.jobs {
+                jobs.send((self.sent, chunk))
+                    .expect("batch worker panicked");
+            }
+
+            self.sent += 1;
+        }
+    }
+}
+
+impl<I, R> Iterator for Batch<I, R>
+where
+    I: Iterator,
+    I::Item: Send + 'static,
+    R: Send + 'static,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<R> {
+        loop {
+            if let Some(r) = self.current.next() {
+                self.items += 1;
+                return Some(r);
+            }
+
+            self.fill();
+
+            if self.next == self.sent {
+                return None;
+            }
+
+            while !self.pending.contains_key(&self.next) {
+                let (seq, results) = self.results.recv().expect("batch worker panicked");
+                self.pending.insert(seq, results);
+            }
+
+            match self.pending.remove(&self.next).unwrap() {
+                Ok(results) => self.current = results.into_iter(),
+                Err(payload) => panic::resume_unwind(payload),
+            }
+
+            self.next += 1;
+        }
+    }
+}
+
+impl<I: Iterator, R> Drop for Batch<I, R> {
+    fn drop(&mut self) {
+        // Closing the job channel stops the workers once the queue drains.
+        self.jobs = None;
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Evaluates `hands` on worker threads, yielding results in input order.
+pub fn evaluate<I>(hands: I, config: BatchConfig) -> Batch<I::IntoIter, Evaluation>
+where
+    I: IntoIterator<Item = Hand>,
+{
+    Batch::new(hands.into_iter(), config, |hand| Evaluation::new(&hand))
+}
@@ -1,31 +1,55 @@
+use shanten_count::analysis::{Analysis, analyze};
+use shanten_count::chinitsu::{Decomposition, Mentsu, random_tenpai, waits};
+use shanten_count::generate::{Constraints, HandGenerator};
+use shanten_count::layout::{Picture, Placement, parse_layout};
+use shanten_count::rng::Rng;
 use shanten_count::shanten::{Hand, SUUHAI_DICT, SuuhaiHand};
+use shanten_count::tile::{Tile, parse_tiles, tiles_to_string};
+use shanten_count::yaku::Wait;
 use std::str::FromStr;
 use wasm_bindgen::JsCast;
-use web_sys::{EventTarget, HtmlInputElement};
+use web_sys::{
+    CanvasRenderingContext2d, Element, EventTarget, HtmlAnchorElement, HtmlCanvasElement,
+    HtmlImageElement, HtmlInputElement,
+};
 use yew::prelude::*;
 
 #[function_component(App)]
 fn app() -> Html {
     SUUHAI_DICT.get_or_init(SuuhaiHand::calc_shanten_to_all_partly_pattern);
 
+    let on_copy = Callback::from(|_: MouseEvent| {
+        if let Some(window) = web_sys::window()
+            && let Ok(href) = window.location().href()
+        {
+            let _ = window.navigator().clipboard().write_text(&href);
+        }
+    });
+
     html! {
         <>
+        <button onclick={on_copy}>{"リンクをコピー"}</button>
+        <br/>
         <ShantenCaluculator/>
         <br/>
         <ImageGenerator/>
+        <br/>
+        <ChinitsuTrainer/>
+        <br/>
+        <NaniKiruQuiz/>
         </>
     }
 }
 
 #[function_component(ImageGenerator)]
 fn image_generator() -> Html {
-    let input_value_handle = use_state(String::default);
+    let input_value_handle = use_state(|| url_param("image").unwrap_or_default());
     let input_value = (*input_value_handle).clone();
 
-    let on_change = {
+    let on_input = {
         let input_value_handle = input_value_handle.clone();
 
-        Callback::from(move |e: Event| {
+        Callback::from(move |e: InputEvent| {
             let target: Option<EventTarget> = e.target();
 
             let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
@@ -36,83 +60,278 @@ fn image_generator() -> Html {
         })
     };
 
+    use_effect_with(input_value.clone(), |v| set_url_param("image", v));
+
+    let picture = Picture::new(&parse_layout(&input_value));
+    let images_ref = use_node_ref();
+
+    let on_save_png = {
+        let images_ref = images_ref.clone();
+        let picture = picture.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            if let Some(images) = images_ref.cast::<Element>()
+                && let Some(canvas) = draw_picture(&images, &picture)
+                && let Ok(url) = canvas.to_data_url()
+            {
+                download(&url, "tiles.png");
+            }
+        })
+    };
+
+    let on_save_svg = {
+        let images_ref = images_ref.clone();
+        let picture = picture.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            if let Some(images) = images_ref.cast::<Element>()
+                && let Some(svg) = picture_svg(&images, &picture)
+            {
+                let svg = String::from(js_sys::encode_uri_component(&svg));
+                download(
+                    &format!("data:image/svg+xml;charset=utf-8,{}", svg),
+                    "tiles.svg",
+                );
+            }
+        })
+    };
+
+    let empty = picture.placements.is_empty();
+
     html! {
         <>
             {"牌姿生成:"}<input size="30"
-                onchange={on_change}
+                oninput={on_input}
                 value={input_value.clone()}
             />
+            <button onclick={on_save_png} disabled={empty}>{"PNG で保存"}</button>
+            <button onclick={on_save_svg} disabled={empty}>{"SVG で保存"}</button>
             <br/>
             {"?: ?の牌を表示します"}
             <br/>
             {"_: 裏向きの牌を表示します"}
             <br/>
             {"空白を入れると次の牌は少し間隔を空けて表示します"}
-            <div>
-            {parse_input(&input_value.clone()).iter().map(|p|
-                if p == "images/empty.png" {html! {<img src={p.clone()}/>}} else {html! {<img src={p.clone()} width=90 height=120/>}}
-            ).collect::<Html>()}
-            </div>
+            <br/>
+            {"': 直前の牌を横向きにします。横向きの牌が続くと重ねて表示します (加槓)"}
+            <br/>
+            {"[1'23m], [55'5p], [5'5'55z], (5555z): 副露を表示します。暗槓は両端を裏向きにします"}
+            {picture_view(&picture, images_ref)}
         </>
     }
 }
 
-fn parse_input(input: &str) -> Vec<String> {
-    let chars = input.chars().collect::<Vec<_>>();
-    let length = chars.len();
+/// The images of `picture`, each placed absolutely so that sideways and
+/// stacked tiles line up as in the exported image.
+fn picture_view(picture: &Picture, images_ref: NodeRef) -> Html {
+    let style = format!(
+        "position: relative; width: {}px; height: {}px;",
+        picture.width, picture.height
+    );
 
-    let mut tiles = vec![];
+    html! {
+        <div ref={images_ref} style={style}>
+        {picture.placements.iter().map(|p| {
+            let (width, height) = upright_size(p);
+            // A rotated image turns about its center, which is the center of
+            // its box.
+            let left = p.x as i64 + (p.width as i64 - width as i64) / 2;
+            let top = p.y as i64 + (p.height as i64 - height as i64) / 2;
+            let mut style = format!(
+                "position: absolute; left: {}px; top: {}px; width: {}px; height: {}px;",
+                left, top, width, height
+            );
 
-    for i in 0..length {
-        if chars[i] == '?' {
-            tiles.push("images/blank.png".to_string());
-        } else if chars[i] == '_' {
-            tiles.push("images/back.png".to_string());
-        } else if chars[i] == ' ' {
-            tiles.push("images/empty.png".to_string());
-        } else {
-            let c = chars[i];
-
-            if ('0'..='9').contains(&c) {
-                let v = c.to_digit(10).unwrap() as u8;
-
-                let tile_type = (i + 1..length)
-                    .map(|j| chars[j])
-                    .filter(|&c| c == 'm' || c == 'p' || c == 's' || c == 'z')
-                    .next();
-
-                if let Some(tile_type) = tile_type {
-                    match tile_type {
-                        'm' => {
-                            tiles.push(format!("images/manzu{}.png", v));
-                        }
-                        's' => {
-                            tiles.push(format!("images/sozu{}.png", v));
-                        }
-                        'p' => {
-                            tiles.push(format!("images/pinzu{}.png", v));
-                        }
-                        'z' => {
-                            tiles.push(format!("images/jihai{}.png", v));
-                        }
-                        _ => unreachable!(),
-                    }
-                }
+            if p.rotated {
+                style += " transform: rotate(-90deg);";
             }
+
+            html! {<img src={format!("images/{}", p.image)} style={style}/>}
+        }).collect::<Html>()}
+        </div>
+    }
+}
+
+/// The size of the image of `p` before it is turned.
+fn upright_size(p: &Placement) -> (u32, u32) {
+    if p.rotated {
+        (p.height, p.width)
+    } else {
+        (p.width, p.height)
+    }
+}
+
+/// Draws the images shown in `images`, in the order of the placements, onto
+/// a canvas the size of `picture`.
+fn draw_picture(images: &Element, picture: &Picture) -> Option<HtmlCanvasElement> {
+    let canvas = new_canvas(picture.width, picture.height)?;
+    let context = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<CanvasRenderingContext2d>()
+        .ok()?;
+    let elements = images.get_elements_by_tag_name("img");
+
+    for (i, p) in picture.placements.iter().enumerate() {
+        let image = elements
+            .item(i as u32)?
+            .dyn_into::<HtmlImageElement>()
+            .ok()?;
+
+        let (width, height) = upright_size(p);
+
+        context.save();
+
+        if p.rotated {
+            context
+                .translate(p.x as f64, (p.y + p.height) as f64)
+                .ok()?;
+            context.rotate(-std::f64::consts::FRAC_PI_2).ok()?;
+        } else {
+            context.translate(p.x as f64, p.y as f64).ok()?;
         }
+
+        context
+            .draw_image_with_html_image_element_and_dw_and_dh(
+                &image,
+                0.0,
+                0.0,
+                width as f64,
+                height as f64,
+            )
+            .ok()?;
+        context.restore();
+    }
+
+    return Some(canvas);
+}
+
+/// An SVG of `picture` that embeds each image at its shown size, so the file
+/// stands alone.
+fn picture_svg(images: &Element, picture: &Picture) -> Option<String> {
+    let elements = images.get_elements_by_tag_name("img");
+    let mut urls = std::collections::HashMap::new();
+
+    for (i, p) in picture.placements.iter().enumerate() {
+        if urls.contains_key(&p.image) {
+            continue;
+        }
+
+        let image = elements
+            .item(i as u32)?
+            .dyn_into::<HtmlImageElement>()
+            .ok()?;
+        let (width, height) = upright_size(p);
+        let canvas = new_canvas(width, height)?;
+
+        canvas
+            .get_context("2d")
+            .ok()??
+            .dyn_into::<CanvasRenderingContext2d>()
+            .ok()?
+            .draw_image_with_html_image_element_and_dw_and_dh(
+                &image,
+                0.0,
+                0.0,
+                width as f64,
+                height as f64,
+            )
+            .ok()?;
+        urls.insert(p.image.clone(), canvas.to_data_url().ok()?);
+    }
+
+    return Some(picture.to_svg(|image| urls[image].clone()));
+}
+
+fn new_canvas(width: u32, height: u32) -> Option<HtmlCanvasElement> {
+    let document = web_sys::window()?.document()?;
+    let canvas = document
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<HtmlCanvasElement>()
+        .ok()?;
+
+    canvas.set_width(width);
+    canvas.set_height(height);
+
+    return Some(canvas);
+}
+
+fn download(url: &str, file_name: &str) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let Some(link) = document
+        .create_element("a")
+        .ok()
+        .and_then(|a| a.dyn_into::<HtmlAnchorElement>().ok())
+    else {
+        return;
+    };
+
+    link.set_href(url);
+    link.set_download(file_name);
+    link.click();
+}
+
+/// The state of the page is kept in the URL hash as `#hand=...&image=...`
+/// so that a link restores it.
+fn url_param(key: &str) -> Option<String> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    let params = web_sys::UrlSearchParams::new_with_str(hash.trim_start_matches('#')).ok()?;
+
+    return params.get(key);
+}
+
+fn set_url_param(key: &str, value: &str) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let hash = window.location().hash().unwrap_or_default();
+    let Ok(params) = web_sys::UrlSearchParams::new_with_str(hash.trim_start_matches('#')) else {
+        return;
+    };
+
+    if value.is_empty() {
+        params.delete(key);
+    } else {
+        params.set(key, value);
     }
 
-    return tiles;
+    let params = String::from(params.to_string());
+    let url = if params.is_empty() {
+        // Keep the path and query but drop the hash.
+        let location = window.location();
+        format!(
+            "{}{}",
+            location.pathname().unwrap_or_default(),
+            location.search().unwrap_or_default()
+        )
+    } else {
+        format!("#{}", params)
+    };
+
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+    }
+}
+
+/// Whether the shanten tables cover the hand: no fifth copy and at most 14
+/// tiles.
+fn analyzable(hand: &Hand) -> bool {
+    return hand.count() <= 14 && hand.counts().iter().all(|&c| c <= 4);
 }
 
 fn shanten_info_text(input: &str) -> Html {
     let shanten_count_text;
     let hand = Hand::from_str(input);
 
-    if hand.is_ok() {
-        let hand = hand.unwrap();
-
-        if hand.count() >= 13 {
+    if let Ok(hand) = hand {
+        if hand.counts().iter().any(|&c| c > 4) {
+            shanten_count_text = "More than 4 copies of a tile.".to_string();
+        } else if hand.count() > 14 {
+            shanten_count_text = "Too many tiles.".to_string();
+        } else if hand.count() >= 13 {
             let standard_shanten = hand.shanten_standard();
             let chiitoitsu_shanten = hand.shanten_chiitoitsu();
             let kokushi_shanten = hand.shanten_kokushimusou();
@@ -122,24 +341,100 @@ fn shanten_info_text(input: &str) -> Html {
                 standard_shanten, chiitoitsu_shanten, kokushi_shanten
             );
         } else {
-            shanten_count_text = format!("Not enough tiles.");
+            shanten_count_text = "Not enough tiles.".to_string();
         }
     } else {
-        shanten_count_text = format!("Failed to parse.");
+        shanten_count_text = "Failed to parse.".to_string();
     }
 
     html! {<>{shanten_count_text}</>}
 }
 
+/// The tiles that lower the shanten of a 13-tile hand, each with the copies
+/// not in the hand, or every discard of a 14-tile hand best first.
+fn efficiency_view(input: &str) -> Html {
+    let Ok(hand) = Hand::from_str(input) else {
+        return html! {};
+    };
+
+    if hand.count() < 13 || !analyzable(&hand) {
+        return html! {};
+    }
+
+    let analysis = analyze(&hand, &[], &[0; 34]);
+
+    if let Some(ukeire) = &analysis.ukeire {
+        let tiles = ukeire
+            .tiles
+            .iter()
+            .map(|&k| {
+                html! {
+                    <span style="display: inline-block; text-align: center">
+                        <img src={tile_image_path(Tile::new(k))} width=30 height=40/>
+                        <br/>
+                        {(4 - hand[k]).to_string()}
+                    </span>
+                }
+            })
+            .collect::<Html>();
+
+        return html! {
+            <>
+                <div>{format!("受け入れ: {}種{}枚", ukeire.tiles.len(), ukeire.count)}</div>
+                <div>{tiles}</div>
+            </>
+        };
+    }
+
+    return discard_table(&analysis, None);
+}
+
+/// Every discard with the shanten and ukeire it leaves, best first, with the
+/// `chosen` one highlighted.
+fn discard_table(analysis: &Analysis, chosen: Option<usize>) -> Html {
+    let rows = analysis
+        .discards
+        .iter()
+        .map(|d| {
+            let style = if Some(d.tile) == chosen {
+                "background-color: #ffe08a"
+            } else {
+                ""
+            };
+            let ukeire_tiles = d
+                .tiles
+                .iter()
+                .map(|&k| html! {<img src={tile_image_path(Tile::new(k))} width=24 height=32/>})
+                .collect::<Html>();
+
+            html! {
+                <tr {style}>
+                    <td><img src={tile_image_path(Tile::new(d.tile))} width=30 height=40/></td>
+                    <td>{d.shanten}</td>
+                    <td>{format!("{}種{}枚", d.tiles.len(), d.ukeire)}</td>
+                    <td>{ukeire_tiles}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <table>
+            <tr><th>{"打牌"}</th><th>{"向聴"}</th><th>{"受け入れ"}</th><th>{"有効牌"}</th></tr>
+            {rows}
+        </table>
+    }
+}
+
 #[function_component(ShantenCaluculator)]
 fn shanten_calculator() -> Html {
-    let input_value_handle = use_state(String::default);
+    let input_value_handle = use_state(|| url_param("hand").unwrap_or_default());
     let input_value = (*input_value_handle).clone();
 
-    let on_change = {
+    let on_input = {
         let input_value_handle = input_value_handle.clone();
 
-        Callback::from(move |e: Event| {
+        Callback::from(move |e: InputEvent| {
             let target: Option<EventTarget> = e.target();
 
             let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
@@ -150,22 +445,348 @@ fn shanten_calculator() -> Html {
         })
     };
 
+    use_effect_with(input_value.clone(), |v| set_url_param("hand", v));
+
+    let hand_view = match parse_tiles(&input_value) {
+        Ok(tiles) => {
+            let images = tiles
+                .iter()
+                .enumerate()
+                .map(|(i, &tile)| {
+                    let onclick = {
+                        let input_value_handle = input_value_handle.clone();
+                        let tiles = tiles.clone();
+
+                        Callback::from(move |_: MouseEvent| {
+                            let mut tiles = tiles.clone();
+                            tiles.remove(i);
+                            input_value_handle.set(tiles_to_string(&tiles));
+                        })
+                    };
+
+                    html! {<img src={tile_image_path(tile)} width=90 height=120 {onclick}/>}
+                })
+                .collect::<Html>();
+
+            html! {
+                <>
+                    <div>{images}</div>
+                    {tile_palette(&tiles, &input_value_handle)}
+                </>
+            }
+        }
+        Err(_) => html! {
+            {picture_view(&Picture::new(&parse_layout(&input_value)), NodeRef::default())}
+        },
+    };
+
     html! {
         <>
             {"シャンテン数計算機:"}<input size="30"
-                onchange={on_change}
+                oninput={on_input}
                 value={input_value.clone()}
             />
             <br/>
             {"mpsz形式で入力できます。13枚か14枚あるときに結果を計算できます。"}
             <br/>
+            {"下の牌をクリックすると手牌に加え、手牌の牌をクリックすると取り除きます。"}
+            <br/>
             {"結果:"}{shanten_info_text(&input_value)}
             <br/>
+            {efficiency_view(&input_value)}
+            {hand_view}
+        </>
+    }
+}
+
+/// Whether `tile` can join `tiles`: at most 14 tiles, four of a kind and the
+/// one red five of each suit.
+fn can_add(tiles: &[Tile], tile: Tile) -> bool {
+    tiles.len() < 14
+        && tiles.iter().filter(|t| t.kind() == tile.kind()).count() < 4
+        && !(tile.is_red() && tiles.contains(&tile))
+}
+
+/// Every tile kind and the red fives, one row per suit. Clicking a tile adds
+/// it to the hand and rewrites the text box in sorted mpsz notation.
+fn tile_palette(tiles: &[Tile], input_value_handle: &UseStateHandle<String>) -> Html {
+    let rows = (0..4).map(|suit| {
+        let mut row = (suit * 9..(suit * 9 + 9).min(34))
+            .map(Tile::new)
+            .collect::<Vec<_>>();
+
+        if suit < 3 {
+            row.insert(5, Tile::red(suit * 9 + 4));
+        }
+
+        let images = row
+            .into_iter()
+            .map(|tile| {
+                if !can_add(tiles, tile) {
+                    return html! {
+                        <img src={tile_image_path(tile)} width=45 height=60 style="opacity: 0.3"/>
+                    };
+                }
+
+                let onclick = {
+                    let input_value_handle = input_value_handle.clone();
+                    let tiles = tiles.to_vec();
+
+                    Callback::from(move |_: MouseEvent| {
+                        let mut tiles = tiles.clone();
+                        tiles.push(tile);
+                        tiles.sort();
+                        input_value_handle.set(tiles_to_string(&tiles));
+                    })
+                };
+
+                html! {<img src={tile_image_path(tile)} width=45 height=60 {onclick}/>}
+            })
+            .collect::<Html>();
+
+        html! {<div>{images}</div>}
+    });
+
+    html! {<div>{rows.collect::<Html>()}</div>}
+}
+
+fn decomposition_text(d: &Decomposition) -> String {
+    match d {
+        Decomposition::Standard { pair, mentsu, wait } => {
+            let mut groups = vec![format!("{0}{0}", pair + 1)];
+
+            for m in mentsu.iter() {
+                groups.push(match m {
+                    Mentsu::Shuntsu(b) => format!("{}{}{}", b + 1, b + 2, b + 3),
+                    Mentsu::Koutsu(b) => format!("{0}{0}{0}", b + 1),
+                });
+            }
+
+            let wait = match wait {
+                Wait::Ryanmen => "両面",
+                Wait::Kanchan => "嵌張",
+                Wait::Penchan => "辺張",
+                Wait::Shanpon => "双碰",
+                Wait::Tanki => "単騎",
+            };
+
+            format!("{} ({})", groups.join(" "), wait)
+        }
+        Decomposition::Chiitoitsu { pairs } => {
+            let pairs = pairs
+                .iter()
+                .map(|p| format!("{0}{0}", p + 1))
+                .collect::<Vec<_>>();
+
+            format!("{} (七対子)", pairs.join(" "))
+        }
+    }
+}
+
+#[function_component(ChinitsuTrainer)]
+fn chinitsu_trainer() -> Html {
+    let min_waits_handle = use_state(|| 3usize);
+    let hand_handle = use_state(|| None::<SuuhaiHand>);
+    let revealed_handle = use_state(|| false);
+
+    let on_change = {
+        let min_waits_handle = min_waits_handle.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+
+            if let Some(n) = input.and_then(|i| i.value().parse::<usize>().ok()) {
+                min_waits_handle.set(n.clamp(1, 9));
+            }
+        })
+    };
+
+    let on_generate = {
+        let min_waits_handle = min_waits_handle.clone();
+        let hand_handle = hand_handle.clone();
+        let revealed_handle = revealed_handle.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let mut rng = Rng::new(js_sys::Date::now() as u64);
+            hand_handle.set(random_tenpai(&mut rng, *min_waits_handle));
+            revealed_handle.set(false);
+        })
+    };
+
+    let on_reveal = {
+        let revealed_handle = revealed_handle.clone();
+
+        Callback::from(move |_: MouseEvent| revealed_handle.set(true))
+    };
+
+    let tile_image =
+        |n: usize| html! {<img src={format!("images/manzu{}.png", n + 1)} width=45 height=60/>};
+
+    let answer = match (&*hand_handle, *revealed_handle) {
+        (Some(hand), true) => waits(hand)
+            .iter()
+            .map(|w| {
+                html! {
+                    <div>
+                    {tile_image(w.tile)}
+                    <ul>
+                    {w.decompositions.iter().map(|d| html! {<li>{decomposition_text(d)}</li>}).collect::<Html>()}
+                    </ul>
+                    </div>
+                }
+            })
+            .collect::<Html>(),
+        _ => html! {},
+    };
+
+    html! {
+        <>
+            {"清一色の待ち: 待ちの種類が"}<input type="number" min="1" max="9"
+                onchange={on_change}
+                value={min_waits_handle.to_string()}
+            />{"以上"}
+            <button onclick={on_generate}>{"出題"}</button>
+            <button onclick={on_reveal}>{"答え"}</button>
             <div>
-            {parse_input(&input_value).iter().map(|p|
-                html! {<img src={p.clone()} width=90 height=120/>}
-            ).collect::<Html>()}
+            {(*hand_handle).iter().flat_map(|hand| (0..9).flat_map(move |n| std::iter::repeat_n(n, hand[n] as usize))).map(tile_image).collect::<Html>()}
             </div>
+            {answer}
+        </>
+    }
+}
+
+fn tile_image_path(tile: Tile) -> String {
+    let number = if tile.is_red() { 0 } else { tile.number() };
+    let name = match tile.suit() {
+        'm' => "manzu",
+        'p' => "pinzu",
+        's' => "sozu",
+        _ => "jihai",
+    };
+
+    format!("images/{}{}.png", name, number)
+}
+
+#[derive(PartialEq, Clone)]
+struct Quiz {
+    hand: Vec<Tile>,
+    chosen: Option<usize>,
+}
+
+#[function_component(NaniKiruQuiz)]
+fn nani_kiru_quiz() -> Html {
+    let shanten_handle = use_state(|| 1i8);
+    let quiz_handle = use_state(|| None::<Quiz>);
+    // Correct answers and questions answered in this session.
+    let score_handle = use_state(|| (0u32, 0u32));
+
+    let on_change = {
+        let shanten_handle = shanten_handle.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+
+            if let Some(n) = input.and_then(|i| i.value().parse::<i8>().ok()) {
+                shanten_handle.set(n.clamp(0, 3));
+            }
+        })
+    };
+
+    let on_generate = {
+        let shanten_handle = shanten_handle.clone();
+        let quiz_handle = quiz_handle.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let constraints = Constraints {
+                tiles: 14,
+                shanten: Some(*shanten_handle),
+                ..Constraints::default()
+            };
+            let seed = js_sys::Date::now() as u64;
+
+            quiz_handle.set(
+                HandGenerator::new(constraints, seed)
+                    .generate()
+                    .map(|hand| Quiz { hand, chosen: None }),
+            );
+        })
+    };
+
+    let (correct, total) = *score_handle;
+    let body = match &*quiz_handle {
+        Some(quiz) => quiz_view(quiz, &quiz_handle, &score_handle),
+        None => html! {},
+    };
+
+    html! {
+        <>
+            {"何切る: "}<input type="number" min="0" max="3"
+                onchange={on_change}
+                value={shanten_handle.to_string()}
+            />{"向聴"}
+            <button onclick={on_generate}>{"出題"}</button>
+            {format!(" 正解 {}/{}", correct, total)}
+            {body}
+        </>
+    }
+}
+
+/// The hand as clickable tiles and, once a tile is chosen, every discard
+/// ranked by shanten and ukeire.
+fn quiz_view(
+    quiz: &Quiz,
+    quiz_handle: &UseStateHandle<Option<Quiz>>,
+    score_handle: &UseStateHandle<(u32, u32)>,
+) -> Html {
+    let hand = quiz.hand.iter().copied().collect::<Hand>();
+    let analysis = analyze(&hand, &[], &[0; 34]);
+
+    let tiles = quiz
+        .hand
+        .iter()
+        .map(|&tile| {
+            let onclick = {
+                let quiz_handle = quiz_handle.clone();
+                let score_handle = score_handle.clone();
+                let quiz = quiz.clone();
+                let best = analysis
+                    .best_discards()
+                    .iter()
+                    .map(|d| d.tile)
+                    .collect::<Vec<_>>();
+
+                Callback::from(move |_: MouseEvent| {
+                    if quiz.chosen.is_some() {
+                        return;
+                    }
+
+                    let (correct, total) = *score_handle;
+                    let hit = best.contains(&tile.kind()) as u32;
+                    score_handle.set((correct + hit, total + 1));
+                    quiz_handle.set(Some(Quiz {
+                        chosen: Some(tile.kind()),
+                        ..quiz.clone()
+                    }));
+                })
+            };
+
+            html! {<img src={tile_image_path(tile)} width=45 height=60 {onclick}/>}
+        })
+        .collect::<Html>();
+
+    let ranking = match quiz.chosen {
+        Some(chosen) => discard_table(&analysis, Some(chosen)),
+        None => html! {},
+    };
+
+    html! {
+        <>
+            <div>{tiles}</div>
+            {ranking}
         </>
     }
 }
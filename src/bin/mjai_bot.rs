@@ -0,0 +1,62 @@
+use shanten_count::agent::agent_by_name;
+use shanten_count::mjai::{Bot, Event, parse_line, to_line};
+use shanten_count::round::Rules;
+use std::io::{BufRead, Write};
+
+const USAGE: &str = "usage: mjai_bot [--agent greedy|random] [--seed S]";
+
+/// Reads Mjai events from stdin, one JSON line each, and answers every line
+/// on stdout. A batched line (a JSON array) gets one answer for its last
+/// event.
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let mut name = "greedy".to_string();
+    let mut seed = 0;
+
+    for pair in args.chunks(2) {
+        match (pair[0].as_str(), pair.get(1)) {
+            ("--agent", Some(v)) => name = v.clone(),
+            ("--seed", Some(v)) => seed = v.parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+
+    let agent = agent_by_name(&name, seed).unwrap_or_else(|| usage());
+    let mut bot = Bot::new(agent, Rules::default());
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line.unwrap();
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut response = Event::None;
+
+        match parse_line(&line) {
+            Ok(events) => {
+                for event in events.iter() {
+                    match bot.react(event) {
+                        Ok(r) => response = r,
+                        Err(e) => {
+                            eprintln!("{}: {}", e, line);
+                            response = Event::None;
+                        }
+                    }
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+
+        writeln!(out, "{}", to_line(&response)).unwrap();
+        out.flush().unwrap();
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,71 @@
+use shanten_count::mjlog::Mjlog;
+use shanten_count::review::ReviewReport;
+
+const USAGE: &str = "usage: review [--summary] <mjlog.xml | tenhou6.json>";
+
+/// Reviews every discard of a game log: prints one tab-separated row per
+/// discard and then a summary line per player.
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let mut summary_only = false;
+    let mut path = None;
+
+    for arg in args.iter() {
+        match arg.as_str() {
+            "--summary" => summary_only = true,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg.clone()),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|e| fail(&path, e));
+
+    let (names, report) = if text.trim_start().starts_with('<') {
+        let log = Mjlog::parse(&text).unwrap_or_else(|e| fail(&path, e));
+        (log.names.clone(), ReviewReport::from_mjlog(&log))
+    } else {
+        tenhou6(&path, &text)
+    };
+
+    if !summary_only {
+        print!("{}", report);
+        println!();
+    }
+
+    for (seat, summary) in report.summaries().iter().enumerate() {
+        match names.get(seat) {
+            Some(name) => println!("{}\t{}\t{}", seat, name, summary),
+            None => println!("{}\t\t{}", seat, summary),
+        }
+    }
+}
+
+#[cfg(feature = "tenhou6")]
+fn tenhou6(path: &str, text: &str) -> (Vec<String>, ReviewReport) {
+    use shanten_count::round::Rules;
+    use shanten_count::tenhou6::TenhouLog;
+
+    let log = TenhouLog::parse(text).unwrap_or_else(|e| fail(path, e));
+    let rounds = log
+        .to_rounds(Rules::default())
+        .unwrap_or_else(|e| fail(path, e));
+
+    return (log.names.clone(), ReviewReport::from_rounds(&rounds));
+}
+
+#[cfg(not(feature = "tenhou6"))]
+fn tenhou6(path: &str, _: &str) -> (Vec<String>, ReviewReport) {
+    fail(path, "tenhou.net/6 logs need the tenhou6 feature")
+}
+
+fn fail(path: &str, e: impl std::fmt::Display) -> ! {
+    eprintln!("{}: {}", path, e);
+    std::process::exit(1);
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
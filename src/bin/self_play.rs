@@ -0,0 +1,104 @@
+#[cfg(feature = "tenhou6")]
+use shanten_count::agent::play_game;
+use shanten_count::agent::{agent_by_name, self_play};
+use shanten_count::game::{GameConfig, GameLength};
+
+const USAGE: &str = "usage: self_play [--games N] [--seed S] [--agents greedy,greedy,random,random] [--tonpuusen] [--tenhou FILE]";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let mut games = 100;
+    let mut seed = 0;
+    let mut names = "greedy,greedy,random,random".to_string();
+    let mut config = GameConfig::default();
+    let mut tenhou = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1);
+
+        match (args[i].as_str(), value) {
+            ("--games", Some(v)) => games = v.parse().unwrap_or_else(|_| usage()),
+            ("--seed", Some(v)) => seed = v.parse().unwrap_or_else(|_| usage()),
+            ("--agents", Some(v)) => names = v.clone(),
+            ("--tenhou", Some(v)) => tenhou = Some(v.clone()),
+            ("--tonpuusen", _) => {
+                config.length = GameLength::Tonpuusen;
+                i += 1;
+                continue;
+            }
+            _ => usage(),
+        }
+
+        i += 2;
+    }
+
+    let mut agents = names
+        .split(',')
+        .enumerate()
+        .map(|(i, name)| agent_by_name(name, seed + i as u64).unwrap_or_else(|| usage()))
+        .collect::<Vec<_>>();
+
+    if agents.len() != config.wall.players() {
+        usage();
+    }
+
+    if let Some(path) = tenhou {
+        write_tenhou(&path, &config, &mut agents, &names, seed);
+    }
+
+    let start = std::time::Instant::now();
+
+    match self_play(&config, &mut agents, games, seed) {
+        Ok(report) => {
+            print!("{}", report);
+            eprintln!("{} games in {:.2?}", games, start.elapsed());
+        }
+        Err(e) => {
+            eprintln!("an agent chose an illegal action: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Plays one match with the agents in the given seats and writes it as a
+/// tenhou.net/6 log.
+#[cfg(feature = "tenhou6")]
+fn write_tenhou(
+    path: &str,
+    config: &GameConfig,
+    agents: &mut [Box<dyn shanten_count::agent::Agent>],
+    names: &str,
+    seed: u64,
+) {
+    use shanten_count::tenhou6::TenhouLog;
+
+    let game = play_game(config.clone(), agents, seed).unwrap_or_else(|e| {
+        eprintln!("an agent chose an illegal action: {}", e);
+        std::process::exit(1);
+    });
+    let names = names.split(',').collect::<Vec<_>>();
+
+    std::fs::write(path, TenhouLog::from_game(&names, &game).to_json()).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    });
+}
+
+#[cfg(not(feature = "tenhou6"))]
+fn write_tenhou(
+    _: &str,
+    _: &GameConfig,
+    _: &mut [Box<dyn shanten_count::agent::Agent>],
+    _: &str,
+    _: u64,
+) {
+    eprintln!("--tenhou needs the tenhou6 feature");
+    std::process::exit(2);
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,537 @@
+use serde_json::{Value, json};
+use shanten_count::analysis::{Analysis, analyze};
+use shanten_count::batch::{Batch, BatchConfig, evaluate};
+use shanten_count::generate::{Constraints, Form, HandGenerator};
+use shanten_count::meld::{Meld, parse_hand};
+use shanten_count::shanten::Hand;
+use shanten_count::tile::{Tile, tiles_to_string};
+use std::io::Write;
+
+const USAGE: &str = "usage: shanten [--format text|json|csv] [--input auto|mpsz|counts|indices]
+               [--threads N] [--stats] [HAND...]
+       shanten --testcase [--verify answer.txt] [--threads N] [--stats] < input.txt
+       shanten generate [--count N] [--seed S] [--tiles N] [--shanten K]
+               [--form standard|chiitoitsu|kokushimusou] [--suits mpsz] [--min-waits W]
+
+HAND is mpsz notation with optional melds (\"123m456p11z [555z] (7777s)\"),
+34 tile counts or a list of tile indices 0-33. Without HAND, one hand is read
+per line from stdin and the hands are evaluated on N threads, printed in input
+order. --stats prints the throughput to stderr.
+
+generate prints random hands meeting every given constraint, one per line in
+mpsz notation, e.g. `shanten generate --suits m --shanten 0 --min-waits 3`.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Auto,
+    Mpsz,
+    Counts,
+    Indices,
+}
+
+/// Prints shanten per form, ukeire, waits and the discards of hands given in
+/// mpsz notation, as tile counts or as tile indices.
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    if args.first().map(|a| a.as_str()) == Some("generate") {
+        generate(&args[1..]);
+        return;
+    }
+
+    let mut format = Format::Text;
+    let mut input = Input::Auto;
+    let mut testcase = false;
+    let mut verify = None;
+    let mut config = BatchConfig::default();
+    let mut stats = false;
+    let mut hand_args = vec![];
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    _ => usage(),
+                }
+            }
+            "--input" => {
+                input = match args.next().as_deref() {
+                    Some("auto") => Input::Auto,
+                    Some("mpsz") => Input::Mpsz,
+                    Some("counts") => Input::Counts,
+                    Some("indices") => Input::Indices,
+                    _ => usage(),
+                }
+            }
+            "--threads" => {
+                config.threads = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => n,
+                    _ => usage(),
+                };
+                config.max_chunks = config.threads * 4;
+            }
+            "--stats" => stats = true,
+            "--testcase" => testcase = true,
+            "--verify" => verify = Some(args.next().unwrap_or_else(|| usage())),
+            _ if arg.starts_with("--") => usage(),
+            _ => hand_args.push(arg),
+        }
+    }
+
+    if testcase {
+        if !hand_args.is_empty() {
+            usage();
+        }
+
+        run_testcase(verify.as_deref(), config, stats);
+        return;
+    }
+
+    if verify.is_some() {
+        usage();
+    }
+
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+    let mut failed = false;
+
+    if format == Format::Csv {
+        writeln!(
+            out,
+            "hand,tiles,shanten,standard,chiitoitsu,kokushimusou,ukeire,ukeire_tiles,waits,best_discards,best_shanten,best_ukeire"
+        )
+        .unwrap();
+    }
+
+    let lines: Box<dyn Iterator<Item = String>> = if hand_args.is_empty() {
+        Box::new(
+            std::io::stdin()
+                .lines()
+                .map(|line| {
+                    line.unwrap_or_else(|e| {
+                        eprintln!("failed to read stdin: {}", e);
+                        std::process::exit(1);
+                    })
+                })
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty()),
+        )
+    } else {
+        Box::new(std::iter::once(hand_args.join(" ")))
+    };
+
+    let mut batch = Batch::new(lines, config, move |line: String| {
+        let result = parse(&line, input)
+            .map(|(hand, melds)| (notation(&hand, &melds), analyze(&hand, &melds, &[0; 34])));
+
+        (line, result)
+    });
+
+    for (line, result) in batch.by_ref() {
+        match result {
+            Ok((notation, analysis)) => match format {
+                Format::Text => write_text(&mut out, &notation, &analysis),
+                Format::Json => writeln!(out, "{}", to_json(&notation, &analysis)).unwrap(),
+                Format::Csv => write_csv(&mut out, &notation, &analysis),
+            },
+            Err(e) => {
+                eprintln!("{}: {}", line, e);
+                failed = true;
+            }
+        }
+    }
+
+    out.flush().unwrap();
+
+    if stats {
+        eprintln!("{}", batch.throughput());
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn parse(line: &str, input: Input) -> Result<(Hand, Vec<Meld>), String> {
+    let input = match input {
+        Input::Auto if line.contains(|c: char| "mpsz".contains(c)) => Input::Mpsz,
+        Input::Auto if numbers(line)?.len() == 34 => Input::Counts,
+        Input::Auto => Input::Indices,
+        input => input,
+    };
+
+    let (hand, melds) = match input {
+        Input::Mpsz => {
+            let (tiles, melds) = parse_hand(line).map_err(|e| e.to_string())?;
+            (tiles.into_iter().collect::<Hand>(), melds)
+        }
+        Input::Counts => {
+            let counts = numbers(line)?;
+
+            if counts.len() != 34 {
+                return Err("expected 34 tile counts".to_string());
+            }
+
+            let mut hand = Hand::default();
+
+            for (kind, &c) in counts.iter().enumerate() {
+                if c > 4 {
+                    return Err("more than 4 copies of a tile".to_string());
+                }
+
+                hand[kind] = c as u8;
+            }
+
+            (hand, vec![])
+        }
+        Input::Indices | Input::Auto => {
+            let mut hand = Hand::default();
+
+            for kind in numbers(line)? {
+                if kind >= 34 {
+                    return Err(format!("invalid tile index {}", kind));
+                }
+
+                hand[kind] += 1;
+            }
+
+            (hand, vec![])
+        }
+    };
+
+    let mut counts = hand.counts();
+
+    for t in melds.iter().flat_map(|m| m.tiles.iter()) {
+        counts[t.kind()] += 1;
+    }
+
+    if counts.iter().any(|&c| c > 4) {
+        return Err("more than 4 copies of a tile".to_string());
+    }
+
+    if hand.count() as usize + 3 * melds.len() > 14 {
+        return Err("more than 14 tiles".to_string());
+    }
+
+    return Ok((hand, melds));
+}
+
+/// Numbers separated by whitespace or commas, optionally inside brackets.
+fn numbers(line: &str) -> Result<Vec<usize>, String> {
+    line.split(|c: char| c.is_whitespace() || c == ',' || c == '[' || c == ']')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<usize>()
+                .map_err(|_| format!("invalid number {}", s))
+        })
+        .collect()
+}
+
+fn notation(hand: &Hand, melds: &[Meld]) -> String {
+    let tiles = (0..34)
+        .flat_map(|k| std::iter::repeat_n(Tile::new(k), hand[k] as usize))
+        .collect::<Vec<_>>();
+    let mut parts = vec![tiles_to_string(&tiles)];
+    parts.extend(melds.iter().map(|m| m.to_string()));
+
+    return parts.join(" ");
+}
+
+fn tile_list(kinds: &[usize], separator: &str) -> String {
+    kinds
+        .iter()
+        .map(|&k| Tile::new(k).to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn form(shanten: Option<i8>) -> String {
+    shanten.map_or("-".to_string(), |s| s.to_string())
+}
+
+fn write_text(out: &mut impl Write, notation: &str, analysis: &Analysis) {
+    writeln!(out, "{}", notation).unwrap();
+    writeln!(
+        out,
+        "shanten {} (standard {}, chiitoitsu {}, kokushimusou {})",
+        analysis.shanten,
+        analysis.standard,
+        form(analysis.chiitoitsu),
+        form(analysis.kokushimusou)
+    )
+    .unwrap();
+
+    if let Some(ukeire) = &analysis.ukeire {
+        writeln!(
+            out,
+            "ukeire {} ({})",
+            ukeire.count,
+            tile_list(&ukeire.tiles, " ")
+        )
+        .unwrap();
+    }
+
+    if !analysis.waits.is_empty() {
+        writeln!(out, "waits {}", tile_list(&analysis.waits, " ")).unwrap();
+    }
+
+    let best = analysis.best_discards().len();
+
+    for (i, d) in analysis.discards.iter().enumerate() {
+        write!(
+            out,
+            "{} discard {}: shanten {}, ukeire {} ({})",
+            if i < best { '*' } else { ' ' },
+            Tile::new(d.tile),
+            d.shanten,
+            d.ukeire,
+            tile_list(&d.tiles, " ")
+        )
+        .unwrap();
+
+        if !d.waits.is_empty() {
+            write!(out, ", waits {}", tile_list(&d.waits, " ")).unwrap();
+        }
+
+        writeln!(out).unwrap();
+    }
+
+    writeln!(out).unwrap();
+}
+
+fn to_json(notation: &str, analysis: &Analysis) -> Value {
+    let tiles = |kinds: &[usize]| {
+        kinds
+            .iter()
+            .map(|&k| Tile::new(k).to_string())
+            .collect::<Vec<_>>()
+    };
+    let discards = analysis
+        .discards
+        .iter()
+        .map(|d| {
+            json!({
+                "tile": Tile::new(d.tile).to_string(),
+                "shanten": d.shanten,
+                "ukeire": d.ukeire,
+                "ukeire_tiles": tiles(&d.tiles),
+                "waits": tiles(&d.waits),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    return json!({
+        "hand": notation,
+        "tiles": analysis.count,
+        "shanten": analysis.shanten,
+        "standard": analysis.standard,
+        "chiitoitsu": analysis.chiitoitsu,
+        "kokushimusou": analysis.kokushimusou,
+        "ukeire": analysis.ukeire.as_ref().map(|u| u.count),
+        "ukeire_tiles": analysis.ukeire.as_ref().map(|u| tiles(&u.tiles)),
+        "waits": tiles(&analysis.waits),
+        "best_discards": tiles(&analysis.best_discards().iter().map(|d| d.tile).collect::<Vec<_>>()),
+        "discards": discards,
+    });
+}
+
+fn write_csv(out: &mut impl Write, notation: &str, analysis: &Analysis) {
+    let best = analysis.best_discards();
+    let best_tiles = best.iter().map(|d| d.tile).collect::<Vec<_>>();
+
+    writeln!(
+        out,
+        "{},{},{},{},{},{},{},{},{},{},{},{}",
+        notation,
+        analysis.count,
+        analysis.shanten,
+        analysis.standard,
+        analysis.chiitoitsu.map_or(String::new(), |s| s.to_string()),
+        analysis
+            .kokushimusou
+            .map_or(String::new(), |s| s.to_string()),
+        analysis
+            .ukeire
+            .as_ref()
+            .map_or(String::new(), |u| u.count.to_string()),
+        analysis
+            .ukeire
+            .as_ref()
+            .map_or(String::new(), |u| tile_list(&u.tiles, " ")),
+        tile_list(&analysis.waits, " "),
+        tile_list(&best_tiles, " "),
+        best.first()
+            .map_or(String::new(), |d| d.shanten.to_string()),
+        best.first().map_or(String::new(), |d| d.ukeire.to_string()),
+    )
+    .unwrap();
+}
+
+/// The fixed format of the shanten sample corpus: a hand count, then 14 tile
+/// indices per hand. Prints "standard kokushimusou chiitoitsu" per hand, or
+/// with `--verify` the lines that differ from the answer file.
+fn run_testcase(answer_path: Option<&str>, config: BatchConfig, stats: bool) {
+    let mut text = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut text).unwrap_or_else(|e| {
+        eprintln!("failed to read stdin: {}", e);
+        std::process::exit(1);
+    });
+
+    let values = numbers(&text).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let Some((&n, tiles)) = values.split_first() else {
+        usage();
+    };
+
+    if tiles.len() < n * 14 || tiles.iter().any(|&t| t >= 34) {
+        eprintln!("expected {} hands of 14 tile indices", n);
+        std::process::exit(1);
+    }
+
+    let hands = tiles.chunks(14).take(n).map(|t| {
+        let mut h = Hand::default();
+
+        for &t in t {
+            h[t] += 1;
+        }
+
+        h
+    });
+    let mut batch = evaluate(hands, config);
+    let results = batch
+        .by_ref()
+        .map(|e| format!("{} {} {}", e.standard, e.kokushimusou, e.chiitoitsu));
+
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+
+    let Some(answer_path) = answer_path else {
+        for result in results {
+            writeln!(out, "{}", result).unwrap();
+        }
+
+        out.flush().unwrap();
+
+        if stats {
+            eprintln!("{}", batch.throughput());
+        }
+
+        return;
+    };
+
+    let answer = match std::fs::read_to_string(answer_path) {
+        Ok(answer) => answer,
+        Err(e) => {
+            eprintln!("failed to read {}: {}", answer_path, e);
+            std::process::exit(2);
+        }
+    };
+
+    let results = results.collect::<Vec<_>>();
+    let expected = answer.lines().collect::<Vec<_>>();
+    let total = n.max(expected.len());
+    let mut mismatch_count = 0;
+
+    for i in 0..total {
+        let expected_line = expected.get(i).map(|l| l.trim()).unwrap_or("<missing>");
+        let result_line = results.get(i).map(|l| l.as_str()).unwrap_or("<missing>");
+
+        if expected_line != result_line {
+            mismatch_count += 1;
+            writeln!(
+                out,
+                "line {}: expected {}, got {}",
+                i + 1,
+                expected_line,
+                result_line
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(out, "{}/{} matched", total - mismatch_count, total).unwrap();
+    out.flush().unwrap();
+
+    if stats {
+        eprintln!("{}", batch.throughput());
+    }
+
+    if mismatch_count > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// The `generate` subcommand.
+fn generate(args: &[String]) {
+    let mut constraints = Constraints::default();
+    let mut count = 1;
+    let mut seed = 0;
+
+    for pair in args.chunks(2) {
+        let [flag, value] = pair else {
+            usage();
+        };
+
+        match flag.as_str() {
+            "--count" => count = value.parse().unwrap_or_else(|_| usage()),
+            "--seed" => seed = value.parse().unwrap_or_else(|_| usage()),
+            "--tiles" => constraints.tiles = value.parse().unwrap_or_else(|_| usage()),
+            "--shanten" => constraints.shanten = Some(value.parse().unwrap_or_else(|_| usage())),
+            "--form" => {
+                constraints.form = Some(match value.as_str() {
+                    "standard" => Form::Standard,
+                    "chiitoitsu" => Form::Chiitoitsu,
+                    "kokushimusou" => Form::Kokushimusou,
+                    _ => usage(),
+                })
+            }
+            "--suits" => {
+                constraints.suits = ['m', 'p', 's', 'z'].map(|c| value.contains(c));
+
+                if value.chars().any(|c| !"mpsz".contains(c)) || !constraints.suits.contains(&true)
+                {
+                    usage();
+                }
+            }
+            "--min-waits" => constraints.min_waits = value.parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+    let mut generator = HandGenerator::new(constraints, seed);
+
+    for _ in 0..count {
+        let Some(tiles) = generator.generate() else {
+            out.flush().unwrap();
+            eprintln!(
+                "no hand met the constraints in {} attempts",
+                generator.max_attempts
+            );
+            std::process::exit(1);
+        };
+
+        writeln!(out, "{}", tiles_to_string(&tiles)).unwrap();
+    }
+
+    out.flush().unwrap();
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,315 @@
+use shanten_count::layout::{Picture, Placement, TILE_HEIGHT, parse_layout};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str =
+    "usage: tile_image [--images DIR] [--height H] [--format png|svg] [-o OUT] <layout>";
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Format {
+    Png,
+    Svg,
+}
+
+/// Composes the tile images of a layout, as the image generator of the web
+/// app shows it, into one PNG or SVG. The SVG embeds the tile images so the
+/// file stands alone. Without `-o` the image is written to stdout.
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let mut images = PathBuf::from("images");
+    let mut height = TILE_HEIGHT;
+    let mut format = None;
+    let mut out = None;
+    let mut layout = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--images" => images = args.next().unwrap_or_else(|| usage()).into(),
+            "--height" => {
+                height = args
+                    .next()
+                    .and_then(|h| h.parse().ok())
+                    .filter(|&h| h > 0)
+                    .unwrap_or_else(|| usage())
+            }
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("png") => Some(Format::Png),
+                    Some("svg") => Some(Format::Svg),
+                    _ => usage(),
+                }
+            }
+            "-o" => out = Some(args.next().unwrap_or_else(|| usage())),
+            _ if layout.is_none() && !arg.starts_with('-') => layout = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let layout = layout.unwrap_or_else(|| usage());
+    let format = format.unwrap_or(match &out {
+        Some(out) if out.ends_with(".svg") => Format::Svg,
+        _ => Format::Png,
+    });
+
+    let picture = scaled(&Picture::new(&parse_layout(&layout)), height);
+
+    if picture.placements.is_empty() {
+        fail(&layout, "no tiles in the layout");
+    }
+
+    let bytes = match format {
+        Format::Png => png(&picture, &images),
+        Format::Svg => svg(&picture, &images).into_bytes(),
+    };
+
+    let written = match &out {
+        Some(out) => std::fs::write(out, bytes).map_err(|e| (out.as_str(), e)),
+        None => std::io::stdout()
+            .write_all(&bytes)
+            .map_err(|e| ("stdout", e)),
+    };
+
+    if let Err((path, e)) = written {
+        fail(path, e);
+    }
+}
+
+/// `picture` resized so an upright tile is `height` tall, keeping adjacent
+/// images touching.
+fn scaled(picture: &Picture, height: u32) -> Picture {
+    let scale = |v: u32| (v as u64 * height as u64 / TILE_HEIGHT as u64) as u32;
+
+    return Picture {
+        placements: picture
+            .placements
+            .iter()
+            .map(|p| Placement {
+                image: p.image.clone(),
+                x: scale(p.x),
+                y: scale(p.y),
+                width: scale(p.x + p.width) - scale(p.x),
+                height: scale(p.y + p.height) - scale(p.y),
+                rotated: p.rotated,
+            })
+            .collect(),
+        width: scale(picture.width),
+        height: scale(picture.height),
+    };
+}
+
+fn svg(picture: &Picture, images: &Path) -> String {
+    let mut files = HashMap::new();
+
+    for p in picture.placements.iter() {
+        files.entry(p.image.clone()).or_insert_with(|| {
+            let path = images.join(&p.image);
+            let bytes =
+                std::fs::read(&path).unwrap_or_else(|e| fail(&path.display().to_string(), e));
+            format!("data:image/png;base64,{}", base64(&bytes))
+        });
+    }
+
+    return picture.to_svg(|image| files[image].clone());
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut ret = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                ret.push(CHARS[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                ret.push('=');
+            }
+        }
+    }
+
+    return ret;
+}
+
+/// An RGBA image with straight alpha.
+struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 4]>,
+}
+
+fn png(picture: &Picture, images: &Path) -> Vec<u8> {
+    let mut files = HashMap::new();
+    let mut canvas = Image {
+        width: picture.width,
+        height: picture.height,
+        pixels: vec![[0; 4]; picture.width as usize * picture.height as usize],
+    };
+
+    for p in picture.placements.iter() {
+        let image = files
+            .entry((p.image.clone(), p.rotated))
+            .or_insert_with(|| {
+                let path = images.join(&p.image);
+                let image = decode(&path).unwrap_or_else(|e| fail(&path.display().to_string(), e));
+
+                if p.rotated { rotate(&image) } else { image }
+            });
+
+        draw(&mut canvas, image, p);
+    }
+
+    let mut bytes = vec![];
+    let mut encoder = png::Encoder::new(&mut bytes, canvas.width, canvas.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let data = canvas.pixels.concat();
+    let written = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data));
+
+    if let Err(e) = written {
+        fail("png", e);
+    }
+
+    return bytes;
+}
+
+fn decode(path: &Path) -> Result<Image, png::DecodingError> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(
+        png::Transformations::normalize_to_color8() | png::Transformations::ALPHA,
+    );
+
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size().unwrap_or(0)];
+    let info = reader.next_frame(&mut buf)?;
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf[..info.buffer_size()]
+            .chunks_exact(4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf[..info.buffer_size()]
+            .chunks_exact(2)
+            .map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        _ => unreachable!("the ALPHA transformation gives RGBA or gray with alpha"),
+    };
+
+    return Ok(Image {
+        width: info.width,
+        height: info.height,
+        pixels,
+    });
+}
+
+/// `image` turned a quarter counterclockwise.
+fn rotate(image: &Image) -> Image {
+    let (w, h) = (image.width as usize, image.height as usize);
+    let mut pixels = Vec::with_capacity(w * h);
+
+    for y in 0..w {
+        for x in 0..h {
+            pixels.push(image.pixels[x * w + (w - 1 - y)]);
+        }
+    }
+
+    return Image {
+        width: image.height,
+        height: image.width,
+        pixels,
+    };
+}
+
+/// Draws `image` over `canvas` into the rectangle of `p`, averaging the
+/// source pixels each destination pixel covers.
+fn draw(canvas: &mut Image, image: &Image, p: &Placement) {
+    let (sw, sh) = (image.width as u64, image.height as u64);
+    let (dw, dh) = (p.width as u64, p.height as u64);
+
+    for y in 0..dh {
+        let (y0, y1) = (
+            y * sh / dh,
+            ((y + 1) * sh).div_ceil(dh).max(y * sh / dh + 1),
+        );
+
+        for x in 0..dw {
+            let (x0, x1) = (
+                x * sw / dw,
+                ((x + 1) * sw).div_ceil(dw).max(x * sw / dw + 1),
+            );
+
+            // Average with premultiplied alpha so transparent pixels don't
+            // darken the edges.
+            let mut sum = [0u64; 4];
+
+            for sy in y0..y1.min(sh) {
+                for sx in x0..x1.min(sw) {
+                    let [r, g, b, a] = image.pixels[(sy * sw + sx) as usize];
+                    let a = a as u64;
+                    sum[0] += r as u64 * a;
+                    sum[1] += g as u64 * a;
+                    sum[2] += b as u64 * a;
+                    sum[3] += a;
+                }
+            }
+
+            let n = (y1.min(sh) - y0) * (x1.min(sw) - x0);
+
+            if sum[3] == 0 {
+                continue;
+            }
+
+            let src = [
+                (sum[0] / sum[3]) as u8,
+                (sum[1] / sum[3]) as u8,
+                (sum[2] / sum[3]) as u8,
+                (sum[3] / n) as u8,
+            ];
+
+            let i = ((p.y as u64 + y) * canvas.width as u64 + p.x as u64 + x) as usize;
+            canvas.pixels[i] = over(src, canvas.pixels[i]);
+        }
+    }
+}
+
+/// `src` composited over `dst`, both with straight alpha.
+fn over(src: [u8; 4], dst: [u8; 4]) -> [u8; 4] {
+    let sa = src[3] as u32;
+    let da = dst[3] as u32 * (255 - sa) / 255;
+    let a = sa + da;
+
+    if a == 0 {
+        return [0; 4];
+    }
+
+    let mut ret = [0, 0, 0, a as u8];
+
+    for c in 0..3 {
+        ret[c] = ((src[c] as u32 * sa + dst[c] as u32 * da) / a) as u8;
+    }
+
+    return ret;
+}
+
+fn fail(path: &str, e: impl std::fmt::Display) -> ! {
+    eprintln!("{}: {}", path, e);
+    std::process::exit(1);
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,273 @@
+//! Waits of single-suit hands with every reading that produces them, and
+//! random tenpai chinitsu hands for wait-reading practice.
+//!
+//! Tiles are numbered 0 to 8 within the suit, as in [`SuuhaiHand`]. A tile
+//! the hand already holds four of is never a wait.
+
+use crate::rng::Rng;
+use crate::shanten::{Hand, SuuhaiHand};
+use crate::yaku::Wait;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mentsu {
+    /// A run starting at the given number.
+    Shuntsu(usize),
+    Koutsu(usize),
+}
+
+/// One way to read the hand completed by the winning tile.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Decomposition {
+    Standard {
+        pair: usize,
+        mentsu: Vec<Mentsu>,
+        /// The shape the winning tile completed.
+        wait: Wait,
+    },
+    Chiitoitsu {
+        pairs: Vec<usize>,
+    },
+}
+
+impl fmt::Display for Decomposition {
+    /// Groups of numbers, e.g. `"11 123 456 789 999 (penchan)"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decomposition::Standard { pair, mentsu, wait } => {
+                write!(f, "{0}{0}", pair + 1)?;
+
+                for m in mentsu.iter() {
+                    match m {
+                        Mentsu::Shuntsu(b) => write!(f, " {}{}{}", b + 1, b + 2, b + 3)?,
+                        Mentsu::Koutsu(b) => write!(f, " {0}{0}{0}", b + 1)?,
+                    }
+                }
+
+                let wait = match wait {
+                    Wait::Ryanmen => "ryanmen",
+                    Wait::Kanchan => "kanchan",
+                    Wait::Penchan => "penchan",
+                    Wait::Shanpon => "shanpon",
+                    Wait::Tanki => "tanki",
+                };
+
+                write!(f, " ({})", wait)
+            }
+            Decomposition::Chiitoitsu { pairs } => {
+                for (i, p) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+
+                    write!(f, "{0}{0}", p + 1)?;
+                }
+
+                write!(f, " (chiitoitsu)")
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChinitsuWait {
+    pub tile: usize,
+    pub decompositions: Vec<Decomposition>,
+}
+
+/// Every wait of a 3n+1 single-suit hand, in number order, with each
+/// reading of the completed hand.
+pub fn waits(hand: &SuuhaiHand) -> Vec<ChinitsuWait> {
+    if hand.count() % 3 != 1 {
+        return vec![];
+    }
+
+    let mut waits = vec![];
+
+    for tile in 0..9 {
+        if hand[tile] >= 4 {
+            continue;
+        }
+
+        let mut counts = [0; 9];
+
+        for (i, c) in counts.iter_mut().enumerate() {
+            *c = hand[i];
+        }
+
+        counts[tile] += 1;
+
+        let decompositions = decompositions(&counts, tile);
+
+        if !decompositions.is_empty() {
+            waits.push(ChinitsuWait {
+                tile,
+                decompositions,
+            });
+        }
+    }
+
+    return waits;
+}
+
+/// Readings of the 3n+2 `counts` for each group `tile` could have completed.
+fn decompositions(counts: &[u8; 9], tile: usize) -> Vec<Decomposition> {
+    let mut ret = vec![];
+
+    for pair in (0..9).filter(|&p| counts[p] >= 2) {
+        let mut rest = *counts;
+        rest[pair] -= 2;
+
+        let mut readings = vec![];
+        split(&mut rest, 0, &mut vec![], &mut readings);
+
+        for mut mentsu in readings {
+            mentsu.sort_by_key(|m| match *m {
+                Mentsu::Koutsu(b) => (b, 0),
+                Mentsu::Shuntsu(b) => (b, 1),
+            });
+
+            let mut shapes = vec![];
+
+            if pair == tile {
+                shapes.push(Wait::Tanki);
+            }
+
+            for m in mentsu.iter() {
+                match *m {
+                    Mentsu::Koutsu(b) if b == tile => shapes.push(Wait::Shanpon),
+                    Mentsu::Shuntsu(b) if b + 1 == tile => shapes.push(Wait::Kanchan),
+                    Mentsu::Shuntsu(b) if (b == tile && b == 6) || (b + 2 == tile && b == 0) => {
+                        shapes.push(Wait::Penchan)
+                    }
+                    Mentsu::Shuntsu(b) if b == tile || b + 2 == tile => shapes.push(Wait::Ryanmen),
+                    _ => {}
+                }
+            }
+
+            for wait in shapes {
+                let d = Decomposition::Standard {
+                    pair,
+                    mentsu: mentsu.clone(),
+                    wait,
+                };
+
+                if !ret.contains(&d) {
+                    ret.push(d);
+                }
+            }
+        }
+    }
+
+    if counts.iter().sum::<u8>() == 14 && counts.iter().all(|&c| c == 0 || c == 2) {
+        ret.push(Decomposition::Chiitoitsu {
+            pairs: (0..9).filter(|&i| counts[i] == 2).collect(),
+        });
+    }
+
+    return ret;
+}
+
+/// Splits `counts` into mentsu in every possible way.
+fn split(counts: &mut [u8; 9], start: usize, groups: &mut Vec<Mentsu>, out: &mut Vec<Vec<Mentsu>>) {
+    let Some(i) = (start..9).find(|&i| counts[i] > 0) else {
+        out.push(groups.clone());
+        return;
+    };
+
+    if counts[i] >= 3 {
+        counts[i] -= 3;
+        groups.push(Mentsu::Koutsu(i));
+        split(counts, i, groups, out);
+        groups.pop();
+        counts[i] += 3;
+    }
+
+    if i <= 6 && counts[i + 1] > 0 && counts[i + 2] > 0 {
+        counts[i] -= 1;
+        counts[i + 1] -= 1;
+        counts[i + 2] -= 1;
+        groups.push(Mentsu::Shuntsu(i));
+        split(counts, i, groups, out);
+        groups.pop();
+        counts[i] += 1;
+        counts[i + 1] += 1;
+        counts[i + 2] += 1;
+    }
+}
+
+/// A random 13-tile tenpai hand with at least `min_waits` waits, as likely
+/// as being dealt from the 36 tiles of one suit. `None` if no hand has that
+/// many waits.
+pub fn random_tenpai(rng: &mut Rng, min_waits: usize) -> Option<SuuhaiHand> {
+    let mut hands = vec![];
+    let mut total = 0;
+    let mut hand = SuuhaiHand::default();
+
+    collect(&mut hand, 0, 13, 1, &mut |hand, weight| {
+        if wait_count(hand) >= min_waits.max(1) {
+            total += weight;
+            hands.push((hand.clone(), weight));
+        }
+    });
+
+    if total == 0 {
+        return None;
+    }
+
+    // The weights sum to at most C(36, 13), which fits a 32-bit usize.
+    let mut r = rng.below(total as usize) as u64;
+
+    for (hand, weight) in hands {
+        if r < weight {
+            return Some(hand);
+        }
+
+        r -= weight;
+    }
+
+    unreachable!();
+}
+
+/// The number of waits, by [`Hand::waits`], which is quicker than finding
+/// every reading.
+fn wait_count(hand: &SuuhaiHand) -> usize {
+    let mut full = Hand::default();
+
+    for i in 0..9 {
+        full[i] = hand[i];
+    }
+
+    return full.waits().len();
+}
+
+/// Calls `f` with every hand of `left` more tiles from `i` on, weighted by
+/// the number of ways to pick its copies of each tile.
+fn collect(
+    hand: &mut SuuhaiHand,
+    i: usize,
+    left: u8,
+    weight: u64,
+    f: &mut impl FnMut(&SuuhaiHand, u64),
+) {
+    if i == 9 {
+        if left == 0 {
+            f(hand, weight);
+        }
+
+        return;
+    }
+
+    const CHOOSE: [u64; 5] = [1, 4, 6, 4, 1];
+
+    for c in 0..=left.min(4) {
+        hand[i] = c;
+        collect(hand, i + 1, left - c, weight * CHOOSE[c as usize], f);
+    }
+
+    hand[i] = 0;
+}
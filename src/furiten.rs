@@ -0,0 +1,42 @@
+//! Furiten: a tenpai hand may not win by ron while one of its waits is among
+//! the player's own discards, was let go since the player's last discard, or
+//! was let go at any point after riichi.
+
+use crate::shanten::Hand;
+use crate::tile::Tile;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Furiten {
+    /// A wait is among the player's own discards. Lasts until the waits
+    /// change.
+    Discard,
+    /// A winning tile was let go after riichi. Lasts for the rest of the
+    /// round.
+    Riichi,
+    /// A winning tile was let go in the current go-around. Lasts until the
+    /// player's next discard.
+    Temporary,
+}
+
+/// Furiten of a tenpai `hand` (3n+1 tiles). `passed` are the tiles other
+/// players discarded or added to a kan since the player's last discard, or
+/// since the riichi declaration when `riichi` is set. Returns `None` when the
+/// player may ron.
+pub fn furiten(hand: &Hand, discards: &[Tile], passed: &[Tile], riichi: bool) -> Option<Furiten> {
+    let waits = hand.waits();
+
+    if discards.iter().any(|t| waits.contains(&t.kind())) {
+        return Some(Furiten::Discard);
+    }
+
+    if passed.iter().any(|t| waits.contains(&t.kind())) {
+        return Some(if riichi {
+            Furiten::Riichi
+        } else {
+            Furiten::Temporary
+        });
+    }
+
+    return None;
+}
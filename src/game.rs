@@ -0,0 +1,437 @@
+//! A full match: rounds are played one after another on seeded walls, scores
+//! are settled with the point tables in [`crate::score`], and the match ends
+//! with placements including uma and oka.
+
+use crate::rng::Rng;
+use crate::round::{Action, Round, RoundEnd, RoundError, RoundSetup, Rules};
+use crate::score::{self, Limit};
+use crate::wall::{Wall, WallConfig};
+use crate::yaku::{self, Agari};
+use alloc::{vec, vec::Vec};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameLength {
+    /// East round only.
+    Tonpuusen,
+    /// East and south rounds.
+    Hanchan,
+}
+
+impl GameLength {
+    pub fn winds(self) -> usize {
+        match self {
+            GameLength::Tonpuusen => 1,
+            GameLength::Hanchan => 2,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    pub length: GameLength,
+    pub wall: WallConfig,
+    pub rules: Rules,
+    pub starting_points: i32,
+    /// Points subtracted from every final score; the difference to the
+    /// starting points goes to first place as oka.
+    pub return_points: i32,
+    /// Added to the final score by placement, first place first.
+    pub uma: Vec<i32>,
+    /// The match ends when a score drops below zero.
+    pub busting: bool,
+    /// In the last round the match ends when the dealer wins while in first
+    /// place.
+    pub agari_yame: bool,
+    /// Likewise when the dealer is tenpai at an exhaustive draw.
+    pub tenpai_yame: bool,
+    /// When nobody has reached the return points after the last round, play
+    /// continues into the next wind until someone does (sudden death).
+    pub extension: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            length: GameLength::Hanchan,
+            wall: WallConfig::default(),
+            rules: Rules::default(),
+            starting_points: 25000,
+            return_points: 30000,
+            uma: vec![20000, 10000, -10000, -20000],
+            busting: true,
+            agari_yame: true,
+            tenpai_yame: false,
+            extension: true,
+        }
+    }
+}
+
+/// How the scores change at the end of a round.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settlement {
+    /// One entry per win, in the order of the round's result.
+    pub agari: Vec<Agari>,
+    /// Score changes, not counting riichi deposits made during the round.
+    pub deltas: Vec<i32>,
+    /// The riichi sticks on the table went to a winner.
+    pub riichi_sticks_taken: bool,
+    /// The dealer won or was tenpai, or the round was aborted.
+    pub renchan: bool,
+}
+
+/// Settles a finished round. Honba are paid to every winner of a multiple
+/// ron, while the riichi sticks go to the first winner in turn order.
+pub fn settle(round: &Round) -> Option<Settlement> {
+    let n = round.player_count();
+    let dealer = round.dealer();
+    let honba = round.honba() as i32;
+    let sticks = round.riichi_sticks() as i32 * 1000;
+    let mut deltas = vec![0; n];
+
+    let settlement = match round.result()? {
+        RoundEnd::Tsumo(win) => {
+            let agari = yaku::evaluate(win)?;
+            pay_tsumo(&mut deltas, win.seat, dealer, agari.base_points(), honba);
+            deltas[win.seat] += sticks;
+
+            Settlement {
+                agari: vec![agari],
+                deltas,
+                riichi_sticks_taken: true,
+                renchan: win.seat == dealer,
+            }
+        }
+        RoundEnd::Ron(wins) => {
+            let mut agaris = vec![];
+
+            for win in wins.iter() {
+                let agari = yaku::evaluate(win)?;
+                let from = win.from.unwrap();
+                let points = score::ron_payment(agari.base_points(), win.seat == dealer) as i32
+                    + honba * 300;
+
+                deltas[win.seat] += points;
+                deltas[from] -= points;
+                agaris.push(agari);
+            }
+
+            deltas[wins[0].seat] += sticks;
+
+            Settlement {
+                agari: agaris,
+                deltas,
+                riichi_sticks_taken: true,
+                renchan: wins.iter().any(|w| w.seat == dealer),
+            }
+        }
+        RoundEnd::ExhaustiveDraw {
+            tenpai,
+            nagashi_mangan,
+        } => {
+            if !nagashi_mangan.is_empty() {
+                for &seat in nagashi_mangan.iter() {
+                    pay_tsumo(&mut deltas, seat, dealer, Limit::Mangan.base_points(), 0);
+                }
+            } else {
+                let count = tenpai.iter().filter(|&&t| t).count() as i32;
+                let total = (n as i32 - 1) * 1000;
+
+                if 0 < count && count < n as i32 {
+                    for seat in 0..n {
+                        deltas[seat] = if tenpai[seat] {
+                            total / count
+                        } else {
+                            -total / (n as i32 - count)
+                        };
+                    }
+                }
+            }
+
+            Settlement {
+                agari: vec![],
+                deltas,
+                riichi_sticks_taken: false,
+                renchan: tenpai[dealer],
+            }
+        }
+        RoundEnd::Abortive(_) => Settlement {
+            agari: vec![],
+            deltas,
+            riichi_sticks_taken: false,
+            renchan: true,
+        },
+    };
+
+    return Some(settlement);
+}
+
+fn pay_tsumo(deltas: &mut [i32], winner: usize, dealer: usize, base: u32, honba: i32) {
+    let (non_dealer_pays, dealer_pays) = score::tsumo_payments(base, winner == dealer);
+
+    for seat in 0..deltas.len() {
+        if seat == winner {
+            continue;
+        }
+
+        let points = if seat == dealer {
+            dealer_pays
+        } else {
+            non_dealer_pays
+        } as i32
+            + honba * 100;
+
+        deltas[seat] -= points;
+        deltas[winner] += points;
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundRecord {
+    pub round_wind: usize,
+    pub dealer: usize,
+    pub honba: u32,
+    pub end: RoundEnd,
+    pub settlement: Settlement,
+    /// Scores after the round.
+    pub scores: Vec<i32>,
+    /// The finished round with its events, for replays.
+    pub round: Round,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Placement {
+    pub seat: usize,
+    /// 0 for first place.
+    pub rank: usize,
+    pub score: i32,
+    /// Score minus return points plus uma and oka, e.g. 45000 for +45.0.
+    pub points: i32,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game {
+    config: GameConfig,
+    rng: Rng,
+    /// Number of dealer changes so far.
+    kyoku: usize,
+    honba: u32,
+    riichi_sticks: u32,
+    scores: Vec<i32>,
+    round: Round,
+    history: Vec<RoundRecord>,
+    placements: Option<Vec<Placement>>,
+}
+
+impl Game {
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        let n = config.wall.players();
+        assert_eq!(config.uma.len(), n);
+
+        let mut rng = Rng::new(seed);
+        let scores = vec![config.starting_points; n];
+        let round = Round::new(
+            config.rules,
+            Wall::new(config.wall, rng.next_u64()),
+            RoundSetup {
+                round_wind: 27,
+                dealer: 0,
+                honba: 0,
+                riichi_sticks: 0,
+                scores: scores.clone(),
+            },
+        );
+
+        Self {
+            config,
+            rng,
+            kyoku: 0,
+            honba: 0,
+            riichi_sticks: 0,
+            scores,
+            round,
+            history: vec![],
+            placements: None,
+        }
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// The round being played, or the last one once the match is over.
+    pub fn round(&self) -> &Round {
+        &self.round
+    }
+
+    /// Scores at the start of the current round.
+    pub fn scores(&self) -> &[i32] {
+        &self.scores
+    }
+
+    pub fn history(&self) -> &[RoundRecord] {
+        &self.history
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.placements.is_some()
+    }
+
+    pub fn placements(&self) -> Option<&[Placement]> {
+        self.placements.as_deref()
+    }
+
+    /// Applies an action to the current round, and settles it and starts the
+    /// next one when it ends.
+    pub fn apply(&mut self, seat: usize, action: Action) -> Result<(), RoundError> {
+        if self.is_over() {
+            return Err(RoundError::RoundOver);
+        }
+
+        self.round.apply(seat, action)?;
+
+        if self.round.is_over() {
+            self.finish_round();
+        }
+
+        return Ok(());
+    }
+
+    fn finish_round(&mut self) {
+        let round = &self.round;
+        let n = round.player_count();
+        let settlement = settle(round).unwrap();
+
+        self.scores = (0..n)
+            .map(|s| round.scores()[s] + settlement.deltas[s])
+            .collect();
+        self.riichi_sticks = if settlement.riichi_sticks_taken {
+            0
+        } else {
+            round.riichi_sticks()
+        };
+
+        let end = round.result().unwrap().clone();
+        let dealer = round.dealer();
+        let dealer_won =
+            matches!(&end, RoundEnd::Tsumo(_) | RoundEnd::Ron(_)) && settlement.renchan;
+        let dealer_tenpai =
+            matches!(&end, RoundEnd::ExhaustiveDraw { tenpai, .. } if tenpai[dealer]);
+        let drawn_or_dealer_won =
+            !matches!(&end, RoundEnd::Tsumo(_) | RoundEnd::Ron(_)) || dealer_won;
+        let renchan = settlement.renchan;
+
+        self.history.push(RoundRecord {
+            round_wind: round.round_wind(),
+            dealer,
+            honba: round.honba(),
+            end,
+            settlement,
+            scores: self.scores.clone(),
+            round: round.clone(),
+        });
+
+        self.honba = if drawn_or_dealer_won {
+            self.honba + 1
+        } else {
+            0
+        };
+
+        if self.config.busting && self.scores.iter().any(|&s| s < 0) {
+            self.finish();
+            return;
+        }
+
+        let last = n * self.config.length.winds() - 1;
+        let limit = if self.config.extension {
+            n * (self.config.length.winds() + 1) - 1
+        } else {
+            last
+        };
+        let someone_returned = self.scores.iter().any(|&s| s >= self.config.return_points);
+
+        if self.kyoku > last && someone_returned {
+            self.finish();
+            return;
+        }
+
+        if self.kyoku >= last {
+            let dealer_top = self.ranking()[0] == dealer;
+
+            if renchan {
+                if dealer_top
+                    && ((self.config.agari_yame && dealer_won)
+                        || (self.config.tenpai_yame && dealer_tenpai))
+                {
+                    self.finish();
+                    return;
+                }
+            } else if self.kyoku >= limit || someone_returned {
+                self.finish();
+                return;
+            }
+        }
+
+        if !renchan {
+            self.kyoku += 1;
+        }
+
+        self.start_round();
+    }
+
+    fn start_round(&mut self) {
+        let n = self.scores.len();
+        let wall = Wall::new(self.config.wall, self.rng.next_u64());
+
+        self.round = Round::new(
+            self.config.rules,
+            wall,
+            RoundSetup {
+                round_wind: 27 + (self.kyoku / n) % 4,
+                dealer: self.kyoku % n,
+                honba: self.honba,
+                riichi_sticks: self.riichi_sticks,
+                scores: self.scores.clone(),
+            },
+        );
+    }
+
+    /// Seats by score, ties broken by seat order from the first dealer.
+    fn ranking(&self) -> Vec<usize> {
+        let mut seats = (0..self.scores.len()).collect::<Vec<_>>();
+        seats.sort_by_key(|&s| (-self.scores[s], s));
+
+        return seats;
+    }
+
+    fn finish(&mut self) {
+        let ranking = self.ranking();
+        let n = self.scores.len() as i32;
+
+        // Leftover riichi sticks go to first place.
+        self.scores[ranking[0]] += self.riichi_sticks as i32 * 1000;
+        self.riichi_sticks = 0;
+
+        let oka = (self.config.return_points - self.config.starting_points) * n;
+
+        self.placements = Some(
+            ranking
+                .iter()
+                .enumerate()
+                .map(|(rank, &seat)| Placement {
+                    seat,
+                    rank,
+                    score: self.scores[seat],
+                    points: self.scores[seat] - self.config.return_points
+                        + self.config.uma[rank]
+                        + if rank == 0 { oka } else { 0 },
+                })
+                .collect(),
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Random hands filtered by constraints, for quizzes and test fixtures.
+//!
+//! Hands are drawn from a seeded shuffle of the tile set (restricted to the
+//! allowed suits) and rejected until they meet every constraint. Kokushimusou
+//! shapes are vanishingly rare in a uniform draw, so for that form the hand
+//! takes at most `shanten + 1` tiles from outside the terminals and honors.
+
+use crate::rng::Rng;
+use crate::shanten::{Hand, YAOCHUU_TILES};
+use crate::tile::Tile;
+use crate::wall::WallConfig;
+use alloc::{vec, vec::Vec};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Form {
+    Standard,
+    Chiitoitsu,
+    Kokushimusou,
+}
+
+impl Form {
+    pub fn shanten(&self, hand: &Hand) -> i8 {
+        match self {
+            Form::Standard => hand.shanten_standard(),
+            Form::Chiitoitsu => hand.shanten_chiitoitsu(),
+            Form::Kokushimusou => hand.shanten_kokushimusou(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constraints {
+    /// Number of tiles in the hand, 1 to 14.
+    pub tiles: usize,
+    pub shanten: Option<i8>,
+    /// The form whose shanten is the hand's shanten. Chiitoitsu and
+    /// kokushimusou need 13 or 14 tiles.
+    pub form: Option<Form>,
+    /// Allowed suits in m, p, s, z order; `[true, false, false, false]`
+    /// gives manzu chinitsu hands.
+    pub suits: [bool; 4],
+    /// For a 3n+1 hand, the fewest tile kinds it must wait on, as counted by
+    /// [`Hand::waits`]: a tile it holds four of is not one. For a 3n+2 hand,
+    /// some discard must leave that many waits. Zero disables it.
+    pub min_waits: usize,
+    /// The tile set, including its red fives.
+    pub wall: WallConfig,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            tiles: 13,
+            shanten: None,
+            form: None,
+            suits: [true; 4],
+            min_waits: 0,
+            wall: WallConfig::default(),
+        }
+    }
+}
+
+impl Constraints {
+    pub fn accepts(&self, hand: &Hand) -> bool {
+        let count = hand.count() as usize;
+
+        if count != self.tiles || (0..34).any(|k| hand[k] > 0 && !self.suits[k / 9]) {
+            return false;
+        }
+
+        let shanten = match self.form {
+            Some(Form::Standard) | None if count < 13 => hand.shanten_standard(),
+            Some(_) if count < 13 => return false,
+            Some(form) => {
+                let shanten = form.shanten(hand);
+
+                if self.shanten.is_some_and(|s| s != shanten) || hand.shanten() < shanten {
+                    return false;
+                }
+
+                shanten
+            }
+            None => hand.shanten(),
+        };
+
+        if self.shanten.is_some_and(|s| s != shanten) {
+            return false;
+        }
+
+        if self.min_waits == 0 {
+            return true;
+        }
+
+        match count % 3 {
+            1 => hand.waits().len() >= self.min_waits,
+            2 => (0..34).filter(|&k| hand[k] > 0).any(|k| {
+                let mut after = hand.clone();
+                after[k] -= 1;
+                after.waits().len() >= self.min_waits
+            }),
+            _ => false,
+        }
+    }
+}
+
+pub struct HandGenerator {
+    constraints: Constraints,
+    rng: Rng,
+    /// Every allowed tile, and the same split into yaochuu tiles and the rest
+    /// for kokushimusou.
+    pool: Vec<Tile>,
+    yaochuu: Vec<Tile>,
+    others: Vec<Tile>,
+    /// Hands tried by [`HandGenerator::generate`] before it gives up.
+    pub max_attempts: u32,
+}
+
+impl HandGenerator {
+    pub fn new(constraints: Constraints, seed: u64) -> Self {
+        let pool = constraints
+            .wall
+            .tiles()
+            .into_iter()
+            .filter(|t| constraints.suits[t.kind() / 9])
+            .collect::<Vec<_>>();
+        let (yaochuu, others) = pool
+            .iter()
+            .copied()
+            .partition(|t| YAOCHUU_TILES.contains(&t.kind()));
+
+        return Self {
+            constraints,
+            rng: Rng::new(seed),
+            pool,
+            yaochuu,
+            others,
+            max_attempts: 1_000_000,
+        };
+    }
+
+    pub fn constraints(&self) -> &Constraints {
+        &self.constraints
+    }
+
+    /// A sorted hand meeting the constraints, or `None` if none was found in
+    /// `max_attempts` tries.
+    pub fn generate(&mut self) -> Option<Vec<Tile>> {
+        let n = self.constraints.tiles;
+        let kokushimusou = self.constraints.form == Some(Form::Kokushimusou);
+
+        let closed_form = matches!(
+            self.constraints.form,
+            Some(Form::Chiitoitsu | Form::Kokushimusou)
+        );
+
+        if !(1..=14).contains(&n) || n > self.pool.len() || (closed_form && n < 13) {
+            return None;
+        }
+
+        for _ in 0..self.max_attempts {
+            let mut tiles = if kokushimusou {
+                let limit = self
+                    .constraints
+                    .shanten
+                    .map_or(n, |s| (s + 1).max(0) as usize);
+                let k = self.rng.below(limit.min(n).min(self.others.len()) + 1);
+
+                if n - k > self.yaochuu.len() {
+                    continue;
+                }
+
+                let mut tiles = draw(&mut self.rng, &mut self.others, k);
+                tiles.extend(draw(&mut self.rng, &mut self.yaochuu, n - k));
+                tiles
+            } else {
+                draw(&mut self.rng, &mut self.pool, n)
+            };
+
+            if self.constraints.accepts(&tiles.iter().copied().collect()) {
+                tiles.sort();
+                return Some(tiles);
+            }
+        }
+
+        return None;
+    }
+}
+
+impl Iterator for HandGenerator {
+    type Item = Vec<Tile>;
+
+    fn next(&mut self) -> Option<Vec<Tile>> {
+        self.generate()
+    }
+}
+
+/// The first `n` tiles of a partial shuffle of `pool`.
+fn draw(rng: &mut Rng, pool: &mut [Tile], n: usize) -> Vec<Tile> {
+    let mut tiles = vec![];
+
+    for i in 0..n {
+        let j = i + rng.below(pool.len() - i);
+        pool.swap(i, j);
+        tiles.push(pool[i]);
+    }
+
+    return tiles;
+}
@@ -0,0 +1,271 @@
+//! The layout syntax of the image generator and the placement of its tile
+//! images, shared by the web app and the `tile_image` command.
+//!
+//! A layout is mpsz notation where `?` is a blank tile, `_` a face-down tile
+//! and a space a narrow gap. A `'` after a number turns the tile sideways,
+//! and a sideways tile right after another is stacked on it. Melds in the
+//! notation of [`Meld`]'s `FromStr` are drawn as on the table: `[1'23m]`
+//! with the called tile sideways, `[5'5'55z]` with the added kakan tile
+//! stacked and `(5555z)` with backs on the ends.
+//!
+//! Placements are in the web app's display units: tiles are 90 by 120 and a
+//! gap is 40 wide. Every item stands on the bottom edge of the picture.
+
+use crate::meld::{Meld, MeldKind};
+use crate::tile::Tile;
+use alloc::{format, string::String, vec, vec::Vec};
+
+pub const TILE_WIDTH: u32 = 90;
+pub const TILE_HEIGHT: u32 = 120;
+pub const GAP_WIDTH: u32 = 40;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutItem {
+    Tile(Tile),
+    /// A called tile, turned sideways.
+    Sideways(Tile),
+    /// A sideways tile with a second one stacked on it, as in a kakan.
+    Stacked(Tile, Tile),
+    Blank,
+    Back,
+    Gap,
+}
+
+impl LayoutItem {
+    pub fn width(&self) -> u32 {
+        match self {
+            LayoutItem::Sideways(_) | LayoutItem::Stacked(..) => TILE_HEIGHT,
+            LayoutItem::Gap => GAP_WIDTH,
+            _ => TILE_WIDTH,
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        match self {
+            LayoutItem::Sideways(_) => TILE_WIDTH,
+            LayoutItem::Stacked(..) => TILE_WIDTH * 2,
+            _ => TILE_HEIGHT,
+        }
+    }
+}
+
+/// File name of the image of `tile` in the `images/` directory.
+pub fn tile_image(tile: Tile) -> String {
+    let number = if tile.is_red() { 0 } else { tile.number() };
+    let name = match tile.suit() {
+        'm' => "manzu",
+        'p' => "pinzu",
+        's' => "sozu",
+        _ => "jihai",
+    };
+
+    return format!("{}{}.png", name, number);
+}
+
+/// Reads a layout. A number takes the suit of the next suit letter after it
+/// and before the next meld; numbers with no suit letter and other
+/// characters are ignored. A bracket that does not hold a valid meld is
+/// ignored and its contents read as loose tiles.
+pub fn parse_layout(s: &str) -> Vec<LayoutItem> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut items = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+
+        let item = match c {
+            '?' => LayoutItem::Blank,
+            '_' => LayoutItem::Back,
+            ' ' => LayoutItem::Gap,
+            '[' | '(' => {
+                let close = if c == '[' { ']' } else { ')' };
+
+                if let Some(len) = chars[i..].iter().position(|&c| c == close)
+                    && let Ok(meld) = chars[i - 1..=i + len]
+                        .iter()
+                        .collect::<String>()
+                        .parse::<Meld>()
+                {
+                    items.extend(meld_items(&meld));
+                    i += len + 1;
+                }
+
+                continue;
+            }
+            c => {
+                let Some(n) = c.to_digit(10) else {
+                    continue;
+                };
+                let Some(suit) = chars[i..]
+                    .iter()
+                    .take_while(|&&c| c != '[' && c != '(')
+                    .find(|c| matches!(c, 'm' | 'p' | 's' | 'z'))
+                else {
+                    continue;
+                };
+
+                let offset = match suit {
+                    'm' => 0,
+                    'p' => 9,
+                    's' => 18,
+                    _ => 27,
+                };
+
+                let tile = match (offset, n) {
+                    (27, 1..=7) => Tile::new(27 + n as usize - 1),
+                    (27, _) => continue,
+                    (_, 0) => Tile::red(offset + 4),
+                    (_, n) => Tile::new(offset + n as usize - 1),
+                };
+
+                if chars.get(i) != Some(&'\'') {
+                    LayoutItem::Tile(tile)
+                } else if let Some(&LayoutItem::Sideways(lower)) = items.last() {
+                    items.pop();
+                    LayoutItem::Stacked(lower, tile)
+                } else {
+                    LayoutItem::Sideways(tile)
+                }
+            }
+        };
+
+        items.push(item);
+    }
+
+    return items;
+}
+
+/// The items of a meld as laid out on the table.
+pub fn meld_items(meld: &Meld) -> Vec<LayoutItem> {
+    let tiles = meld.arrangement();
+
+    let Some(called) = meld.called_position() else {
+        // The last two show a red five if there is one.
+        return vec![
+            LayoutItem::Back,
+            LayoutItem::Tile(tiles[2]),
+            LayoutItem::Tile(tiles[3]),
+            LayoutItem::Back,
+        ];
+    };
+
+    let mut items = vec![];
+    let mut i = 0;
+
+    while i < tiles.len() {
+        if i != called {
+            items.push(LayoutItem::Tile(tiles[i]));
+        } else if meld.kind == MeldKind::Kakan {
+            items.push(LayoutItem::Stacked(tiles[i], tiles[i + 1]));
+            i += 1;
+        } else {
+            items.push(LayoutItem::Sideways(tiles[i]));
+        }
+
+        i += 1;
+    }
+
+    return items;
+}
+
+/// Where one image goes in the composed picture.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Placement {
+    pub image: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// The image is turned a quarter counterclockwise to fill the box, so
+    /// it is drawn `height` wide and `width` tall before turning.
+    pub rotated: bool,
+}
+
+/// The placed images of a layout.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Picture {
+    pub placements: Vec<Placement>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Picture {
+    pub fn new(items: &[LayoutItem]) -> Self {
+        let mut picture = Self {
+            height: items.iter().map(|i| i.height()).max().unwrap_or(0),
+            ..Self::default()
+        };
+
+        for item in items {
+            let bottom = picture.height;
+            let mut place = |image: String, y: u32, rotated: bool| {
+                picture.placements.push(Placement {
+                    image,
+                    x: picture.width,
+                    y,
+                    width: item.width(),
+                    height: if rotated { TILE_WIDTH } else { TILE_HEIGHT },
+                    rotated,
+                });
+            };
+
+            match *item {
+                LayoutItem::Tile(tile) => place(tile_image(tile), bottom - TILE_HEIGHT, false),
+                LayoutItem::Sideways(tile) => place(tile_image(tile), bottom - TILE_WIDTH, true),
+                LayoutItem::Stacked(lower, upper) => {
+                    place(tile_image(lower), bottom - TILE_WIDTH, true);
+                    place(tile_image(upper), bottom - TILE_WIDTH * 2, true);
+                }
+                LayoutItem::Blank => place("blank.png".into(), bottom - TILE_HEIGHT, false),
+                LayoutItem::Back => place("back.png".into(), bottom - TILE_HEIGHT, false),
+                LayoutItem::Gap => place("empty.png".into(), bottom - TILE_HEIGHT, false),
+            }
+
+            picture.width += item.width();
+        }
+
+        return picture;
+    }
+
+    /// An SVG document drawing every placement, with each image's `href`
+    /// given by `href`, e.g. a relative path or a data URI.
+    pub fn to_svg(&self, href: impl Fn(&str) -> String) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            self.width, self.height
+        );
+
+        for p in self.placements.iter() {
+            let (width, height, transform) = if p.rotated {
+                let transform = format!(
+                    " transform=\"translate({} {}) rotate(-90)\"",
+                    p.x,
+                    p.y + p.height
+                );
+                (p.height, p.width, transform)
+            } else {
+                (p.width, p.height, String::new())
+            };
+            let (x, y) = if p.rotated { (0, 0) } else { (p.x, p.y) };
+
+            svg += &format!(
+                "<image href=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\"{}/>\n",
+                href(&p.image),
+                x,
+                y,
+                width,
+                height,
+                transform
+            );
+        }
+
+        svg += "</svg>\n";
+
+        return svg;
+    }
+}
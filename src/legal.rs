@@ -0,0 +1,562 @@
+//! Legal actions of one player, decided from the player's tiles, melds and
+//! riichi status plus a few facts about the table. [`crate::round::Round`]
+//! validates every action with [`check`], and bots and UIs can call
+//! [`legal_actions`] on positions that do not come from a running round.
+
+use crate::meld::MeldKind;
+use crate::round::{Action, Player, RoundError, Rules, Win};
+use crate::shanten::YAOCHUU_TILES;
+use crate::tile::Tile;
+use crate::wall::MAX_KAN_COUNT;
+use crate::yaku;
+use alloc::{vec, vec::Vec};
+
+/// The table as seen by one seat.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Situation {
+    pub rules: Rules,
+    pub seat: usize,
+    pub players: usize,
+    pub round_wind: usize,
+    pub seat_wind: usize,
+    pub score: i32,
+    /// Tiles left in the live wall.
+    pub remaining: usize,
+    /// Kans declared by anyone so far.
+    pub kans: usize,
+    /// No call or kan has happened yet, so everyone is still in their first
+    /// go-around.
+    pub uninterrupted: bool,
+}
+
+/// What the player has to decide on.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Prompt {
+    /// The player holds 3n+2 tiles.
+    Turn {
+        /// The turn started with a chi or pon rather than a draw.
+        after_call: bool,
+        rinshan: bool,
+    },
+    /// The seat `from` discarded `tile`.
+    Discard { tile: Tile, from: usize },
+    /// The seat `from` added `tile` to a pon, or declared an ankan of it.
+    Kan {
+        tile: Tile,
+        from: usize,
+        ankan: bool,
+    },
+}
+
+/// Every action the player may take, in a fixed order: wins first, then
+/// discards, riichi, kans and calls, and `Pass` last when responding.
+pub fn legal_actions(player: &Player, situation: &Situation, prompt: Prompt) -> Vec<Action> {
+    let mut tiles = player.concealed.clone();
+    tiles.sort();
+    tiles.dedup();
+
+    let mut candidates = vec![];
+
+    match prompt {
+        Prompt::Turn { .. } => {
+            let mut kinds = tiles.iter().map(|t| t.kind()).collect::<Vec<_>>();
+            kinds.dedup();
+
+            candidates.push(Action::Tsumo);
+            candidates.extend(tiles.iter().map(|&t| Action::Discard(t)));
+            candidates.extend(tiles.iter().map(|&t| Action::Riichi(t)));
+            candidates.extend(kinds.iter().map(|&k| Action::Ankan(k)));
+            candidates.extend(tiles.iter().map(|&t| Action::Kakan(t)));
+            candidates.push(Action::KyuushuKyuuhai);
+        }
+        Prompt::Discard { tile, .. } => {
+            let kind = tile.kind();
+            let same_kind = player
+                .concealed
+                .iter()
+                .copied()
+                .filter(|t| t.kind() == kind)
+                .collect::<Vec<_>>();
+
+            candidates.push(Action::Ron);
+            candidates.extend(distinct_pairs(&same_kind).into_iter().map(Action::Pon));
+            candidates.push(Action::Daiminkan);
+
+            if kind < 27 {
+                let number = kind % 9;
+                let base = kind - number;
+
+                for (a, b) in [(-2, -1), (-1, 1), (1, 2)] {
+                    let (Some(na), Some(nb)) =
+                        (number.checked_add_signed(a), number.checked_add_signed(b))
+                    else {
+                        continue;
+                    };
+
+                    if na > 8 || nb > 8 {
+                        continue;
+                    }
+
+                    for ta in distinct_tiles(&player.concealed, base + na) {
+                        for tb in distinct_tiles(&player.concealed, base + nb) {
+                            candidates.push(Action::Chi([ta, tb]));
+                        }
+                    }
+                }
+            }
+
+            candidates.push(Action::Pass);
+        }
+        Prompt::Kan { .. } => {
+            candidates.push(Action::Ron);
+            candidates.push(Action::Pass);
+        }
+    }
+
+    return candidates
+        .into_iter()
+        .filter(|&a| check(player, situation, prompt, a).is_ok())
+        .collect();
+}
+
+/// Whether the player may take `action`, and why not otherwise.
+pub fn check(
+    player: &Player,
+    situation: &Situation,
+    prompt: Prompt,
+    action: Action,
+) -> Result<(), RoundError> {
+    match prompt {
+        Prompt::Turn {
+            after_call,
+            rinshan,
+        } => match action {
+            Action::Discard(tile) => check_discard(player, tile),
+            Action::Riichi(tile) => check_riichi(player, situation, tile, after_call),
+            Action::Tsumo => check_tsumo(player, situation, after_call, rinshan).map(|_| ()),
+            Action::Ankan(kind) => check_ankan(player, situation, kind, after_call),
+            Action::Kakan(tile) => check_kakan(player, situation, tile, after_call),
+            Action::KyuushuKyuuhai => {
+                if can_kyuushu_kyuuhai(player, situation, after_call) {
+                    Ok(())
+                } else {
+                    Err(RoundError::NotKyuushuKyuuhai)
+                }
+            }
+            _ => Err(RoundError::InvalidAction),
+        },
+        Prompt::Discard { tile, from } => match action {
+            Action::Pass => Ok(()),
+            Action::Ron => check_ron(player, situation, tile, from, false).map(|_| ()),
+            Action::Chi(tiles) => check_call(player, situation, tile, from, &tiles, MeldKind::Chi),
+            Action::Pon(tiles) => check_call(player, situation, tile, from, &tiles, MeldKind::Pon),
+            Action::Daiminkan => {
+                let tiles = player
+                    .concealed
+                    .iter()
+                    .copied()
+                    .filter(|t| t.kind() == tile.kind())
+                    .collect::<Vec<_>>();
+
+                check_call(player, situation, tile, from, &tiles, MeldKind::Daiminkan)
+            }
+            _ => Err(RoundError::InvalidAction),
+        },
+        Prompt::Kan { tile, from, ankan } => match action {
+            Action::Pass => Ok(()),
+            Action::Ron if ankan => {
+                let mut hand = player.hand();
+                hand[tile.kind()] += 1;
+
+                if !situation.rules.kokushi_chankan_on_ankan
+                    || hand.count() != 14
+                    || hand.shanten_kokushimusou() != -1
+                {
+                    return Err(RoundError::NotAgari);
+                }
+
+                check_ron(player, situation, tile, from, true).map(|_| ())
+            }
+            Action::Ron => check_ron(player, situation, tile, from, true).map(|_| ()),
+            _ => Err(RoundError::InvalidAction),
+        },
+    }
+}
+
+/// The win a tsumo would make, or why it is not possible.
+pub fn check_tsumo(
+    player: &Player,
+    situation: &Situation,
+    after_call: bool,
+    rinshan: bool,
+) -> Result<Win, RoundError> {
+    if after_call {
+        return Err(RoundError::InvalidAction);
+    }
+
+    let Some(tile) = player.drawn else {
+        return Err(RoundError::InvalidAction);
+    };
+
+    if !player.hand().is_agari() {
+        return Err(RoundError::NotAgari);
+    }
+
+    let haitei = !rinshan && situation.remaining == 0;
+    let win = win(player, situation, tile, None, haitei, rinshan, false);
+
+    if yaku::evaluate(&win).is_none() {
+        return Err(RoundError::NoYaku);
+    }
+
+    return Ok(win);
+}
+
+/// The win a ron on `tile` from `from` would make, or why it is not possible.
+pub fn check_ron(
+    player: &Player,
+    situation: &Situation,
+    tile: Tile,
+    from: usize,
+    chankan: bool,
+) -> Result<Win, RoundError> {
+    if from == situation.seat {
+        return Err(RoundError::InvalidAction);
+    }
+
+    let mut hand = player.hand();
+    hand[tile.kind()] += 1;
+
+    if !hand.is_agari() {
+        return Err(RoundError::NotAgari);
+    }
+
+    if player.furiten().is_some() {
+        return Err(RoundError::Furiten);
+    }
+
+    let houtei = !chankan && situation.remaining == 0;
+    let win = win(player, situation, tile, Some(from), houtei, false, chankan);
+
+    if yaku::evaluate(&win).is_none() {
+        return Err(RoundError::NoYaku);
+    }
+
+    return Ok(win);
+}
+
+/// A win by the player without dora, which do not affect whether the hand has
+/// a yaku.
+fn win(
+    player: &Player,
+    situation: &Situation,
+    tile: Tile,
+    from: Option<usize>,
+    last_tile: bool,
+    rinshan: bool,
+    chankan: bool,
+) -> Win {
+    let mut concealed = player.concealed.clone();
+    if from.is_some() {
+        concealed.push(tile);
+    }
+
+    let first_draw = from.is_none() && situation.uninterrupted && player.discards.is_empty();
+    let dealer = situation.seat_wind == 27;
+
+    Win {
+        seat: situation.seat,
+        from,
+        tile,
+        concealed,
+        melds: player.melds.clone(),
+        round_wind: situation.round_wind,
+        seat_wind: situation.seat_wind,
+        riichi: player.is_riichi(),
+        double_riichi: player.double_riichi,
+        ippatsu: player.ippatsu,
+        haitei: from.is_none() && last_tile,
+        houtei: from.is_some() && last_tile,
+        rinshan,
+        chankan,
+        tenhou: first_draw && dealer,
+        chiihou: first_draw && !dealer,
+        dora: vec![],
+        ura_dora: vec![],
+    }
+}
+
+fn check_discard(player: &Player, tile: Tile) -> Result<(), RoundError> {
+    if !player.concealed.contains(&tile) {
+        return Err(RoundError::TileNotInHand);
+    }
+
+    if player.is_riichi() && player.drawn != Some(tile) {
+        return Err(RoundError::RiichiLocked);
+    }
+
+    if player.kuikae.contains(&tile.kind()) {
+        return Err(RoundError::Kuikae);
+    }
+
+    return Ok(());
+}
+
+fn check_riichi(
+    player: &Player,
+    situation: &Situation,
+    tile: Tile,
+    after_call: bool,
+) -> Result<(), RoundError> {
+    if player.is_riichi() {
+        return Err(RoundError::AlreadyRiichi);
+    }
+
+    if after_call || !player.is_closed() {
+        return Err(RoundError::OpenHand);
+    }
+
+    if situation.score < 1000 {
+        return Err(RoundError::NotEnoughPoints);
+    }
+
+    if situation.remaining < situation.players {
+        return Err(RoundError::NotEnoughTiles);
+    }
+
+    check_discard(player, tile)?;
+
+    let mut hand = player.hand();
+    hand[tile.kind()] -= 1;
+
+    if hand.waits().is_empty() {
+        return Err(RoundError::NotTenpai);
+    }
+
+    return Ok(());
+}
+
+fn check_ankan(
+    player: &Player,
+    situation: &Situation,
+    kind: usize,
+    after_call: bool,
+) -> Result<(), RoundError> {
+    if after_call || kind >= 34 {
+        return Err(RoundError::InvalidAction);
+    }
+
+    if player.concealed.iter().filter(|t| t.kind() == kind).count() < 4 {
+        return Err(RoundError::TileNotInHand);
+    }
+
+    check_kan_allowed(situation)?;
+
+    if player.is_riichi() {
+        if player.drawn.map(|t| t.kind()) != Some(kind) {
+            return Err(RoundError::RiichiLocked);
+        }
+
+        let mut before = player.hand();
+        before[kind] -= 1;
+        let mut after = before.clone();
+        after[kind] -= 3;
+
+        if before.waits() != after.waits() {
+            return Err(RoundError::WaitChanged);
+        }
+    }
+
+    return Ok(());
+}
+
+fn check_kakan(
+    player: &Player,
+    situation: &Situation,
+    tile: Tile,
+    after_call: bool,
+) -> Result<(), RoundError> {
+    if after_call {
+        return Err(RoundError::InvalidAction);
+    }
+
+    if !player.concealed.contains(&tile) {
+        return Err(RoundError::TileNotInHand);
+    }
+
+    if !player
+        .melds
+        .iter()
+        .any(|m| m.kind == MeldKind::Pon && m.base() == tile.kind())
+    {
+        return Err(RoundError::InvalidMeld);
+    }
+
+    return check_kan_allowed(situation);
+}
+
+fn check_kan_allowed(situation: &Situation) -> Result<(), RoundError> {
+    if situation.kans >= MAX_KAN_COUNT {
+        return Err(RoundError::TooManyKans);
+    }
+
+    if situation.remaining == 0 {
+        return Err(RoundError::NotEnoughTiles);
+    }
+
+    return Ok(());
+}
+
+fn can_kyuushu_kyuuhai(player: &Player, situation: &Situation, after_call: bool) -> bool {
+    if !situation.rules.abortive_draws || after_call || !situation.uninterrupted {
+        return false;
+    }
+
+    if !player.discards.is_empty() {
+        return false;
+    }
+
+    let hand = player.hand();
+
+    YAOCHUU_TILES.iter().filter(|&&t| hand[t] > 0).count() >= 9
+}
+
+/// Checks a chi, pon or daiminkan of `called` from `from` using `tiles` from
+/// the hand.
+fn check_call(
+    player: &Player,
+    situation: &Situation,
+    called: Tile,
+    from: usize,
+    tiles: &[Tile],
+    kind: MeldKind,
+) -> Result<(), RoundError> {
+    if from == situation.seat {
+        return Err(RoundError::InvalidAction);
+    }
+
+    if player.is_riichi() {
+        return Err(RoundError::RiichiLocked);
+    }
+
+    if situation.remaining == 0 {
+        return Err(RoundError::NotEnoughTiles);
+    }
+
+    let mut rest = player.concealed.clone();
+    for tile in tiles {
+        let Some(i) = rest.iter().position(|t| t == tile) else {
+            return Err(RoundError::InvalidMeld);
+        };
+        rest.remove(i);
+    }
+
+    let mut kinds = tiles.iter().map(|t| t.kind()).collect::<Vec<_>>();
+    kinds.push(called.kind());
+    kinds.sort();
+
+    match kind {
+        MeldKind::Chi => {
+            let kamicha = (situation.seat + situation.players - 1) % situation.players;
+
+            if situation.players != 4 || from != kamicha {
+                return Err(RoundError::InvalidMeld);
+            }
+
+            if kinds[0] >= 27 || kinds[0] / 9 != kinds[2] / 9 {
+                return Err(RoundError::InvalidMeld);
+            }
+
+            if kinds[1] != kinds[0] + 1 || kinds[2] != kinds[0] + 2 {
+                return Err(RoundError::InvalidMeld);
+            }
+        }
+        MeldKind::Pon => {
+            if kinds.len() != 3 || kinds[0] != kinds[2] {
+                return Err(RoundError::InvalidMeld);
+            }
+        }
+        MeldKind::Daiminkan => {
+            if kinds.len() != 4 || kinds[0] != kinds[3] {
+                return Err(RoundError::InvalidMeld);
+            }
+
+            if situation.kans >= MAX_KAN_COUNT {
+                return Err(RoundError::TooManyKans);
+            }
+
+            return Ok(());
+        }
+        _ => return Err(RoundError::InvalidAction),
+    }
+
+    // A chi or pon must leave something that can be discarded.
+    let forbidden = if situation.rules.forbid_kuikae {
+        kuikae_kinds(tiles, called, kind)
+    } else {
+        vec![]
+    };
+
+    if !rest.iter().any(|t| !forbidden.contains(&t.kind())) {
+        return Err(RoundError::Kuikae);
+    }
+
+    return Ok(());
+}
+
+/// Tile kinds that may not be discarded right after calling `called` with
+/// `tiles`.
+pub fn kuikae_kinds(tiles: &[Tile], called: Tile, kind: MeldKind) -> Vec<usize> {
+    let mut kinds = vec![called.kind()];
+
+    if kind == MeldKind::Chi {
+        let called = called.kind();
+        let low = tiles.iter().map(|t| t.kind()).min().unwrap();
+        let high = tiles.iter().map(|t| t.kind()).max().unwrap();
+
+        if called < low && high % 9 < 8 {
+            kinds.push(high + 1);
+        }
+
+        if called > high && low % 9 > 0 {
+            kinds.push(low - 1);
+        }
+    }
+
+    return kinds;
+}
+
+/// Tiles of `kind` in `tiles`, one per distinct physical variant (plain or
+/// red).
+fn distinct_tiles(tiles: &[Tile], kind: usize) -> Vec<Tile> {
+    let mut ret = tiles
+        .iter()
+        .copied()
+        .filter(|t| t.kind() == kind)
+        .collect::<Vec<_>>();
+    ret.sort();
+    ret.dedup();
+
+    return ret;
+}
+
+/// Pairs that can be taken out of `tiles`, which all share one kind, without
+/// repeating pairs that only differ by which identical copy is used.
+fn distinct_pairs(tiles: &[Tile]) -> Vec<[Tile; 2]> {
+    let mut ret = vec![];
+
+    for i in 0..tiles.len() {
+        for j in i + 1..tiles.len() {
+            let mut pair = [tiles[i], tiles[j]];
+            pair.sort();
+
+            if !ret.contains(&pair) {
+                ret.push(pair);
+            }
+        }
+    }
+
+    return ret;
+}
@@ -1 +1,32 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod agent;
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod batch;
+pub mod chinitsu;
+pub mod furiten;
+pub mod game;
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+pub mod generate;
+pub mod layout;
+pub mod legal;
+pub mod meld;
+#[cfg(feature = "mjai")]
+pub mod mjai;
+pub mod mjlog;
+pub mod reference;
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+pub mod review;
+pub mod rng;
+pub mod round;
+pub mod score;
 pub mod shanten;
+pub mod table;
+#[cfg(feature = "tenhou6")]
+pub mod tenhou6;
+pub mod tile;
+pub mod wall;
+pub mod yaku;
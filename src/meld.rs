@@ -0,0 +1,265 @@
+use crate::tile::{Tile, parse_tiles, tiles_to_string};
+use alloc::{string::String, vec, vec::Vec};
+use core::{fmt, str::FromStr};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeldKind {
+    Chi,
+    Pon,
+    Daiminkan,
+    Kakan,
+    Ankan,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Meld {
+    pub kind: MeldKind,
+    /// Every tile of the meld, including the called one, sorted.
+    pub tiles: Vec<Tile>,
+    /// The tile taken from another player's discard. For a kakan this is the
+    /// tile of the original pon; `None` for an ankan.
+    pub called: Option<Tile>,
+    /// Seat of the discarder relative to the caller: 1 for the right
+    /// (shimocha), 2 across (toimen), 3 for the left (kamicha), 0 for an ankan.
+    pub from: usize,
+}
+
+impl Meld {
+    /// The lowest tile kind of the meld.
+    pub fn base(&self) -> usize {
+        self.tiles[0].kind()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.kind != MeldKind::Ankan
+    }
+
+    pub fn is_kan(&self) -> bool {
+        matches!(
+            self.kind,
+            MeldKind::Daiminkan | MeldKind::Kakan | MeldKind::Ankan
+        )
+    }
+
+    /// Whether the meld is three or four of a kind.
+    pub fn is_koutsu(&self) -> bool {
+        self.kind != MeldKind::Chi
+    }
+
+    /// The tiles in the order they are laid out on the table, left to right.
+    /// The called tile, turned sideways, is at [`Meld::called_position`];
+    /// for a kakan the tile after it is the added one, stacked on top.
+    pub fn arrangement(&self) -> Vec<Tile> {
+        let mut rest = self.tiles.clone();
+
+        let Some(position) = self.called_position() else {
+            return rest;
+        };
+        let called = self.called.unwrap_or(rest[0]);
+
+        if let Some(i) = rest.iter().position(|&t| t == called) {
+            rest.remove(i);
+        }
+
+        let mut tiles = vec![];
+        // The added tile of a kakan goes with the called one.
+        let mut group = vec![called];
+
+        if self.kind == MeldKind::Kakan {
+            group.extend(rest.pop());
+        }
+
+        tiles.extend(rest.drain(..position.min(rest.len())));
+        tiles.extend(group);
+        tiles.extend(rest);
+
+        return tiles;
+    }
+
+    /// Index in [`Meld::arrangement`] of the called tile; `None` for an
+    /// ankan.
+    pub fn called_position(&self) -> Option<usize> {
+        if !self.is_open() {
+            return None;
+        }
+
+        let places = if self.kind == MeldKind::Kakan {
+            3
+        } else {
+            self.tiles.len()
+        };
+
+        return Some(match self.from {
+            1 => places - 1,
+            2 => 1,
+            _ => 0,
+        });
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ParseMeldError;
+
+impl fmt::Display for ParseMeldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid meld notation")
+    }
+}
+
+impl core::error::Error for ParseMeldError {}
+
+/// Bracketed mpsz notation: `[123m]` for a chi, `[555z]` for a pon,
+/// `[5555z]` for an open kan and `(5555z)` for an ankan. Tiles are written in
+/// the order they are laid out, and a `'` after a number marks the called
+/// tile, turned sideways: leftmost for the left player (kamicha), rightmost
+/// for the right (shimocha) and in between for the player across (toimen),
+/// e.g. `[55'5p]`. A chi is called from the left, so it can only mark its
+/// first tile, e.g. `[3'24m]`. A kakan marks two adjacent tiles, the called
+/// one and the added one stacked on it, e.g. `[5'5'55z]`. Without a mark the
+/// first written tile is the one called from the left.
+impl FromStr for Meld {
+    type Err = ParseMeldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (open, inner) =
+            if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                (true, inner)
+            } else if let Some(inner) = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+                (false, inner)
+            } else {
+                return Err(ParseMeldError);
+            };
+
+        let mut plain = String::new();
+        let mut marks = vec![];
+        let mut numbers = 0;
+
+        for c in inner.chars() {
+            match c {
+                '\'' if plain.ends_with(|c: char| c.is_ascii_digit()) => marks.push(numbers - 1),
+                '\'' => return Err(ParseMeldError),
+                c => {
+                    numbers += c.is_ascii_digit() as usize;
+                    plain.push(c);
+                }
+            }
+        }
+
+        let written = parse_tiles(&plain).map_err(|_| ParseMeldError)?;
+        let mut tiles = written.clone();
+        tiles.sort();
+
+        let same = tiles.iter().all(|t| t.kind() == tiles[0].kind());
+        let run = tiles.len() == 3
+            && !tiles[0].is_jihai()
+            && tiles[0].suit() == tiles[2].suit()
+            && tiles[1].kind() == tiles[0].kind() + 1
+            && tiles[2].kind() == tiles[0].kind() + 2;
+
+        let kind = match (open, tiles.len(), marks.as_slice()) {
+            // A chi is only ever called from the left.
+            (true, 3, [] | [0]) if run => MeldKind::Chi,
+            (true, 3, [] | [_]) if same => MeldKind::Pon,
+            (true, 4, [] | [_]) if same => MeldKind::Daiminkan,
+            (true, 4, &[a, b]) if same && b == a + 1 => MeldKind::Kakan,
+            (false, 4, []) if same => MeldKind::Ankan,
+            _ => return Err(ParseMeldError),
+        };
+
+        if !open {
+            return Ok(Meld {
+                kind,
+                tiles,
+                called: None,
+                from: 0,
+            });
+        }
+
+        // The stacked pair of a kakan takes one place in the row.
+        let places = if kind == MeldKind::Kakan {
+            3
+        } else {
+            tiles.len()
+        };
+        let called = marks.first().copied().unwrap_or(0);
+
+        return Ok(Meld {
+            kind,
+            tiles,
+            called: Some(written[called]),
+            from: match called {
+                0 => 3,
+                i if i == places - 1 => 1,
+                _ => 2,
+            },
+        });
+    }
+}
+
+/// The notation of [`Meld`]'s `FromStr`, with the tiles in table order. The
+/// mark is left out for a tile called from the left, which is what the
+/// unmarked notation means.
+impl fmt::Display for Meld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(called) = self.called_position() else {
+            return write!(f, "({})", tiles_to_string(&self.tiles));
+        };
+
+        let tiles = self.arrangement();
+        let mark = self.kind == MeldKind::Kakan || called > 0;
+
+        write!(f, "[")?;
+
+        for (i, tile) in tiles.iter().enumerate() {
+            let number = if tile.is_red() { 0 } else { tile.number() };
+            write!(f, "{}", number)?;
+
+            if mark && (i == called || (self.kind == MeldKind::Kakan && i == called + 1)) {
+                write!(f, "'")?;
+            }
+        }
+
+        write!(f, "{}]", tiles[0].suit())
+    }
+}
+
+/// Parses a hand written as concealed tiles in mpsz notation followed or
+/// interleaved by bracketed melds, e.g. `"123m456p11z [555z] (7777s)"`.
+pub fn parse_hand(s: &str) -> Result<(Vec<Tile>, Vec<Meld>), ParseMeldError> {
+    let mut concealed = String::new();
+    let mut melds = vec![];
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        let close = match c {
+            '[' => ']',
+            '(' => ')',
+            ']' | ')' => return Err(ParseMeldError),
+            c if c.is_whitespace() => continue,
+            c => {
+                concealed.push(c);
+                continue;
+            }
+        };
+
+        let mut group = String::from(c);
+
+        loop {
+            match chars.next() {
+                Some(c) if c == close => break,
+                Some(c) => group.push(c),
+                None => return Err(ParseMeldError),
+            }
+        }
+
+        group.push(close);
+        melds.push(group.parse()?);
+    }
+
+    let tiles = parse_tiles(&concealed).map_err(|_| ParseMeldError)?;
+
+    return Ok((tiles, melds));
+}
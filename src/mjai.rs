@@ -0,0 +1,1137 @@
+//! The Mjai protocol: one JSON event per line, e.g.
+//! `{"type":"dahai","actor":0,"pai":"5mr","tsumogiri":false}`.
+//!
+//! [`State`] follows a four-player match from the events one seat receives,
+//! and [`Bot`] answers each event with the action an [`Agent`] picks from the
+//! [`legal`] actions of that state. [`round_events`] records a finished
+//! [`Round`] as events, so self-play rounds can be replayed to Mjai bots.
+
+use crate::agent::{Agent, Observation};
+use crate::game;
+use crate::legal::{self, Prompt, Situation};
+use crate::meld::{Meld, MeldKind};
+use crate::round::{self, AbortiveDraw, Action, Discard, Player, Round, RoundEnd, Rules, Win};
+use crate::tile::Tile;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const HONORS: [&str; 7] = ["E", "S", "W", "N", "P", "F", "C"];
+
+/// A tile in Mjai notation: `1m` to `9s`, `5mr` for a red five, `E S W N`
+/// for the winds, `P F C` for the dragons, and `?` for a hidden tile.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Pai {
+    Tile(Tile),
+    Unknown,
+}
+
+impl Pai {
+    pub fn tile(self) -> Option<Tile> {
+        match self {
+            Pai::Tile(tile) => Some(tile),
+            Pai::Unknown => None,
+        }
+    }
+}
+
+impl From<Tile> for Pai {
+    fn from(tile: Tile) -> Self {
+        Pai::Tile(tile)
+    }
+}
+
+impl fmt::Display for Pai {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Pai::Tile(tile) = self else {
+            return write!(f, "?");
+        };
+
+        if tile.is_jihai() {
+            return write!(f, "{}", HONORS[tile.kind() - 27]);
+        }
+
+        write!(f, "{}{}", tile.number(), tile.suit())?;
+
+        if tile.is_red() {
+            write!(f, "r")?;
+        }
+
+        return Ok(());
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ParsePaiError;
+
+impl fmt::Display for ParsePaiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Mjai tile notation")
+    }
+}
+
+impl FromStr for Pai {
+    type Err = ParsePaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "?" {
+            return Ok(Pai::Unknown);
+        }
+
+        if let Some(i) = HONORS.iter().position(|&h| h == s) {
+            return Ok(Pai::Tile(Tile::new(27 + i)));
+        }
+
+        match s.as_bytes() {
+            &[n @ b'1'..=b'9', suit] | &[n @ b'5', suit, b'r'] => {
+                let offset = match suit {
+                    b'm' => 0,
+                    b'p' => 9,
+                    b's' => 18,
+                    _ => return Err(ParsePaiError),
+                };
+                let kind = offset + (n - b'1') as usize;
+
+                return Ok(Pai::Tile(if s.len() == 3 {
+                    Tile::red(kind)
+                } else {
+                    Tile::new(kind)
+                }));
+            }
+            _ => return Err(ParsePaiError),
+        }
+    }
+}
+
+impl TryFrom<String> for Pai {
+    type Error = ParsePaiError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Pai> for String {
+    fn from(pai: Pai) -> Self {
+        pai.to_string()
+    }
+}
+
+/// An Mjai event, from the server or from a bot. Fields that servers differ on
+/// are optional and omitted when empty.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    StartGame {
+        /// The seat of the player receiving the event.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<usize>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        names: Vec<String>,
+    },
+    StartKyoku {
+        bakaze: Pai,
+        /// 1 to 4 within the round wind.
+        kyoku: u8,
+        honba: u32,
+        kyotaku: u32,
+        oya: usize,
+        dora_marker: Pai,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        scores: Vec<i32>,
+        tehais: Vec<Vec<Pai>>,
+    },
+    Tsumo {
+        actor: usize,
+        pai: Pai,
+    },
+    Dahai {
+        actor: usize,
+        pai: Pai,
+        #[serde(default)]
+        tsumogiri: bool,
+    },
+    Chi {
+        actor: usize,
+        target: usize,
+        pai: Pai,
+        consumed: Vec<Pai>,
+    },
+    Pon {
+        actor: usize,
+        target: usize,
+        pai: Pai,
+        consumed: Vec<Pai>,
+    },
+    Daiminkan {
+        actor: usize,
+        target: usize,
+        pai: Pai,
+        consumed: Vec<Pai>,
+    },
+    Kakan {
+        actor: usize,
+        pai: Pai,
+        /// The tiles of the pon.
+        consumed: Vec<Pai>,
+    },
+    Ankan {
+        actor: usize,
+        consumed: Vec<Pai>,
+    },
+    Dora {
+        dora_marker: Pai,
+    },
+    Reach {
+        actor: usize,
+    },
+    ReachAccepted {
+        actor: usize,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deltas: Vec<i32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        scores: Vec<i32>,
+    },
+    Hora {
+        actor: usize,
+        /// The discarder, or the actor for tsumo.
+        target: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pai: Option<Pai>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        uradora_markers: Vec<Pai>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fu: Option<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fan: Option<u8>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deltas: Vec<i32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        scores: Vec<i32>,
+    },
+    Ryukyoku {
+        /// The player declaring kyuushu kyuuhai, when a bot sends it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        actor: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tenpais: Vec<bool>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deltas: Vec<i32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        scores: Vec<i32>,
+    },
+    EndKyoku,
+    EndGame {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        scores: Vec<i32>,
+    },
+    /// No action.
+    None,
+    Error {
+        #[serde(default)]
+        message: String,
+    },
+}
+
+/// Parses a line holding one event or, as some servers batch them, an array
+/// of events.
+pub fn parse_line(line: &str) -> Result<Vec<Event>, MjaiError> {
+    let line = line.trim();
+
+    let events = if line.starts_with('[') {
+        serde_json::from_str(line)
+    } else {
+        serde_json::from_str(line).map(|e| vec![e])
+    };
+
+    return events.map_err(|e| MjaiError::Json(e.to_string()));
+}
+
+pub fn to_line(event: &Event) -> String {
+    serde_json::to_string(event).unwrap()
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum MjaiError {
+    Json(String),
+    /// An event arrived before `start_game` or `start_kyoku`.
+    NotStarted,
+    /// A tile the player should see was sent as `?`.
+    HiddenTile,
+    /// The player was told to give up a tile they do not hold.
+    TileNotInHand(Tile),
+    /// A kakan named a pon the player does not have.
+    NoPon(Tile),
+    /// A seat outside the table.
+    InvalidSeat(usize),
+}
+
+impl fmt::Display for MjaiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MjaiError::Json(e) => write!(f, "invalid event: {}", e),
+            MjaiError::NotStarted => write!(f, "the game or round has not started"),
+            MjaiError::HiddenTile => write!(f, "a visible tile was sent as ?"),
+            MjaiError::TileNotInHand(t) => write!(f, "{} is not in the hand", Pai::from(*t)),
+            MjaiError::NoPon(t) => write!(f, "no pon of {} to add to", Pai::from(*t)),
+            MjaiError::InvalidSeat(s) => write!(f, "seat {} is not at the table", s),
+        }
+    }
+}
+
+impl std::error::Error for MjaiError {}
+
+/// The table as one seat sees it. Other players' concealed tiles are never
+/// known and stay empty.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct State {
+    pub seat: usize,
+    /// The rules of the game, which Mjai does not send. They outlast
+    /// `start_game`.
+    pub rules: Rules,
+    pub round_wind: usize,
+    pub dealer: usize,
+    pub honba: u32,
+    pub riichi_sticks: u32,
+    pub scores: Vec<i32>,
+    pub players: Vec<Player>,
+    pub dora_indicators: Vec<Tile>,
+    /// Tiles left in the live wall.
+    pub remaining: usize,
+    pub kans: usize,
+    pub uninterrupted: bool,
+    /// What the seat has to decide on after the last event.
+    pub prompt: Option<Prompt>,
+    started: bool,
+    /// Seats whose next discard declares riichi.
+    declaring: Vec<bool>,
+    rinshan: bool,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            seat: 0,
+            rules: Rules::default(),
+            round_wind: 27,
+            dealer: 0,
+            honba: 0,
+            riichi_sticks: 0,
+            scores: vec![25000; 4],
+            players: vec![Player::default(); 4],
+            dora_indicators: vec![],
+            remaining: 0,
+            kans: 0,
+            uninterrupted: true,
+            prompt: None,
+            started: false,
+            declaring: vec![false; 4],
+            rinshan: false,
+        }
+    }
+}
+
+impl State {
+    pub fn new(seat: usize) -> Self {
+        Self {
+            seat,
+            ..Self::default()
+        }
+    }
+
+    pub fn me(&self) -> &Player {
+        &self.players[self.seat]
+    }
+
+    pub fn seat_wind(&self, seat: usize) -> usize {
+        27 + (seat + 4 - self.dealer) % 4
+    }
+
+    pub fn situation(&self) -> Situation {
+        Situation {
+            rules: self.rules,
+            seat: self.seat,
+            players: 4,
+            round_wind: self.round_wind,
+            seat_wind: self.seat_wind(self.seat),
+            score: self.scores[self.seat],
+            remaining: self.remaining,
+            kans: self.kans,
+            uninterrupted: self.uninterrupted,
+        }
+    }
+
+    /// Everything an [`Agent`] needs to answer the last event, if it asked the
+    /// seat for a decision.
+    pub fn observation(&self) -> Option<Observation> {
+        let prompt = self.prompt?;
+        let me = self.me();
+        let target = match prompt {
+            Prompt::Turn { .. } => None,
+            Prompt::Discard { tile, from } | Prompt::Kan { tile, from, .. } => Some((from, tile)),
+        };
+
+        Some(Observation {
+            seat: self.seat,
+            round_wind: self.round_wind,
+            seat_wind: self.seat_wind(self.seat),
+            dealer: self.dealer,
+            honba: self.honba,
+            riichi_sticks: self.riichi_sticks,
+            scores: self.scores.clone(),
+            hand: me.concealed.clone(),
+            drawn: me.drawn,
+            melds: self.players.iter().map(|p| p.melds.clone()).collect(),
+            discards: self.players.iter().map(|p| p.discards.clone()).collect(),
+            riichi: self.players.iter().map(|p| p.is_riichi()).collect(),
+            dora_indicators: self.dora_indicators.clone(),
+            remaining: self.remaining,
+            target,
+            legal_actions: legal::legal_actions(me, &self.situation(), prompt),
+        })
+    }
+
+    pub fn update(&mut self, event: &Event) -> Result<(), MjaiError> {
+        // The seat let the previous discard or kan go, unless this is its
+        // own call or win on it.
+        if let Some(Prompt::Discard { tile, .. } | Prompt::Kan { tile, .. }) = self.prompt.take() {
+            let seat = self.seat;
+            let taken = match event {
+                Event::Chi { actor, .. }
+                | Event::Pon { actor, .. }
+                | Event::Daiminkan { actor, .. }
+                | Event::Hora { actor, .. } => *actor == seat,
+                _ => false,
+            };
+
+            if !taken {
+                self.players[seat].passed.push(tile);
+            }
+        }
+
+        if !self.started && !matches!(event, Event::StartGame { .. } | Event::StartKyoku { .. }) {
+            return Err(MjaiError::NotStarted);
+        }
+
+        let seat = self.seat;
+
+        match event {
+            Event::StartGame { id, .. } => {
+                *self = State {
+                    rules: self.rules,
+                    ..State::new(id.unwrap_or(self.seat))
+                };
+            }
+            Event::StartKyoku {
+                bakaze,
+                honba,
+                kyotaku,
+                oya,
+                dora_marker,
+                scores,
+                tehais,
+                ..
+            } => {
+                check_seat(*oya)?;
+
+                self.round_wind = bakaze.tile().map_or(27, |t| t.kind());
+                self.dealer = *oya;
+                self.honba = *honba;
+                self.riichi_sticks = *kyotaku;
+                if !scores.is_empty() {
+                    self.scores = scores.clone();
+                }
+                self.players = vec![Player::default(); 4];
+                self.players[seat].concealed = tehais
+                    .get(seat)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|p| p.tile())
+                    .collect();
+                self.dora_indicators = dora_marker.tile().into_iter().collect();
+                self.remaining = 70;
+                self.kans = 0;
+                self.uninterrupted = true;
+                self.declaring = vec![false; 4];
+                self.rinshan = false;
+                self.started = true;
+            }
+            Event::Tsumo { actor, pai } => {
+                check_seat(*actor)?;
+
+                self.remaining = self.remaining.saturating_sub(1);
+
+                if *actor == seat {
+                    let tile = pai.tile().ok_or(MjaiError::HiddenTile)?;
+                    let me = &mut self.players[seat];
+                    me.concealed.push(tile);
+                    me.drawn = Some(tile);
+
+                    self.prompt = Some(Prompt::Turn {
+                        after_call: false,
+                        rinshan: self.rinshan,
+                    });
+                }
+
+                self.rinshan = false;
+            }
+            Event::Dahai {
+                actor,
+                pai,
+                tsumogiri,
+            } => {
+                check_seat(*actor)?;
+
+                let tile = pai.tile().ok_or(MjaiError::HiddenTile)?;
+                let riichi = core::mem::take(&mut self.declaring[*actor]);
+                let player = &mut self.players[*actor];
+
+                if *actor == seat {
+                    take(&mut player.concealed, &[tile])?;
+
+                    player.kuikae.clear();
+                    if !player.is_riichi() {
+                        player.passed.clear();
+                    }
+                } else {
+                    self.prompt = Some(Prompt::Discard { tile, from: *actor });
+                }
+
+                player.drawn = None;
+                player.ippatsu = false;
+                player.discards.push(Discard {
+                    tile,
+                    tsumogiri: *tsumogiri,
+                    riichi,
+                    called: false,
+                });
+            }
+            Event::Chi {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | Event::Pon {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | Event::Daiminkan {
+                actor,
+                target,
+                pai,
+                consumed,
+            } => {
+                check_seat(*actor)?;
+                check_seat(*target)?;
+
+                let kind = match event {
+                    Event::Chi { .. } => MeldKind::Chi,
+                    Event::Pon { .. } => MeldKind::Pon,
+                    _ => MeldKind::Daiminkan,
+                };
+                let called = pai.tile().ok_or(MjaiError::HiddenTile)?;
+                let consumed = tiles(consumed);
+
+                if let Some(discard) = self.players[*target].discards.last_mut() {
+                    discard.called = true;
+                }
+
+                let player = &mut self.players[*actor];
+
+                if *actor == seat {
+                    take(&mut player.concealed, &consumed)?;
+
+                    if kind != MeldKind::Daiminkan {
+                        if self.rules.forbid_kuikae {
+                            player.kuikae = legal::kuikae_kinds(&consumed, called, kind);
+                        }
+
+                        self.prompt = Some(Prompt::Turn {
+                            after_call: true,
+                            rinshan: false,
+                        });
+                    }
+                }
+
+                let mut meld_tiles = consumed;
+                meld_tiles.push(called);
+                meld_tiles.sort();
+
+                player.melds.push(Meld {
+                    kind,
+                    tiles: meld_tiles,
+                    called: Some(called),
+                    from: (target + 4 - actor) % 4,
+                });
+
+                if kind == MeldKind::Daiminkan {
+                    self.kans += 1;
+                    self.rinshan = true;
+                }
+
+                self.interrupt();
+            }
+            Event::Kakan { actor, pai, .. } => {
+                check_seat(*actor)?;
+
+                let tile = pai.tile().ok_or(MjaiError::HiddenTile)?;
+                let player = &mut self.players[*actor];
+
+                if *actor == seat {
+                    take(&mut player.concealed, &[tile])?;
+                    player.drawn = None;
+                } else {
+                    self.prompt = Some(Prompt::Kan {
+                        tile,
+                        from: *actor,
+                        ankan: false,
+                    });
+                }
+
+                let meld = player
+                    .melds
+                    .iter_mut()
+                    .find(|m| m.kind == MeldKind::Pon && m.base() == tile.kind())
+                    .ok_or(MjaiError::NoPon(tile))?;
+                meld.kind = MeldKind::Kakan;
+                meld.tiles.push(tile);
+                meld.tiles.sort();
+
+                self.kans += 1;
+                self.rinshan = true;
+                self.interrupt();
+            }
+            Event::Ankan { actor, consumed } => {
+                check_seat(*actor)?;
+
+                let mut consumed = tiles(consumed);
+                consumed.sort();
+                let player = &mut self.players[*actor];
+
+                if *actor == seat {
+                    take(&mut player.concealed, &consumed)?;
+                    player.drawn = None;
+                } else if let Some(&tile) = consumed.first() {
+                    self.prompt = Some(Prompt::Kan {
+                        tile,
+                        from: *actor,
+                        ankan: true,
+                    });
+                }
+
+                player.melds.push(Meld {
+                    kind: MeldKind::Ankan,
+                    tiles: consumed,
+                    called: None,
+                    from: 0,
+                });
+
+                self.kans += 1;
+                self.rinshan = true;
+                self.interrupt();
+            }
+            Event::Dora { dora_marker } => {
+                self.dora_indicators.extend(dora_marker.tile());
+            }
+            Event::Reach { actor } => {
+                check_seat(*actor)?;
+
+                self.declaring[*actor] = true;
+            }
+            Event::ReachAccepted {
+                actor,
+                deltas,
+                scores,
+            } => {
+                check_seat(*actor)?;
+
+                let uninterrupted = self.uninterrupted;
+                let player = &mut self.players[*actor];
+
+                player.riichi = Some(player.discards.len().saturating_sub(1));
+                player.double_riichi = uninterrupted && player.discards.len() == 1;
+                player.ippatsu = true;
+
+                self.riichi_sticks += 1;
+                self.update_scores(deltas, scores, |s| if s == *actor { -1000 } else { 0 });
+            }
+            Event::Hora { deltas, scores, .. } | Event::Ryukyoku { deltas, scores, .. } => {
+                self.update_scores(deltas, scores, |_| 0);
+            }
+            Event::EndKyoku | Event::None | Event::Error { .. } => {}
+            Event::EndGame { scores } => {
+                if !scores.is_empty() {
+                    self.scores = scores.clone();
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn interrupt(&mut self) {
+        self.uninterrupted = false;
+
+        for player in self.players.iter_mut() {
+            player.ippatsu = false;
+        }
+    }
+
+    /// Takes `scores` when given, or else applies `deltas`, or else the
+    /// default delta per seat.
+    fn update_scores(&mut self, deltas: &[i32], scores: &[i32], default: impl Fn(usize) -> i32) {
+        if scores.len() == 4 {
+            self.scores = scores.to_vec();
+        } else if deltas.len() == 4 {
+            for (score, delta) in self.scores.iter_mut().zip(deltas) {
+                *score += delta;
+            }
+        } else {
+            for (seat, score) in self.scores.iter_mut().enumerate() {
+                *score += default(seat);
+            }
+        }
+    }
+}
+
+fn check_seat(seat: usize) -> Result<(), MjaiError> {
+    if seat >= 4 {
+        return Err(MjaiError::InvalidSeat(seat));
+    }
+
+    return Ok(());
+}
+
+fn tiles(pais: &[Pai]) -> Vec<Tile> {
+    pais.iter().filter_map(|p| p.tile()).collect()
+}
+
+fn take(concealed: &mut Vec<Tile>, tiles: &[Tile]) -> Result<(), MjaiError> {
+    for tile in tiles {
+        let i = concealed
+            .iter()
+            .position(|t| t == tile)
+            .ok_or(MjaiError::TileNotInHand(*tile))?;
+        concealed.remove(i);
+    }
+
+    return Ok(());
+}
+
+/// Plays one seat over Mjai: every event is answered with an action or
+/// `none`.
+pub struct Bot {
+    state: State,
+    agent: Box<dyn Agent>,
+    /// The tile to discard once the server echoes our riichi declaration.
+    riichi: Option<Tile>,
+}
+
+impl Bot {
+    pub fn new(agent: Box<dyn Agent>, rules: Rules) -> Self {
+        Self {
+            state: State {
+                rules,
+                ..State::default()
+            },
+            agent,
+            riichi: None,
+        }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn react(&mut self, event: &Event) -> Result<Event, MjaiError> {
+        self.state.update(event)?;
+
+        let seat = self.state.seat;
+
+        if let Event::Reach { actor } = event
+            && *actor == seat
+            && let Some(tile) = self.riichi.take()
+        {
+            return Ok(Event::Dahai {
+                actor: seat,
+                pai: tile.into(),
+                tsumogiri: self.state.me().drawn == Some(tile),
+            });
+        }
+
+        let Some(observation) = self.state.observation() else {
+            return Ok(Event::None);
+        };
+
+        if observation.legal_actions.iter().all(|&a| a == Action::Pass) {
+            return Ok(Event::None);
+        }
+
+        let action = self.agent.act(&observation);
+
+        return Ok(self.answer(action, observation.target));
+    }
+
+    fn answer(&mut self, action: Action, target: Option<(usize, Tile)>) -> Event {
+        let seat = self.state.seat;
+        let me = self.state.me();
+        let (from, called) = target.unwrap_or((seat, Tile::new(0)));
+        let pais = |tiles: &[Tile]| tiles.iter().map(|&t| Pai::from(t)).collect::<Vec<_>>();
+        let of_kind = |kind: usize| {
+            me.concealed
+                .iter()
+                .copied()
+                .filter(|t| t.kind() == kind)
+                .collect::<Vec<_>>()
+        };
+
+        match action {
+            Action::Discard(tile) => Event::Dahai {
+                actor: seat,
+                pai: tile.into(),
+                tsumogiri: me.drawn == Some(tile),
+            },
+            Action::Riichi(tile) => {
+                self.riichi = Some(tile);
+                Event::Reach { actor: seat }
+            }
+            Action::Tsumo | Action::Ron => Event::Hora {
+                actor: seat,
+                target: from,
+                pai: target.map(|(_, t)| t).or(me.drawn).map(Pai::from),
+                uradora_markers: vec![],
+                fu: None,
+                fan: None,
+                deltas: vec![],
+                scores: vec![],
+            },
+            Action::Chi(tiles) => Event::Chi {
+                actor: seat,
+                target: from,
+                pai: called.into(),
+                consumed: pais(&tiles),
+            },
+            Action::Pon(tiles) => Event::Pon {
+                actor: seat,
+                target: from,
+                pai: called.into(),
+                consumed: pais(&tiles),
+            },
+            Action::Daiminkan => Event::Daiminkan {
+                actor: seat,
+                target: from,
+                pai: called.into(),
+                consumed: pais(&of_kind(called.kind())),
+            },
+            Action::Ankan(kind) => Event::Ankan {
+                actor: seat,
+                consumed: pais(&of_kind(kind)),
+            },
+            Action::Kakan(tile) => {
+                let pon = me
+                    .melds
+                    .iter()
+                    .find(|m| m.kind == MeldKind::Pon && m.base() == tile.kind())
+                    .unwrap();
+
+                Event::Kakan {
+                    actor: seat,
+                    pai: tile.into(),
+                    consumed: pais(&pon.tiles),
+                }
+            }
+            Action::KyuushuKyuuhai => Event::Ryukyoku {
+                actor: Some(seat),
+                reason: Some("kyushukyuhai".into()),
+                tenpais: vec![],
+                deltas: vec![],
+                scores: vec![],
+            },
+            Action::Pass => Event::None,
+        }
+    }
+}
+
+/// Records a round as the events every seat would see, without `start_game`
+/// and `end_game`. Pass the events through [`hide`] to get one seat's view.
+pub fn round_events(round: &Round) -> Vec<Event> {
+    let n = round.player_count();
+    let accepted = round
+        .events()
+        .iter()
+        .filter(|e| matches!(e, round::Event::RiichiAccepted { .. }))
+        .count();
+    let mut scores = round.scores().to_vec();
+
+    // Undo the riichi deposits made during the round.
+    for e in round.events() {
+        if let round::Event::RiichiAccepted { seat } = e {
+            scores[*seat] += 1000;
+        }
+    }
+
+    let mut events = vec![Event::StartKyoku {
+        bakaze: Tile::new(round.round_wind()).into(),
+        kyoku: round.dealer() as u8 + 1,
+        honba: round.honba(),
+        kyotaku: round.riichi_sticks() - accepted as u32,
+        oya: round.dealer(),
+        dora_marker: round.wall().dora_indicators()[0].into(),
+        scores: scores.clone(),
+        tehais: round
+            .initial_hands()
+            .iter()
+            .map(|h| h.iter().map(|&t| Pai::from(t)).collect())
+            .collect(),
+    }];
+    let mut pons: Vec<Vec<Meld>> = vec![vec![]; n];
+    let mut drawn: Vec<Option<Tile>> = vec![None; n];
+
+    for e in round.events() {
+        match e {
+            round::Event::Draw { seat, tile, .. } => {
+                drawn[*seat] = Some(*tile);
+                events.push(Event::Tsumo {
+                    actor: *seat,
+                    pai: (*tile).into(),
+                });
+            }
+            round::Event::Discard {
+                seat,
+                tile,
+                tsumogiri,
+                riichi,
+            } => {
+                if *riichi {
+                    events.push(Event::Reach { actor: *seat });
+                }
+
+                events.push(Event::Dahai {
+                    actor: *seat,
+                    pai: (*tile).into(),
+                    tsumogiri: *tsumogiri,
+                });
+            }
+            round::Event::RiichiAccepted { seat } => {
+                scores[*seat] -= 1000;
+
+                let mut deltas = vec![0; n];
+                deltas[*seat] = -1000;
+
+                events.push(Event::ReachAccepted {
+                    actor: *seat,
+                    deltas,
+                    scores: scores.clone(),
+                });
+            }
+            round::Event::Meld { seat, meld } => {
+                let actor = *seat;
+                let pais = |tiles: &[Tile]| tiles.iter().map(|&t| Pai::from(t)).collect();
+                let called = || {
+                    let called = meld.called.unwrap();
+                    let mut consumed = meld.tiles.clone();
+                    let i = consumed.iter().position(|&t| t == called).unwrap();
+                    consumed.remove(i);
+
+                    (called.into(), pais(&consumed), (actor + meld.from) % n)
+                };
+
+                events.push(match meld.kind {
+                    MeldKind::Chi => {
+                        let (pai, consumed, target) = called();
+                        Event::Chi {
+                            actor,
+                            target,
+                            pai,
+                            consumed,
+                        }
+                    }
+                    MeldKind::Pon => {
+                        pons[actor].push(meld.clone());
+
+                        let (pai, consumed, target) = called();
+                        Event::Pon {
+                            actor,
+                            target,
+                            pai,
+                            consumed,
+                        }
+                    }
+                    MeldKind::Daiminkan => {
+                        let (pai, consumed, target) = called();
+                        Event::Daiminkan {
+                            actor,
+                            target,
+                            pai,
+                            consumed,
+                        }
+                    }
+                    MeldKind::Kakan => {
+                        let i = pons[actor]
+                            .iter()
+                            .position(|m| m.base() == meld.base())
+                            .unwrap();
+                        let pon = pons[actor].remove(i);
+                        let mut added = meld.tiles.clone();
+                        for t in pon.tiles.iter() {
+                            let j = added.iter().position(|a| a == t).unwrap();
+                            added.remove(j);
+                        }
+
+                        Event::Kakan {
+                            actor,
+                            pai: added[0].into(),
+                            consumed: pais(&pon.tiles),
+                        }
+                    }
+                    MeldKind::Ankan => Event::Ankan {
+                        actor,
+                        consumed: pais(&meld.tiles),
+                    },
+                });
+            }
+            round::Event::Dora { indicator } => events.push(Event::Dora {
+                dora_marker: (*indicator).into(),
+            }),
+        }
+    }
+
+    let settlement = game::settle(round);
+    let deltas = settlement.as_ref().map_or(vec![], |s| s.deltas.clone());
+    let final_scores = scores
+        .iter()
+        .zip(deltas.iter())
+        .map(|(s, d)| s + d)
+        .collect::<Vec<_>>();
+
+    match round.result() {
+        Some(RoundEnd::Tsumo(win)) => {
+            let agari = &settlement.as_ref().unwrap().agari[0];
+            events.push(hora(round, win, agari.fu, agari.han, deltas, final_scores));
+        }
+        Some(RoundEnd::Ron(wins)) => {
+            let agari = &settlement.as_ref().unwrap().agari;
+
+            for (i, win) in wins.iter().enumerate() {
+                // The score change covers every winner and goes on the last.
+                let (d, s) = if i + 1 == wins.len() {
+                    (deltas.clone(), final_scores.clone())
+                } else {
+                    (vec![], vec![])
+                };
+
+                events.push(hora(round, win, agari[i].fu, agari[i].han, d, s));
+            }
+        }
+        Some(RoundEnd::ExhaustiveDraw {
+            tenpai,
+            nagashi_mangan,
+        }) => events.push(Event::Ryukyoku {
+            actor: None,
+            reason: Some(if nagashi_mangan.is_empty() {
+                "fanpai".into()
+            } else {
+                "nagashimangan".into()
+            }),
+            tenpais: tenpai.clone(),
+            deltas,
+            scores: final_scores,
+        }),
+        Some(RoundEnd::Abortive(draw)) => {
+            let (actor, reason) = match draw {
+                AbortiveDraw::KyuushuKyuuhai { seat } => (Some(*seat), "kyushukyuhai"),
+                AbortiveDraw::SuufonRenda => (None, "sufonrenda"),
+                AbortiveDraw::SuuchaRiichi => (None, "suchareach"),
+                AbortiveDraw::SuukanSanra => (None, "sukaikan"),
+                AbortiveDraw::Sanchahou => (None, "sanchaho"),
+            };
+
+            events.push(Event::Ryukyoku {
+                actor,
+                reason: Some(reason.into()),
+                tenpais: vec![],
+                deltas,
+                scores: final_scores,
+            });
+        }
+        None => return events,
+    }
+
+    events.push(Event::EndKyoku);
+
+    return events;
+}
+
+fn hora(round: &Round, win: &Win, fu: u8, han: u8, deltas: Vec<i32>, scores: Vec<i32>) -> Event {
+    let ura = if win.riichi {
+        round
+            .wall()
+            .ura_dora_indicators()
+            .iter()
+            .map(|&t| Pai::from(t))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Event::Hora {
+        actor: win.seat,
+        target: win.from.unwrap_or(win.seat),
+        pai: Some(win.tile.into()),
+        uradora_markers: ura,
+        fu: Some(fu),
+        fan: Some(han),
+        deltas,
+        scores,
+    }
+}
+
+/// `events` as `seat` receives them: other players' starting hands and draws
+/// are hidden.
+pub fn hide(events: &[Event], seat: usize) -> Vec<Event> {
+    events
+        .iter()
+        .map(|e| match e {
+            Event::StartKyoku {
+                bakaze,
+                kyoku,
+                honba,
+                kyotaku,
+                oya,
+                dora_marker,
+                scores,
+                tehais,
+            } => Event::StartKyoku {
+                bakaze: *bakaze,
+                kyoku: *kyoku,
+                honba: *honba,
+                kyotaku: *kyotaku,
+                oya: *oya,
+                dora_marker: *dora_marker,
+                scores: scores.clone(),
+                tehais: tehais
+                    .iter()
+                    .enumerate()
+                    .map(|(s, h)| {
+                        if s == seat {
+                            h.clone()
+                        } else {
+                            vec![Pai::Unknown; h.len()]
+                        }
+                    })
+                    .collect(),
+            },
+            Event::Tsumo { actor, .. } if *actor != seat => Event::Tsumo {
+                actor: *actor,
+                pai: Pai::Unknown,
+            },
+            _ => e.clone(),
+        })
+        .collect()
+}
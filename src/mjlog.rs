@@ -0,0 +1,818 @@
+//! Tenhou mjlog replays: the XML format of `https://tenhou.net/0/log/?...`.
+//!
+//! Tiles are identified by Tenhou's 136 ids, four per kind in the crate's
+//! kind order (`id / 4` is the kind); with red fives enabled ids 16, 52 and 88
+//! are the red ones. [`MjlogRound::steps`] replays a round into concealed
+//! tiles and melds after every event.
+
+use crate::meld::{Meld, MeldKind};
+use crate::round;
+use crate::shanten::Hand;
+use crate::tile::Tile;
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum MjlogError {
+    /// Malformed XML at the byte offset.
+    Syntax(usize),
+    MissingAttribute(&'static str),
+    InvalidNumber(String),
+    /// A three-player kita (nukidora), which the crate does not model.
+    UnsupportedMeld(u32),
+    /// A meld code naming tiles outside the 136.
+    InvalidMeld(u32),
+    /// A tile id outside the 136.
+    InvalidTile(u32),
+    /// A seat outside the table.
+    InvalidSeat(usize),
+    /// A die outside 1-6, as stored in the seed (0-5).
+    InvalidDice(u32),
+    /// An event before the first `INIT`.
+    NotStarted,
+    TileNotInHand {
+        seat: usize,
+        id: u8,
+    },
+    /// A kakan without the pon it extends.
+    NoPon {
+        seat: usize,
+        kind: usize,
+    },
+}
+
+impl fmt::Display for MjlogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MjlogError::Syntax(at) => write!(f, "malformed XML at byte {}", at),
+            MjlogError::MissingAttribute(name) => write!(f, "missing attribute {}", name),
+            MjlogError::InvalidNumber(s) => write!(f, "invalid number {:?}", s),
+            MjlogError::UnsupportedMeld(m) => write!(f, "unsupported meld code {}", m),
+            MjlogError::InvalidMeld(m) => write!(f, "invalid meld code {}", m),
+            MjlogError::InvalidTile(id) => write!(f, "invalid tile id {}", id),
+            MjlogError::InvalidSeat(seat) => write!(f, "seat {} is not at the table", seat),
+            MjlogError::InvalidDice(d) => write!(f, "invalid die {}", d),
+            MjlogError::NotStarted => write!(f, "event before the first INIT"),
+            MjlogError::TileNotInHand { seat, id } => {
+                write!(f, "tile {} is not in the hand of seat {}", id, seat)
+            }
+            MjlogError::NoPon { seat, kind } => {
+                write!(f, "seat {} has no pon of kind {} to add to", seat, kind)
+            }
+        }
+    }
+}
+
+impl core::error::Error for MjlogError {}
+
+/// The tile with Tenhou id `id` (0-135).
+pub fn tile(id: u8, aka: bool) -> Tile {
+    let kind = id as usize / 4;
+
+    if aka && matches!(id, 16 | 52 | 88) {
+        Tile::red(kind)
+    } else {
+        Tile::new(kind)
+    }
+}
+
+/// A decoded `N` meld code, with tiles as Tenhou ids.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeldCode {
+    pub kind: MeldKind,
+    /// Every tile of the meld, sorted.
+    pub ids: Vec<u8>,
+    /// The tile taken from another player; for a kakan, the tile of the
+    /// original pon. `None` for an ankan.
+    pub called: Option<u8>,
+    /// The tile added to a pon by a kakan.
+    pub added: Option<u8>,
+    /// Seat of the discarder relative to the caller as in [`Meld::from`].
+    pub from: usize,
+}
+
+impl MeldCode {
+    /// Decodes the bitfield of an `N` tag's `m` attribute. The low two bits
+    /// are the relative seat of the discarder; then bit 2 marks a chi, bits 3
+    /// and 4 a pon and a kakan, bit 5 a kita, and none of them a daiminkan or
+    /// an ankan.
+    pub fn decode(m: u32) -> Result<Self, MjlogError> {
+        let from = (m & 3) as usize;
+
+        if m & 0x4 != 0 {
+            // Bits 10-15: the lowest tile as one of the 21 chi starts times 3
+            // plus the position of the called tile; bits 3-8: the copy (0-3)
+            // of each tile.
+            let t = (m >> 10) as usize;
+            let called = t % 3;
+            let start = t / 3;
+            if m > 0xFFFF || start >= 21 {
+                return Err(MjlogError::InvalidMeld(m));
+            }
+            let kind = start / 7 * 9 + start % 7;
+            let ids = (0..3)
+                .map(|i| ((kind + i) * 4) as u8 + ((m >> (3 + 2 * i)) & 3) as u8)
+                .collect::<Vec<_>>();
+
+            return Ok(Self {
+                kind: MeldKind::Chi,
+                called: Some(ids[called]),
+                ids,
+                added: None,
+                from,
+            });
+        }
+
+        if m & 0x18 != 0 {
+            // Bits 9-15: the kind times 3 plus the position of the called tile
+            // among the three of the pon; bits 5-6: the copy left out of the
+            // pon.
+            let t = (m >> 9) as usize;
+            let called = t % 3;
+            if m > 0xFFFF || t / 3 >= 34 {
+                return Err(MjlogError::InvalidMeld(m));
+            }
+            let base = (t / 3 * 4) as u8;
+            let unused = base + ((m >> 5) & 3) as u8;
+            let pon = (base..base + 4)
+                .filter(|&id| id != unused)
+                .collect::<Vec<_>>();
+
+            if m & 0x8 != 0 {
+                return Ok(Self {
+                    kind: MeldKind::Pon,
+                    called: Some(pon[called]),
+                    ids: pon,
+                    added: None,
+                    from,
+                });
+            }
+
+            return Ok(Self {
+                kind: MeldKind::Kakan,
+                called: Some(pon[called]),
+                ids: (base..base + 4).collect(),
+                added: Some(unused),
+                from,
+            });
+        }
+
+        if m & 0x20 != 0 {
+            return Err(MjlogError::UnsupportedMeld(m));
+        }
+
+        // Bits 8-15: the called tile, or for an ankan any of the four.
+        if m >= 136 << 8 {
+            return Err(MjlogError::InvalidMeld(m));
+        }
+        let id = (m >> 8) as u8;
+        let base = id / 4 * 4;
+
+        if from == 0 {
+            return Ok(Self {
+                kind: MeldKind::Ankan,
+                ids: (base..base + 4).collect(),
+                called: None,
+                added: None,
+                from,
+            });
+        }
+
+        return Ok(Self {
+            kind: MeldKind::Daiminkan,
+            ids: (base..base + 4).collect(),
+            called: Some(id),
+            added: None,
+            from,
+        });
+    }
+
+    /// The inverse of [`MeldCode::decode`].
+    pub fn encode(&self) -> u32 {
+        let from = self.from as u32;
+        let kind = self.ids[0] as u32 / 4;
+        let position = |called: u8, ids: &[u8]| ids.iter().position(|&i| i == called).unwrap();
+
+        match self.kind {
+            MeldKind::Chi => {
+                let start = kind / 9 * 7 + kind % 9;
+                let t = start * 3 + position(self.called.unwrap(), &self.ids) as u32;
+                let copies = (0..3).fold(0, |acc, i| acc | (self.ids[i] as u32 & 3) << (3 + 2 * i));
+
+                (t << 10) | copies | 0x4 | from
+            }
+            MeldKind::Pon | MeldKind::Kakan => {
+                let (pon, unused, flag) = if self.kind == MeldKind::Pon {
+                    let unused = (kind * 4..kind * 4 + 4)
+                        .find(|&id| !self.ids.contains(&(id as u8)))
+                        .unwrap() as u8;
+                    (self.ids.clone(), unused, 0x8)
+                } else {
+                    let unused = self.added.unwrap();
+                    let pon = self
+                        .ids
+                        .iter()
+                        .copied()
+                        .filter(|&id| id != unused)
+                        .collect();
+                    (pon, unused, 0x10)
+                };
+                let t = kind * 3 + position(self.called.unwrap(), &pon) as u32;
+
+                (t << 9) | ((unused as u32 & 3) << 5) | flag | from
+            }
+            MeldKind::Daiminkan => ((self.called.unwrap() as u32) << 8) | from,
+            MeldKind::Ankan => (self.ids[0] as u32) << 8,
+        }
+    }
+
+    pub fn to_meld(&self, aka: bool) -> Meld {
+        let mut tiles = self.ids.iter().map(|&id| tile(id, aka)).collect::<Vec<_>>();
+        tiles.sort();
+
+        Meld {
+            kind: self.kind,
+            tiles,
+            called: self.called.map(|id| tile(id, aka)),
+            from: self.from,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Agari {
+    pub seat: usize,
+    /// The discarder; the winner for tsumo.
+    pub from: usize,
+    /// Concealed tiles including the winning tile.
+    pub hand: Vec<u8>,
+    pub melds: Vec<MeldCode>,
+    pub machi: u8,
+    pub fu: u32,
+    pub points: u32,
+    /// `(yaku id, han)` pairs in Tenhou's numbering.
+    pub yaku: Vec<(u32, u32)>,
+    pub yakuman: Vec<u32>,
+    pub dora_indicators: Vec<u8>,
+    pub ura_dora_indicators: Vec<u8>,
+    /// Score changes of every seat.
+    pub deltas: Vec<i32>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ryuukyoku {
+    /// Tenhou's reason for an abortive draw, such as `yao9` or `reach4`;
+    /// `None` for an exhaustive draw.
+    pub reason: Option<String>,
+    /// Seats that showed their hand as tenpai.
+    pub tenpai: Vec<usize>,
+    pub deltas: Vec<i32>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MjlogEvent {
+    /// `T`, `U`, `V`, `W`.
+    Draw {
+        seat: usize,
+        id: u8,
+    },
+    /// `D`, `E`, `F`, `G`.
+    Discard {
+        seat: usize,
+        id: u8,
+        tsumogiri: bool,
+    },
+    /// `N`.
+    Meld {
+        seat: usize,
+        meld: MeldCode,
+    },
+    /// `REACH`: step 1 declares, step 2 is accepted once the discard passes.
+    Riichi {
+        seat: usize,
+        step: u8,
+    },
+    Dora {
+        id: u8,
+    },
+    Agari(Agari),
+    Ryuukyoku(Ryuukyoku),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MjlogRound {
+    /// 0 for East 1, 4 for South 1, and so on.
+    pub kyoku: usize,
+    pub honba: u32,
+    pub riichi_sticks: u32,
+    pub dice: [u8; 2],
+    pub dora_indicator: u8,
+    pub dealer: usize,
+    pub scores: Vec<i32>,
+    /// Starting hands as Tenhou ids, by seat.
+    pub hands: Vec<Vec<u8>>,
+    pub events: Vec<MjlogEvent>,
+    /// Red fives are in play.
+    pub aka: bool,
+}
+
+/// Concealed tiles, melds and discards of every seat after an event.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Step {
+    /// Index into [`MjlogRound::events`].
+    pub event: usize,
+    pub concealed: Vec<Vec<Tile>>,
+    pub melds: Vec<Vec<Meld>>,
+    pub discards: Vec<Vec<Tile>>,
+}
+
+impl Step {
+    pub fn hand(&self, seat: usize) -> Hand {
+        self.concealed[seat].iter().copied().collect()
+    }
+}
+
+impl MjlogRound {
+    /// `seat` if it is one of the players.
+    fn seat(&self, seat: usize) -> Result<usize, MjlogError> {
+        if seat >= self.hands.len() {
+            return Err(MjlogError::InvalidSeat(seat));
+        }
+
+        return Ok(seat);
+    }
+
+    /// Tile kind of the round wind.
+    pub fn round_wind(&self) -> usize {
+        27 + self.kyoku / 4
+    }
+
+    /// Starting hands as tiles, by seat.
+    pub fn initial_hands(&self) -> Vec<Vec<Tile>> {
+        self.hands
+            .iter()
+            .map(|h| h.iter().map(|&id| tile(id, self.aka)).collect())
+            .collect()
+    }
+
+    /// The events as the crate's [`round::Event`]s. Kan replacement draws
+    /// are not marked as rinshan, which the log does not tell apart.
+    pub fn round_events(&self) -> Vec<round::Event> {
+        let mut declared = None;
+        let mut events = vec![];
+
+        for event in self.events.iter() {
+            match event {
+                MjlogEvent::Draw { seat, id } => events.push(round::Event::Draw {
+                    seat: *seat,
+                    tile: tile(*id, self.aka),
+                    rinshan: false,
+                }),
+                MjlogEvent::Discard {
+                    seat,
+                    id,
+                    tsumogiri,
+                } => events.push(round::Event::Discard {
+                    seat: *seat,
+                    tile: tile(*id, self.aka),
+                    tsumogiri: *tsumogiri,
+                    riichi: declared.take() == Some(*seat),
+                }),
+                MjlogEvent::Meld { seat, meld } => events.push(round::Event::Meld {
+                    seat: *seat,
+                    meld: meld.to_meld(self.aka),
+                }),
+                MjlogEvent::Riichi { seat, step: 1 } => declared = Some(*seat),
+                MjlogEvent::Riichi { seat, .. } => {
+                    events.push(round::Event::RiichiAccepted { seat: *seat })
+                }
+                MjlogEvent::Dora { id } => events.push(round::Event::Dora {
+                    indicator: tile(*id, self.aka),
+                }),
+                MjlogEvent::Agari(_) | MjlogEvent::Ryuukyoku(_) => {}
+            }
+        }
+
+        return events;
+    }
+
+    /// Replays the round, one [`Step`] per event.
+    pub fn steps(&self) -> Result<Vec<Step>, MjlogError> {
+        let n = self.hands.len();
+        let mut concealed = self.hands.clone();
+        let mut melds: Vec<Vec<MeldCode>> = vec![vec![]; n];
+        let mut discards: Vec<Vec<u8>> = vec![vec![]; n];
+        let mut steps = vec![];
+
+        for (i, event) in self.events.iter().enumerate() {
+            match event {
+                MjlogEvent::Draw { seat, id } => concealed[*seat].push(*id),
+                MjlogEvent::Discard { seat, id, .. } => {
+                    take(&mut concealed[*seat], *seat, &[*id])?;
+                    discards[*seat].push(*id);
+                }
+                MjlogEvent::Meld { seat, meld } => {
+                    let seat = *seat;
+
+                    match meld.kind {
+                        MeldKind::Kakan => {
+                            let added = meld.added.unwrap();
+                            let kind = added as usize / 4;
+                            let pon = melds[seat]
+                                .iter_mut()
+                                .find(|m| m.kind == MeldKind::Pon && m.ids[0] as usize / 4 == kind)
+                                .ok_or(MjlogError::NoPon { seat, kind })?;
+
+                            take(&mut concealed[seat], seat, &[added])?;
+                            *pon = meld.clone();
+                        }
+                        MeldKind::Ankan => {
+                            take(&mut concealed[seat], seat, &meld.ids)?;
+                            melds[seat].push(meld.clone());
+                        }
+                        _ => {
+                            let called = meld.called.unwrap();
+                            let own = meld
+                                .ids
+                                .iter()
+                                .copied()
+                                .filter(|&id| id != called)
+                                .collect::<Vec<_>>();
+
+                            take(&mut concealed[seat], seat, &own)?;
+                            melds[seat].push(meld.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            let tiles = |ids: &Vec<u8>| ids.iter().map(|&id| tile(id, self.aka)).collect();
+
+            steps.push(Step {
+                event: i,
+                concealed: concealed.iter().map(tiles).collect(),
+                melds: melds
+                    .iter()
+                    .map(|ms| ms.iter().map(|m| m.to_meld(self.aka)).collect())
+                    .collect(),
+                discards: discards.iter().map(tiles).collect(),
+            });
+        }
+
+        return Ok(steps);
+    }
+}
+
+fn take(concealed: &mut Vec<u8>, seat: usize, ids: &[u8]) -> Result<(), MjlogError> {
+    for &id in ids {
+        let i = concealed
+            .iter()
+            .position(|&c| c == id)
+            .ok_or(MjlogError::TileNotInHand { seat, id })?;
+        concealed.remove(i);
+    }
+
+    return Ok(());
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mjlog {
+    /// Player names, decoded from the `UN` tag.
+    pub names: Vec<String>,
+    /// The `type` attribute of `GO`: lobby rules as bit flags.
+    pub rules: u32,
+    pub rounds: Vec<MjlogRound>,
+    /// Final scores from the `owari` attribute.
+    pub final_scores: Option<Vec<i32>>,
+}
+
+impl Mjlog {
+    /// Red fives are in play (bit 1 of the rules turns them off).
+    pub fn aka(&self) -> bool {
+        self.rules & 0x2 == 0
+    }
+
+    pub fn parse(xml: &str) -> Result<Self, MjlogError> {
+        let mut log = Mjlog::default();
+        let mut last_draw = vec![None; 4];
+
+        for tag in tags(xml) {
+            let tag = tag?;
+            let name = tag.name;
+
+            if let Some((seat, id)) = numbered(name, "TUVW") {
+                let round = log.rounds.last_mut().ok_or(MjlogError::NotStarted)?;
+                let (seat, id) = (round.seat(seat)?, tile_id(id)?);
+                last_draw[seat] = Some(id);
+                round.events.push(MjlogEvent::Draw { seat, id });
+                continue;
+            }
+
+            if let Some((seat, id)) = numbered(name, "DEFG") {
+                let round = log.rounds.last_mut().ok_or(MjlogError::NotStarted)?;
+                let (seat, id) = (round.seat(seat)?, tile_id(id)?);
+                let tsumogiri = last_draw[seat].take() == Some(id);
+                round.events.push(MjlogEvent::Discard {
+                    seat,
+                    id,
+                    tsumogiri,
+                });
+                continue;
+            }
+
+            match name {
+                "GO" => log.rules = tag.number("type")?,
+                // A reconnection repeats UN without names.
+                "UN" if tag.get("n0").is_some() => {
+                    log.names = ["n0", "n1", "n2", "n3"]
+                        .iter()
+                        .filter_map(|n| tag.get(n))
+                        .map(percent_decode)
+                        .collect();
+                }
+                "INIT" => {
+                    let seed = numbers::<u32>(tag.require("seed")?)?;
+                    if seed.len() < 6 {
+                        return Err(MjlogError::MissingAttribute("seed"));
+                    }
+                    if let Some(&die) = seed[3..5].iter().find(|&&d| d > 5) {
+                        return Err(MjlogError::InvalidDice(die));
+                    }
+
+                    let scores = numbers::<i32>(tag.require("ten")?)?;
+                    let hands = ["hai0", "hai1", "hai2", "hai3"]
+                        .iter()
+                        .filter_map(|h| tag.get(h))
+                        .filter(|h| !h.is_empty())
+                        .map(tile_ids)
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    last_draw = vec![None; hands.len()];
+                    let round = MjlogRound {
+                        kyoku: seed[0] as usize,
+                        honba: seed[1],
+                        riichi_sticks: seed[2],
+                        dice: [seed[3] as u8 + 1, seed[4] as u8 + 1],
+                        dora_indicator: tile_id(seed[5])?,
+                        dealer: tag.number("oya")?,
+                        scores: scores.iter().map(|s| s * 100).collect(),
+                        hands,
+                        events: vec![],
+                        aka: log.aka(),
+                    };
+                    round.seat(round.dealer)?;
+                    log.rounds.push(round);
+                }
+                "N" => {
+                    let round = log.rounds.last_mut().ok_or(MjlogError::NotStarted)?;
+                    round.events.push(MjlogEvent::Meld {
+                        seat: round.seat(tag.number("who")?)?,
+                        meld: MeldCode::decode(tag.number("m")?)?,
+                    });
+                }
+                "REACH" => {
+                    let round = log.rounds.last_mut().ok_or(MjlogError::NotStarted)?;
+                    round.events.push(MjlogEvent::Riichi {
+                        seat: round.seat(tag.number("who")?)?,
+                        step: tag.number("step")?,
+                    });
+                }
+                "DORA" => {
+                    let round = log.rounds.last_mut().ok_or(MjlogError::NotStarted)?;
+                    round.events.push(MjlogEvent::Dora {
+                        id: tile_id(tag.number("hai")?)?,
+                    });
+                }
+                "AGARI" => {
+                    let round = log.rounds.last_mut().ok_or(MjlogError::NotStarted)?;
+                    let ten = numbers::<u32>(tag.require("ten")?)?;
+                    let pairs = |key| -> Result<Vec<(u32, u32)>, MjlogError> {
+                        let values = tag.get(key).map_or(Ok(vec![]), numbers::<u32>)?;
+                        Ok(values.chunks(2).map(|c| (c[0], c[1])).collect())
+                    };
+                    let melds = tag
+                        .get("m")
+                        .map_or(Ok(vec![]), numbers::<u32>)?
+                        .into_iter()
+                        .map(MeldCode::decode)
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    round.events.push(MjlogEvent::Agari(Agari {
+                        seat: round.seat(tag.number("who")?)?,
+                        from: round.seat(tag.number("fromWho")?)?,
+                        hand: tile_ids(tag.require("hai")?)?,
+                        melds,
+                        machi: tile_id(tag.number("machi")?)?,
+                        fu: ten.first().copied().unwrap_or(0),
+                        points: ten.get(1).copied().unwrap_or(0),
+                        yaku: pairs("yaku")?,
+                        yakuman: tag.get("yakuman").map_or(Ok(vec![]), numbers)?,
+                        dora_indicators: tag.get("doraHai").map_or(Ok(vec![]), tile_ids)?,
+                        ura_dora_indicators: tag.get("doraHaiUra").map_or(Ok(vec![]), tile_ids)?,
+                        deltas: deltas(tag.require("sc")?)?,
+                    }));
+
+                    if let Some(owari) = tag.get("owari") {
+                        log.final_scores = Some(final_scores(owari)?);
+                    }
+                }
+                "RYUUKYOKU" => {
+                    let round = log.rounds.last_mut().ok_or(MjlogError::NotStarted)?;
+                    let tenpai = ["hai0", "hai1", "hai2", "hai3"]
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, h)| tag.get(h).is_some())
+                        .map(|(seat, _)| seat)
+                        .collect();
+
+                    round.events.push(MjlogEvent::Ryuukyoku(Ryuukyoku {
+                        reason: tag.get("type").map(|t| t.to_string()),
+                        tenpai,
+                        deltas: deltas(tag.require("sc")?)?,
+                    }));
+
+                    if let Some(owari) = tag.get("owari") {
+                        log.final_scores = Some(final_scores(owari)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(log);
+    }
+}
+
+/// `T52` as `(0, 52)` for `letters = "TUVW"`.
+fn numbered(name: &str, letters: &str) -> Option<(usize, u32)> {
+    let mut chars = name.chars();
+    let seat = letters.find(chars.next()?)?;
+    let rest = chars.as_str();
+
+    if rest.is_empty() || !rest.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    return Some((seat, rest.parse().ok()?));
+}
+
+fn tile_id(id: u32) -> Result<u8, MjlogError> {
+    if id >= 136 {
+        return Err(MjlogError::InvalidTile(id));
+    }
+
+    return Ok(id as u8);
+}
+
+fn tile_ids(s: &str) -> Result<Vec<u8>, MjlogError> {
+    numbers::<u32>(s)?.into_iter().map(tile_id).collect()
+}
+
+fn numbers<T: core::str::FromStr>(s: &str) -> Result<Vec<T>, MjlogError> {
+    if s.is_empty() {
+        return Ok(vec![]);
+    }
+
+    s.split(',')
+        .map(|v| {
+            v.trim()
+                .parse()
+                .map_err(|_| MjlogError::InvalidNumber(v.to_string()))
+        })
+        .collect()
+}
+
+/// `sc` lists score and change in hundreds for every seat.
+fn deltas(sc: &str) -> Result<Vec<i32>, MjlogError> {
+    Ok(numbers::<i32>(sc)?
+        .chunks(2)
+        .map(|c| c.get(1).copied().unwrap_or(0) * 100)
+        .collect())
+}
+
+/// `owari` lists score in hundreds and final points for every seat.
+fn final_scores(owari: &str) -> Result<Vec<i32>, MjlogError> {
+    let values = owari
+        .split(',')
+        .step_by(2)
+        .map(|v| {
+            v.trim()
+                .parse::<i32>()
+                .map_err(|_| MjlogError::InvalidNumber(v.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    return Ok(values.iter().map(|s| s * 100).collect());
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = |b: u8| (b as char).to_digit(16);
+
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && let (Some(h), Some(l)) = (hex(bytes[i + 1]), hex(bytes[i + 2]))
+        {
+            out.push((h * 16 + l) as u8);
+            i += 3;
+            continue;
+        }
+
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    return String::from_utf8_lossy(&out).into_owned();
+}
+
+struct Tag<'a> {
+    name: &'a str,
+    attributes: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Tag<'a> {
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+    }
+
+    fn require(&self, key: &'static str) -> Result<&'a str, MjlogError> {
+        self.get(key).ok_or(MjlogError::MissingAttribute(key))
+    }
+
+    fn number<T: core::str::FromStr>(&self, key: &'static str) -> Result<T, MjlogError> {
+        let value = self.require(key)?;
+
+        value
+            .parse()
+            .map_err(|_| MjlogError::InvalidNumber(value.to_string()))
+    }
+}
+
+/// Opening and empty-element tags in document order. Closing tags,
+/// declarations and text are skipped; mjlog has no nesting worth keeping.
+fn tags(xml: &str) -> impl Iterator<Item = Result<Tag<'_>, MjlogError>> {
+    let mut offset = 0;
+
+    core::iter::from_fn(move || {
+        loop {
+            let start = offset + xml[offset..].find('<')?;
+            let Some(len) = xml[start..].find('>') else {
+                offset = xml.len();
+                return Some(Err(MjlogError::Syntax(start)));
+            };
+            let end = start + len;
+            offset = end + 1;
+
+            let body = xml[start + 1..end].trim_end_matches('/').trim();
+
+            if body.starts_with(['?', '!', '/']) || body.is_empty() {
+                continue;
+            }
+
+            return Some(parse_tag(body, start));
+        }
+    })
+}
+
+fn parse_tag(body: &str, at: usize) -> Result<Tag<'_>, MjlogError> {
+    let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+    let name = &body[..name_end];
+    let mut rest = body[name_end..].trim_start();
+    let mut attributes = vec![];
+
+    while !rest.is_empty() {
+        let eq = rest.find('=').ok_or(MjlogError::Syntax(at))?;
+        let key = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = value.chars().next().ok_or(MjlogError::Syntax(at))?;
+
+        if quote != '"' && quote != '\'' {
+            return Err(MjlogError::Syntax(at));
+        }
+
+        let close = value[1..].find(quote).ok_or(MjlogError::Syntax(at))?;
+        attributes.push((key, &value[1..1 + close]));
+        rest = value[close + 2..].trim_start();
+    }
+
+    return Ok(Tag { name, attributes });
+}
@@ -0,0 +1,202 @@
+//! Slow reference implementations that follow the definition of shanten
+//! directly: the fewest tiles missing from any complete hand of the right
+//! size, minus one. They exist to cross-check the table-driven versions in
+//! [`crate::shanten`].
+
+use crate::shanten::{Hand, Ukeire, YAOCHUU_TILES};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+pub fn shanten_standard(hand: &Hand) -> i8 {
+    let state = State {
+        counts: hand.counts(),
+        target: [0; 34],
+        mentsu: hand.count() / 3,
+        toitsu: 1,
+    };
+
+    return decompose(state, &mut BTreeMap::new()) as i8 - 1;
+}
+
+/// Tiles of the hand not yet placed, the groups (mentsu or pair) of the
+/// complete hand chosen so far, and the groups still to choose.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+struct State {
+    counts: [u8; 34],
+    target: [u8; 34],
+    mentsu: u8,
+    toitsu: u8,
+}
+
+/// The fewest tiles missing over every way to split the remaining tiles into
+/// blocks, each block part of one group of a complete hand, with the tiles
+/// in no block left unused. A block costs the tiles its group still lacks,
+/// and a group with no block costs all its tiles: with at most 14 tiles in
+/// the complete hand some tile kind is absent from it, so such a group
+/// always fits. A complete hand has at most four copies of a tile.
+///
+/// `memo` only saves recomputing a state reached by several splits.
+fn decompose(mut state: State, memo: &mut BTreeMap<State, u8>) -> u8 {
+    let Some(i) = (0..34).find(|&t| state.counts[t] > 0) else {
+        return 3 * state.mentsu + 2 * state.toitsu;
+    };
+
+    // Groups chosen from here on start at `i - 2` or later, so the copies
+    // below that no longer matter.
+    state.target[..i.saturating_sub(2)].fill(0);
+
+    if let Some(&cost) = memo.get(&state) {
+        return cost;
+    }
+
+    // Leave one copy of the lowest tile unused.
+    let mut unused = state;
+    unused.counts[i] -= 1;
+    let mut best = decompose(unused, memo);
+
+    // Or use it in a block of some group containing it.
+    let mut groups = vec![];
+
+    if state.mentsu > 0 {
+        groups.push(vec![i, i, i]);
+
+        if i < 27 {
+            for start in i.saturating_sub(2)..=i {
+                if start / 9 == i / 9 && start % 9 <= 6 {
+                    groups.push(vec![start, start + 1, start + 2]);
+                }
+            }
+        }
+    }
+
+    if state.toitsu > 0 {
+        groups.push(vec![i, i]);
+    }
+
+    for group in groups {
+        let mut next = state;
+
+        if group.len() == 2 {
+            next.toitsu -= 1;
+        } else {
+            next.mentsu -= 1;
+        }
+
+        for &t in group.iter() {
+            next.target[t] += 1;
+        }
+
+        if group.iter().any(|&t| next.target[t] > 4) {
+            continue;
+        }
+
+        // Each subset of the group's tiles that contains `i` and is in hand.
+        for mask in 1..1u8 << group.len() {
+            let used = (0..group.len())
+                .filter(|&k| mask >> k & 1 == 1)
+                .map(|k| group[k])
+                .collect::<Vec<_>>();
+            if !used.contains(&i) {
+                continue;
+            }
+
+            let mut placed = next;
+
+            if used
+                .iter()
+                .any(|&t| placed.counts[t] < used.iter().filter(|&&u| u == t).count() as u8)
+            {
+                continue;
+            }
+
+            for &t in used.iter() {
+                placed.counts[t] -= 1;
+            }
+
+            let cost = (group.len() - used.len()) as u8;
+            best = best.min(cost + decompose(placed, memo));
+        }
+    }
+
+    memo.insert(state, best);
+
+    return best;
+}
+
+pub fn shanten_chiitoitsu(hand: &Hand) -> i8 {
+    let mut missing = hand
+        .counts()
+        .iter()
+        .map(|&c| 2 - c.min(2))
+        .collect::<Vec<_>>();
+    missing.sort();
+
+    return missing[..7].iter().sum::<u8>() as i8 - 1;
+}
+
+pub fn shanten_kokushimusou(hand: &Hand) -> i8 {
+    let counts = hand.counts();
+
+    let missing_tiles = YAOCHUU_TILES.iter().filter(|&&t| counts[t] == 0).count();
+    let missing_toitsu = if YAOCHUU_TILES.iter().any(|&t| counts[t] >= 2) {
+        0
+    } else {
+        1
+    };
+
+    return (missing_tiles + missing_toitsu) as i8 - 1;
+}
+
+pub fn shanten(hand: &Hand) -> i8 {
+    if hand.count() < 13 {
+        return shanten_standard(hand);
+    }
+
+    shanten_standard(hand)
+        .min(shanten_chiitoitsu(hand))
+        .min(shanten_kokushimusou(hand))
+}
+
+/// The ukeire of a 3n+1 hand by drawing each of the 34 tiles in turn and
+/// each unseen physical tile of the 136, with [`shanten`] as the judge.
+/// `visible` is as in [`Hand::ukeire`].
+pub fn ukeire(hand: &Hand, visible: &[u8; 34]) -> Ukeire {
+    let shanten = self::shanten(hand);
+
+    if hand.count() % 3 != 1 {
+        return Ukeire {
+            shanten,
+            tiles: vec![],
+            count: 0,
+        };
+    }
+
+    let lowers = (0..34)
+        .map(|t| {
+            // There is no fifth copy to draw.
+            if hand[t] >= 4 {
+                return false;
+            }
+
+            let mut drawn = hand.clone();
+            drawn[t] += 1;
+
+            self::shanten(&drawn) < shanten
+        })
+        .collect::<Vec<_>>();
+
+    let mut unseen = vec![];
+
+    for t in 0..34 {
+        for copy in 0..4 {
+            if copy >= hand[t] + visible[t] {
+                unseen.push(t);
+            }
+        }
+    }
+
+    return Ukeire {
+        shanten,
+        tiles: (0..34).filter(|&t| lowers[t]).collect(),
+        count: unseen.iter().filter(|&&t| lowers[t]).count() as u32,
+    };
+}
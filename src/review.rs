@@ -0,0 +1,283 @@
+//! Discard review: for every discard of a replayed round, the shanten and
+//! ukeire after the chosen tile against the best tile the hand had.
+//!
+//! Ukeire counts the copies not visible to the player: discards still in the
+//! ponds, melds, dora indicators, the player's own hand and the tile being
+//! discarded. Discards made
+//! after riichi are forced and left out.
+
+use crate::meld::MeldKind;
+use crate::mjlog::Mjlog;
+use crate::round::{Event, Round};
+use crate::shanten::Hand;
+use crate::tile::Tile;
+use alloc::{string::ToString, vec, vec::Vec};
+use core::fmt;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiscardReview {
+    /// Index of the round in the reviewed game.
+    pub round: usize,
+    pub seat: usize,
+    /// Concealed tiles before the discard.
+    pub hand: Vec<Tile>,
+    pub discard: Tile,
+    /// Shanten and ukeire after the chosen discard.
+    pub shanten: i8,
+    pub ukeire: u32,
+    /// Tile kinds whose discard gives the lowest shanten and, among those,
+    /// the most ukeire.
+    pub best: Vec<usize>,
+    pub best_shanten: i8,
+    pub best_ukeire: u32,
+}
+
+impl DiscardReview {
+    pub fn lost_shanten(&self) -> bool {
+        self.shanten > self.best_shanten
+    }
+
+    /// Kept the shanten but took fewer ukeire than the best discard.
+    pub fn lost_efficiency(&self) -> bool {
+        !self.lost_shanten() && self.ukeire < self.best_ukeire
+    }
+}
+
+/// Counts of one player's reviewed discards.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReviewSummary {
+    pub discards: usize,
+    pub lost_efficiency: usize,
+    pub lost_shanten: usize,
+    /// Ukeire given up by the discards that lost efficiency.
+    pub ukeire_lost: u32,
+}
+
+impl fmt::Display for ReviewSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} discards lost efficiency, {} lost shanten",
+            self.lost_efficiency, self.lost_shanten
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReviewReport {
+    pub discards: Vec<DiscardReview>,
+}
+
+impl ReviewReport {
+    pub fn from_rounds(rounds: &[Round]) -> Self {
+        let mut report = Self::default();
+
+        for (i, round) in rounds.iter().enumerate() {
+            report.add_round(
+                i,
+                round.initial_hands(),
+                round.wall().dora_indicators()[0],
+                round.events(),
+            );
+        }
+
+        return report;
+    }
+
+    pub fn from_mjlog(log: &Mjlog) -> Self {
+        let mut report = Self::default();
+
+        for (i, round) in log.rounds.iter().enumerate() {
+            let dora = crate::mjlog::tile(round.dora_indicator, round.aka);
+            report.add_round(i, &round.initial_hands(), dora, &round.round_events());
+        }
+
+        return report;
+    }
+
+    /// Replays a round from its starting hands, first dora indicator and
+    /// events, reviewing every discard.
+    pub fn add_round(&mut self, round: usize, hands: &[Vec<Tile>], dora: Tile, events: &[Event]) {
+        let n = hands.len();
+        let mut concealed = hands.to_vec();
+        let mut riichi = vec![false; n];
+        let mut visible = [0u8; 34];
+        let mut last_discard: Option<Tile> = None;
+
+        visible[dora.kind()] += 1;
+
+        for event in events {
+            match event {
+                Event::Draw { seat, tile, .. } => concealed[*seat].push(*tile),
+                Event::Discard {
+                    seat,
+                    tile,
+                    riichi: declared,
+                    ..
+                } => {
+                    if !riichi[*seat] {
+                        self.discards.push(review(
+                            round,
+                            *seat,
+                            &concealed[*seat],
+                            *tile,
+                            &visible,
+                        ));
+                    }
+
+                    remove(&mut concealed[*seat], *tile);
+                    visible[tile.kind()] += 1;
+                    riichi[*seat] |= *declared;
+                    last_discard = Some(*tile);
+                }
+                Event::Meld { seat, meld } => {
+                    let mut own = meld.tiles.clone();
+
+                    match meld.kind {
+                        MeldKind::Chi | MeldKind::Pon | MeldKind::Daiminkan => {
+                            // The called discard leaves the pond for the meld.
+                            if let Some(called) = last_discard.take() {
+                                visible[called.kind()] -= 1;
+                                remove(&mut own, called);
+                            }
+                        }
+                        MeldKind::Kakan => {
+                            // Only the added tile is new.
+                            let kind = meld.base();
+                            visible[kind] -= 3;
+                            own = concealed[*seat]
+                                .iter()
+                                .copied()
+                                .filter(|t| t.kind() == kind)
+                                .take(1)
+                                .collect();
+                        }
+                        MeldKind::Ankan => {}
+                    }
+
+                    for t in own.iter() {
+                        remove(&mut concealed[*seat], *t);
+                    }
+
+                    for t in meld.tiles.iter() {
+                        visible[t.kind()] += 1;
+                    }
+                }
+                Event::Dora { indicator } => visible[indicator.kind()] += 1,
+                Event::RiichiAccepted { .. } => {}
+            }
+        }
+    }
+
+    /// One summary per seat.
+    pub fn summaries(&self) -> Vec<ReviewSummary> {
+        let n = self.discards.iter().map(|d| d.seat + 1).max().unwrap_or(0);
+        let mut summaries = vec![ReviewSummary::default(); n];
+
+        for d in self.discards.iter() {
+            let s = &mut summaries[d.seat];
+            s.discards += 1;
+
+            if d.lost_shanten() {
+                s.lost_shanten += 1;
+            } else if d.lost_efficiency() {
+                s.lost_efficiency += 1;
+                s.ukeire_lost += d.best_ukeire - d.ukeire;
+            }
+        }
+
+        return summaries;
+    }
+}
+
+impl fmt::Display for ReviewReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "round\tseat\thand\tdiscard\tshanten\tukeire\tbest\tbest_shanten\tbest_ukeire"
+        )?;
+
+        for d in self.discards.iter() {
+            let best = d
+                .best
+                .iter()
+                .map(|&k| Tile::new(k).to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                d.round,
+                d.seat,
+                crate::tile::tiles_to_string(&d.hand),
+                d.discard,
+                d.shanten,
+                d.ukeire,
+                best,
+                d.best_shanten,
+                d.best_ukeire
+            )?;
+        }
+
+        return Ok(());
+    }
+}
+
+fn remove(tiles: &mut Vec<Tile>, tile: Tile) {
+    if let Some(i) = tiles.iter().position(|&t| t == tile) {
+        tiles.remove(i);
+    }
+}
+
+fn review(
+    round: usize,
+    seat: usize,
+    concealed: &[Tile],
+    discard: Tile,
+    visible: &[u8; 34],
+) -> DiscardReview {
+    let hand: Hand = concealed.iter().copied().collect();
+    let mut kinds = concealed.iter().map(|t| t.kind()).collect::<Vec<_>>();
+    kinds.sort();
+    kinds.dedup();
+
+    let evaluate = |kind: usize| {
+        let mut after = hand.clone();
+        after[kind] -= 1;
+        let mut visible = *visible;
+        visible[kind] += 1;
+        let ukeire = after.ukeire(&visible);
+
+        (ukeire.shanten, ukeire.count)
+    };
+    let results = kinds.iter().map(|&k| (k, evaluate(k))).collect::<Vec<_>>();
+    let (best_shanten, best_ukeire) = results
+        .iter()
+        .map(|&(_, r)| r)
+        .min_by_key(|&(shanten, ukeire)| (shanten, core::cmp::Reverse(ukeire)))
+        .unwrap();
+    let (shanten, ukeire) = evaluate(discard.kind());
+
+    let mut sorted = concealed.to_vec();
+    sorted.sort();
+
+    return DiscardReview {
+        round,
+        seat,
+        hand: sorted,
+        discard,
+        shanten,
+        ukeire,
+        best: results
+            .iter()
+            .filter(|&&(_, r)| r == (best_shanten, best_ukeire))
+            .map(|&(k, _)| k)
+            .collect(),
+        best_shanten,
+        best_ukeire,
+    };
+}
@@ -0,0 +1,1043 @@
+//! A single round (kyoku) of riichi mahjong as a state machine. Every action is
+//! validated against the current phase; illegal ones are rejected with a
+//! [`RoundError`] and leave the state untouched.
+
+use crate::furiten::{self, Furiten};
+use crate::legal::{self, Prompt, Situation};
+use crate::meld::{Meld, MeldKind};
+use crate::shanten::{Hand, TenpaiRule};
+use crate::tile::Tile;
+use crate::wall::{MAX_KAN_COUNT, Wall};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rules {
+    pub tenpai_rule: TenpaiRule,
+    /// Kyuushu kyuuhai, suufon renda, suucha riichi and suukan sanra.
+    pub abortive_draws: bool,
+    /// Three players declaring ron on one discard aborts the round.
+    pub triple_ron_draw: bool,
+    pub nagashi_mangan: bool,
+    /// Forbids discarding, right after a call, the called tile or the tile on
+    /// the other end of the chi (kuikae).
+    pub forbid_kuikae: bool,
+    /// Allows robbing an ankan for kokushimusou.
+    pub kokushi_chankan_on_ankan: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            tenpai_rule: TenpaiRule::DenyJunKaraten,
+            abortive_draws: true,
+            triple_ron_draw: true,
+            nagashi_mangan: true,
+            forbid_kuikae: true,
+            kokushi_chankan_on_ankan: true,
+        }
+    }
+}
+
+/// Where the round sits in a match.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundSetup {
+    /// Tile kind of the round wind, 27 (east) to 30 (north).
+    pub round_wind: usize,
+    pub dealer: usize,
+    pub honba: u32,
+    pub riichi_sticks: u32,
+    pub scores: Vec<i32>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Discard {
+    pub tile: Tile,
+    pub tsumogiri: bool,
+    pub riichi: bool,
+    /// Taken by another player's chi, pon or kan.
+    pub called: bool,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Player {
+    /// Concealed tiles, including the drawn tile during the player's turn.
+    pub concealed: Vec<Tile>,
+    pub drawn: Option<Tile>,
+    pub melds: Vec<Meld>,
+    pub discards: Vec<Discard>,
+    /// Index into `discards` of the accepted riichi declaration.
+    pub riichi: Option<usize>,
+    pub double_riichi: bool,
+    pub ippatsu: bool,
+    /// Tile kinds that may not be discarded right after a call.
+    pub kuikae: Vec<usize>,
+    /// Tiles other players let go since the player's last discard, or since
+    /// riichi.
+    pub passed: Vec<Tile>,
+}
+
+impl Player {
+    pub fn hand(&self) -> Hand {
+        self.concealed.iter().copied().collect()
+    }
+
+    pub fn is_riichi(&self) -> bool {
+        self.riichi.is_some()
+    }
+
+    /// No melds other than ankan.
+    pub fn is_closed(&self) -> bool {
+        self.melds.iter().all(|m| !m.is_open())
+    }
+
+    /// Why the player may not ron now, if they are furiten. Only meaningful
+    /// outside the player's own turn.
+    pub fn furiten(&self) -> Option<Furiten> {
+        let discards = self.discards.iter().map(|d| d.tile).collect::<Vec<_>>();
+
+        furiten::furiten(&self.hand(), &discards, &self.passed, self.is_riichi())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Discard(Tile),
+    /// Declares riichi and discards the tile.
+    Riichi(Tile),
+    Tsumo,
+    Ron,
+    /// Calls the discard with these two tiles from the hand.
+    Chi([Tile; 2]),
+    Pon([Tile; 2]),
+    Daiminkan,
+    /// Declares a closed kan of the tile kind.
+    Ankan(usize),
+    /// Adds the tile to a pon.
+    Kakan(Tile),
+    KyuushuKyuuhai,
+    Pass,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundError {
+    RoundOver,
+    NotYourTurn,
+    AlreadyResponded,
+    /// The action does not exist in the current phase.
+    InvalidAction,
+    TileNotInHand,
+    /// After riichi only the drawn tile may be discarded.
+    RiichiLocked,
+    Kuikae,
+    AlreadyRiichi,
+    OpenHand,
+    NotEnoughPoints,
+    NotEnoughTiles,
+    NotTenpai,
+    NotAgari,
+    /// The hand is complete but has no yaku.
+    NoYaku,
+    /// A wait was discarded or let go; see [`crate::furiten`].
+    Furiten,
+    InvalidMeld,
+    TooManyKans,
+    /// An ankan after riichi would change the waits.
+    WaitChanged,
+    NotKyuushuKyuuhai,
+}
+
+impl fmt::Display for RoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            RoundError::RoundOver => "the round is over",
+            RoundError::NotYourTurn => "the player is not expected to act",
+            RoundError::AlreadyResponded => "the player has already responded",
+            RoundError::InvalidAction => "the action is not possible now",
+            RoundError::TileNotInHand => "the tile is not in the hand",
+            RoundError::RiichiLocked => "only the drawn tile can be discarded after riichi",
+            RoundError::Kuikae => "the tile cannot be discarded right after the call",
+            RoundError::AlreadyRiichi => "riichi has already been declared",
+            RoundError::OpenHand => "riichi needs a closed hand",
+            RoundError::NotEnoughPoints => "riichi needs 1000 points",
+            RoundError::NotEnoughTiles => "not enough tiles are left in the wall",
+            RoundError::NotTenpai => "the hand would not be tenpai",
+            RoundError::NotAgari => "the hand is not complete",
+            RoundError::NoYaku => "the hand has no yaku",
+            RoundError::Furiten => "the hand is furiten",
+            RoundError::InvalidMeld => "the tiles do not form the meld",
+            RoundError::TooManyKans => "no more kans can be declared",
+            RoundError::WaitChanged => "the kan would change the waits",
+            RoundError::NotKyuushuKyuuhai => "kyuushu kyuuhai is not possible",
+        };
+
+        write!(f, "{}", message)
+    }
+}
+
+impl core::error::Error for RoundError {}
+
+/// Everything about a winning hand needed to score it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Win {
+    pub seat: usize,
+    /// The discarder, or `None` for tsumo.
+    pub from: Option<usize>,
+    pub tile: Tile,
+    /// Concealed tiles including the winning tile.
+    pub concealed: Vec<Tile>,
+    pub melds: Vec<Meld>,
+    pub round_wind: usize,
+    pub seat_wind: usize,
+    pub riichi: bool,
+    pub double_riichi: bool,
+    pub ippatsu: bool,
+    pub haitei: bool,
+    pub houtei: bool,
+    pub rinshan: bool,
+    pub chankan: bool,
+    pub tenhou: bool,
+    pub chiihou: bool,
+    /// Dora kinds, one per revealed indicator.
+    pub dora: Vec<usize>,
+    /// Ura dora kinds; empty unless the winner is in riichi.
+    pub ura_dora: Vec<usize>,
+}
+
+impl Win {
+    pub fn is_tsumo(&self) -> bool {
+        self.from.is_none()
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AbortiveDraw {
+    KyuushuKyuuhai { seat: usize },
+    SuufonRenda,
+    SuuchaRiichi,
+    SuukanSanra,
+    Sanchahou,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundEnd {
+    Tsumo(Win),
+    /// One entry per winner, in turn order from the discarder.
+    Ron(Vec<Win>),
+    ExhaustiveDraw {
+        tenpai: Vec<bool>,
+        nagashi_mangan: Vec<usize>,
+    },
+    Abortive(AbortiveDraw),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    /// `seat` holds 3n+2 tiles and must discard, win, kan or abort.
+    Turn {
+        seat: usize,
+        /// The turn started with a chi or pon rather than a draw.
+        after_call: bool,
+        rinshan: bool,
+    },
+    /// The other players may call or ron the discard. `None` marks players
+    /// who still have to respond; players without any option are passed
+    /// automatically.
+    Calls {
+        discarder: usize,
+        tile: Tile,
+        responses: Vec<Option<Action>>,
+    },
+    /// The other players may rob the kan.
+    Chankan {
+        seat: usize,
+        tile: Tile,
+        responses: Vec<Option<Action>>,
+    },
+    Ended(RoundEnd),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    Draw {
+        seat: usize,
+        tile: Tile,
+        rinshan: bool,
+    },
+    Discard {
+        seat: usize,
+        tile: Tile,
+        tsumogiri: bool,
+        riichi: bool,
+    },
+    RiichiAccepted {
+        seat: usize,
+    },
+    Meld {
+        seat: usize,
+        meld: Meld,
+    },
+    Dora {
+        indicator: Tile,
+    },
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Round {
+    rules: Rules,
+    wall: Wall,
+    round_wind: usize,
+    dealer: usize,
+    honba: u32,
+    riichi_sticks: u32,
+    scores: Vec<i32>,
+    initial_hands: Vec<Vec<Tile>>,
+    players: Vec<Player>,
+    phase: Phase,
+    events: Vec<Event>,
+    /// No call or kan has happened yet, so everyone is still in their first
+    /// go-around.
+    uninterrupted: bool,
+    pending_dora: usize,
+    kan_seats: Vec<usize>,
+}
+
+impl Round {
+    /// Deals from `wall` and lets the dealer draw.
+    pub fn new(rules: Rules, mut wall: Wall, setup: RoundSetup) -> Self {
+        let mut initial_hands = wall.deal_tiles();
+        assert_eq!(setup.scores.len(), initial_hands.len());
+
+        // The wall deals the dealer first; store hands by seat.
+        initial_hands.rotate_right(setup.dealer);
+
+        let players = initial_hands
+            .iter()
+            .map(|tiles| Player {
+                concealed: tiles.clone(),
+                ..Player::default()
+            })
+            .collect();
+
+        let mut round = Self {
+            rules,
+            wall,
+            round_wind: setup.round_wind,
+            dealer: setup.dealer,
+            honba: setup.honba,
+            riichi_sticks: setup.riichi_sticks,
+            scores: setup.scores,
+            initial_hands,
+            players,
+            phase: Phase::Turn {
+                seat: setup.dealer,
+                after_call: false,
+                rinshan: false,
+            },
+            events: vec![],
+            uninterrupted: true,
+            pending_dora: 0,
+            kan_seats: vec![],
+        };
+
+        round.draw(setup.dealer, false);
+
+        return round;
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// The wall, including tiles no player can see yet.
+    pub fn wall(&self) -> &Wall {
+        &self.wall
+    }
+
+    pub fn round_wind(&self) -> usize {
+        self.round_wind
+    }
+
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    pub fn honba(&self) -> u32 {
+        self.honba
+    }
+
+    /// Riichi sticks on the table, including those carried over.
+    pub fn riichi_sticks(&self) -> u32 {
+        self.riichi_sticks
+    }
+
+    /// Scores after riichi deposits made so far in this round.
+    pub fn scores(&self) -> &[i32] {
+        &self.scores
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn player(&self, seat: usize) -> &Player {
+        &self.players[seat]
+    }
+
+    pub fn seat_wind(&self, seat: usize) -> usize {
+        27 + (seat + self.players.len() - self.dealer) % self.players.len()
+    }
+
+    pub fn initial_hands(&self) -> &[Vec<Tile>] {
+        &self.initial_hands
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn result(&self) -> Option<&RoundEnd> {
+        match &self.phase {
+            Phase::Ended(end) => Some(end),
+            _ => None,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.result().is_some()
+    }
+
+    /// Whether no call or kan has happened yet.
+    pub fn is_uninterrupted(&self) -> bool {
+        self.uninterrupted
+    }
+
+    /// Seats the round is waiting on.
+    pub fn waiting_for(&self) -> Vec<usize> {
+        match &self.phase {
+            Phase::Turn { seat, .. } => vec![*seat],
+            Phase::Calls { responses, .. } | Phase::Chankan { responses, .. } => (0..responses
+                .len())
+                .filter(|&s| responses[s].is_none())
+                .collect(),
+            Phase::Ended(_) => vec![],
+        }
+    }
+
+    /// The table as seen by `seat`, for [`legal::legal_actions`].
+    pub fn situation(&self, seat: usize) -> Situation {
+        Situation {
+            rules: self.rules,
+            seat,
+            players: self.players.len(),
+            round_wind: self.round_wind,
+            seat_wind: self.seat_wind(seat),
+            score: self.scores[seat],
+            remaining: self.wall.remaining(),
+            kans: self.wall.kan_count(),
+            uninterrupted: self.uninterrupted,
+        }
+    }
+
+    /// What `seat` has to decide on, if the round is waiting on the seat.
+    pub fn prompt(&self, seat: usize) -> Option<Prompt> {
+        match &self.phase {
+            Phase::Turn {
+                seat: s,
+                after_call,
+                rinshan,
+            } => (*s == seat).then_some(Prompt::Turn {
+                after_call: *after_call,
+                rinshan: *rinshan,
+            }),
+            Phase::Calls {
+                discarder,
+                tile,
+                responses,
+            } => responses.get(seat)?.is_none().then_some(Prompt::Discard {
+                tile: *tile,
+                from: *discarder,
+            }),
+            Phase::Chankan {
+                seat: s,
+                tile,
+                responses,
+            } => responses.get(seat)?.is_none().then_some(Prompt::Kan {
+                tile: *tile,
+                from: *s,
+                ankan: self.players[*s].melds.last().unwrap().kind == MeldKind::Ankan,
+            }),
+            Phase::Ended(_) => None,
+        }
+    }
+
+    /// Every action `seat` may take now; empty if the round is not waiting on
+    /// the seat.
+    pub fn legal_actions(&self, seat: usize) -> Vec<Action> {
+        let Some(prompt) = self.prompt(seat) else {
+            return vec![];
+        };
+
+        legal::legal_actions(&self.players[seat], &self.situation(seat), prompt)
+    }
+
+    /// Copies of each tile kind visible to everyone: discards still in the
+    /// ponds, melds and dora indicators.
+    pub fn visible_counts(&self) -> [u8; 34] {
+        let mut counts = [0; 34];
+
+        for player in self.players.iter() {
+            for discard in player.discards.iter().filter(|d| !d.called) {
+                counts[discard.tile.kind()] += 1;
+            }
+
+            for meld in player.melds.iter() {
+                for tile in meld.tiles.iter() {
+                    counts[tile.kind()] += 1;
+                }
+            }
+        }
+
+        for tile in self.wall.dora_indicators() {
+            counts[tile.kind()] += 1;
+        }
+
+        return counts;
+    }
+
+    pub fn apply(&mut self, seat: usize, action: Action) -> Result<(), RoundError> {
+        if seat >= self.players.len() {
+            return Err(RoundError::NotYourTurn);
+        }
+
+        match &self.phase {
+            Phase::Ended(_) => Err(RoundError::RoundOver),
+            Phase::Turn { seat: s, .. } => {
+                if *s != seat {
+                    return Err(RoundError::NotYourTurn);
+                }
+
+                self.apply_turn(seat, action)
+            }
+            Phase::Calls { .. } | Phase::Chankan { .. } => self.apply_response(seat, action),
+        }
+    }
+
+    fn next_seat(&self, seat: usize) -> usize {
+        (seat + 1) % self.players.len()
+    }
+
+    fn draw(&mut self, seat: usize, rinshan: bool) {
+        let tile = if rinshan {
+            self.wall.draw_rinshan()
+        } else {
+            self.wall.draw()
+        }
+        .unwrap();
+
+        let player = &mut self.players[seat];
+        player.concealed.push(tile);
+        player.drawn = Some(tile);
+
+        self.events.push(Event::Draw {
+            seat,
+            tile,
+            rinshan,
+        });
+        self.phase = Phase::Turn {
+            seat,
+            after_call: false,
+            rinshan,
+        };
+    }
+
+    fn reveal_pending_dora(&mut self) {
+        while self.pending_dora > 0 {
+            self.pending_dora -= 1;
+
+            if let Some(indicator) = self.wall.reveal_dora() {
+                self.events.push(Event::Dora { indicator });
+            }
+        }
+    }
+
+    fn apply_turn(&mut self, seat: usize, action: Action) -> Result<(), RoundError> {
+        let Phase::Turn {
+            after_call,
+            rinshan,
+            ..
+        } = self.phase
+        else {
+            unreachable!();
+        };
+
+        let player = &self.players[seat];
+        let situation = self.situation(seat);
+        legal::check(player, &situation, self.prompt(seat).unwrap(), action)?;
+
+        match action {
+            Action::Discard(tile) => self.discard(seat, tile, false),
+            Action::Riichi(tile) => self.discard(seat, tile, true),
+            Action::Tsumo => {
+                let win = legal::check_tsumo(player, &situation, after_call, rinshan)?;
+                self.phase = Phase::Ended(RoundEnd::Tsumo(self.with_dora(win)));
+            }
+            Action::Ankan(kind) => self.ankan(seat, kind),
+            Action::Kakan(tile) => self.kakan(seat, tile),
+            Action::KyuushuKyuuhai => {
+                self.phase =
+                    Phase::Ended(RoundEnd::Abortive(AbortiveDraw::KyuushuKyuuhai { seat }));
+            }
+            _ => unreachable!(),
+        }
+
+        return Ok(());
+    }
+
+    fn discard(&mut self, seat: usize, tile: Tile, riichi: bool) {
+        let player = &mut self.players[seat];
+
+        remove_tiles(&mut player.concealed, &[tile]);
+
+        let tsumogiri = player.drawn == Some(tile);
+        player.drawn = None;
+        player.kuikae.clear();
+        if !player.is_riichi() {
+            player.passed.clear();
+        }
+        player.ippatsu = false;
+        player.discards.push(Discard {
+            tile,
+            tsumogiri,
+            riichi,
+            called: false,
+        });
+
+        self.events.push(Event::Discard {
+            seat,
+            tile,
+            tsumogiri,
+            riichi,
+        });
+        self.reveal_pending_dora();
+
+        let responses = (0..self.players.len())
+            .map(|s| {
+                if s != seat && !self.discard_options(s, seat, tile).is_empty() {
+                    None
+                } else {
+                    Some(Action::Pass)
+                }
+            })
+            .collect();
+
+        self.phase = Phase::Calls {
+            discarder: seat,
+            tile,
+            responses,
+        };
+        self.resolve_responses();
+    }
+
+    /// Every non-pass response `seat` could make to `tile` discarded by
+    /// `discarder`.
+    pub fn discard_options(&self, seat: usize, discarder: usize, tile: Tile) -> Vec<Action> {
+        let prompt = Prompt::Discard {
+            tile,
+            from: discarder,
+        };
+
+        let mut options = legal::legal_actions(&self.players[seat], &self.situation(seat), prompt);
+        options.retain(|&a| a != Action::Pass);
+
+        return options;
+    }
+
+    /// Whether `seat` could rob the kan of `tile` declared by `from`.
+    fn can_chankan(&self, seat: usize, tile: Tile, from: usize, ankan: bool) -> bool {
+        let prompt = Prompt::Kan { tile, from, ankan };
+
+        seat != from
+            && legal::check(
+                &self.players[seat],
+                &self.situation(seat),
+                prompt,
+                Action::Ron,
+            )
+            .is_ok()
+    }
+
+    fn interrupt(&mut self) {
+        self.uninterrupted = false;
+
+        for player in self.players.iter_mut() {
+            player.ippatsu = false;
+        }
+    }
+
+    fn ankan(&mut self, seat: usize, kind: usize) {
+        let player = &mut self.players[seat];
+        let mut tiles = player
+            .concealed
+            .iter()
+            .copied()
+            .filter(|t| t.kind() == kind)
+            .collect::<Vec<_>>();
+        tiles.sort();
+
+        remove_tiles(&mut player.concealed, &tiles);
+        player.drawn = None;
+
+        let meld = Meld {
+            kind: MeldKind::Ankan,
+            tiles,
+            called: None,
+            from: 0,
+        };
+
+        player.melds.push(meld.clone());
+        self.events.push(Event::Meld { seat, meld });
+        self.interrupt();
+        self.kan_seats.push(seat);
+        self.pending_dora += 1;
+        self.reveal_pending_dora();
+
+        let tile = Tile::new(kind);
+        let responses = (0..self.players.len())
+            .map(|s| {
+                if self.can_chankan(s, tile, seat, true) {
+                    None
+                } else {
+                    Some(Action::Pass)
+                }
+            })
+            .collect();
+
+        self.phase = Phase::Chankan {
+            seat,
+            tile,
+            responses,
+        };
+        self.resolve_responses();
+    }
+
+    fn kakan(&mut self, seat: usize, tile: Tile) {
+        let player = &mut self.players[seat];
+
+        remove_tiles(&mut player.concealed, &[tile]);
+        player.drawn = None;
+
+        let meld = player
+            .melds
+            .iter_mut()
+            .find(|m| m.kind == MeldKind::Pon && m.base() == tile.kind())
+            .unwrap();
+        meld.kind = MeldKind::Kakan;
+        meld.tiles.push(tile);
+        meld.tiles.sort();
+
+        let meld = meld.clone();
+        self.events.push(Event::Meld { seat, meld });
+        self.interrupt();
+        self.kan_seats.push(seat);
+        self.pending_dora += 1;
+
+        let responses = (0..self.players.len())
+            .map(|s| {
+                if self.can_chankan(s, tile, seat, false) {
+                    None
+                } else {
+                    Some(Action::Pass)
+                }
+            })
+            .collect();
+
+        self.phase = Phase::Chankan {
+            seat,
+            tile,
+            responses,
+        };
+        self.resolve_responses();
+    }
+
+    fn apply_response(&mut self, seat: usize, action: Action) -> Result<(), RoundError> {
+        let Some(prompt) = self.prompt(seat) else {
+            return Err(RoundError::AlreadyResponded);
+        };
+
+        legal::check(&self.players[seat], &self.situation(seat), prompt, action)?;
+
+        match &mut self.phase {
+            Phase::Calls { responses, .. } | Phase::Chankan { responses, .. } => {
+                responses[seat] = Some(action);
+            }
+            _ => unreachable!(),
+        }
+
+        self.resolve_responses();
+
+        return Ok(());
+    }
+
+    fn resolve_responses(&mut self) {
+        let (actor, tile, responses, chankan) = match &self.phase {
+            Phase::Calls {
+                discarder,
+                tile,
+                responses,
+            } => (*discarder, *tile, responses.clone(), false),
+            Phase::Chankan {
+                seat,
+                tile,
+                responses,
+            } => (*seat, *tile, responses.clone(), true),
+            _ => unreachable!(),
+        };
+
+        if responses.iter().any(|r| r.is_none()) {
+            return;
+        }
+
+        let n = self.players.len();
+        let order = (1..n).map(|i| (actor + i) % n).collect::<Vec<_>>();
+
+        let rons = order
+            .iter()
+            .copied()
+            .filter(|&s| responses[s] == Some(Action::Ron))
+            .collect::<Vec<_>>();
+
+        if !rons.is_empty() {
+            if rons.len() == 3 && self.rules.triple_ron_draw {
+                self.phase = Phase::Ended(RoundEnd::Abortive(AbortiveDraw::Sanchahou));
+                return;
+            }
+
+            let wins = rons
+                .iter()
+                .map(|&s| {
+                    let player = &self.players[s];
+                    let win = legal::check_ron(player, &self.situation(s), tile, actor, chankan);
+
+                    self.with_dora(win.unwrap())
+                })
+                .collect();
+
+            self.phase = Phase::Ended(RoundEnd::Ron(wins));
+            return;
+        }
+
+        for &s in order.iter() {
+            self.players[s].passed.push(tile);
+        }
+
+        if chankan {
+            self.draw(actor, true);
+            return;
+        }
+
+        if self.players[actor].discards.last().unwrap().riichi {
+            self.accept_riichi(actor);
+
+            if self.rules.abortive_draws && n == 4 && self.players.iter().all(|p| p.is_riichi()) {
+                self.phase = Phase::Ended(RoundEnd::Abortive(AbortiveDraw::SuuchaRiichi));
+                return;
+            }
+        }
+
+        for &s in order.iter() {
+            match responses[s] {
+                Some(Action::Pon(tiles)) => {
+                    self.call(s, actor, tile, &tiles, MeldKind::Pon);
+                    return;
+                }
+                Some(Action::Daiminkan) => {
+                    let tiles = self.players[s]
+                        .concealed
+                        .iter()
+                        .copied()
+                        .filter(|t| t.kind() == tile.kind())
+                        .collect::<Vec<_>>();
+                    self.call(s, actor, tile, &tiles, MeldKind::Daiminkan);
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        for &s in order.iter() {
+            if let Some(Action::Chi(tiles)) = responses[s] {
+                self.call(s, actor, tile, &tiles, MeldKind::Chi);
+                return;
+            }
+        }
+
+        self.discard_passed(actor);
+    }
+
+    fn accept_riichi(&mut self, seat: usize) {
+        let uninterrupted = self.uninterrupted;
+        let player = &mut self.players[seat];
+
+        player.riichi = Some(player.discards.len() - 1);
+        player.double_riichi = uninterrupted && player.discards.len() == 1;
+        player.ippatsu = true;
+
+        self.scores[seat] -= 1000;
+        self.riichi_sticks += 1;
+        self.events.push(Event::RiichiAccepted { seat });
+    }
+
+    fn call(&mut self, seat: usize, discarder: usize, tile: Tile, tiles: &[Tile], kind: MeldKind) {
+        let n = self.players.len();
+
+        self.players[discarder].discards.last_mut().unwrap().called = true;
+
+        let player = &mut self.players[seat];
+        remove_tiles(&mut player.concealed, tiles);
+
+        let mut meld_tiles = tiles.to_vec();
+        meld_tiles.push(tile);
+        meld_tiles.sort();
+
+        let meld = Meld {
+            kind,
+            tiles: meld_tiles,
+            called: Some(tile),
+            from: (discarder + n - seat) % n,
+        };
+
+        if self.rules.forbid_kuikae {
+            player.kuikae = legal::kuikae_kinds(tiles, tile, kind);
+        }
+
+        player.melds.push(meld.clone());
+        self.events.push(Event::Meld { seat, meld });
+        self.interrupt();
+
+        if kind == MeldKind::Daiminkan {
+            self.kan_seats.push(seat);
+            self.pending_dora += 1;
+            self.draw(seat, true);
+        } else {
+            self.phase = Phase::Turn {
+                seat,
+                after_call: true,
+                rinshan: false,
+            };
+        }
+    }
+
+    fn discard_passed(&mut self, discarder: usize) {
+        let n = self.players.len();
+
+        if self.rules.abortive_draws
+            && n == 4
+            && self.uninterrupted
+            && self.players.iter().all(|p| p.discards.len() == 1)
+        {
+            let first = self.players[0].discards[0].tile.kind();
+
+            if (27..31).contains(&first)
+                && self
+                    .players
+                    .iter()
+                    .all(|p| p.discards[0].tile.kind() == first)
+            {
+                self.phase = Phase::Ended(RoundEnd::Abortive(AbortiveDraw::SuufonRenda));
+                return;
+            }
+        }
+
+        if self.rules.abortive_draws
+            && self.kan_seats.len() == MAX_KAN_COUNT
+            && self.kan_seats.iter().any(|&s| s != self.kan_seats[0])
+        {
+            self.phase = Phase::Ended(RoundEnd::Abortive(AbortiveDraw::SuukanSanra));
+            return;
+        }
+
+        if self.wall.remaining() == 0 {
+            self.exhaustive_draw();
+            return;
+        }
+
+        self.draw(self.next_seat(discarder), false);
+    }
+
+    fn exhaustive_draw(&mut self) {
+        let visible = self.visible_counts();
+
+        let tenpai = self
+            .players
+            .iter()
+            .map(|p| {
+                let hand = p.hand();
+                let mut seen = visible;
+
+                // The player's own melds are passed separately.
+                for tile in p.melds.iter().flat_map(|m| m.tiles.iter()) {
+                    seen[tile.kind()] -= 1;
+                }
+
+                hand.is_tenpai(self.rules.tenpai_rule, &p.melds, &seen).tenpai
+            })
+            .collect();
+
+        let nagashi_mangan = if self.rules.nagashi_mangan {
+            (0..self.players.len())
+                .filter(|&s| {
+                    let discards = &self.players[s].discards;
+
+                    discards.iter().all(|d| !d.called && d.tile.is_yaochuu())
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        self.phase = Phase::Ended(RoundEnd::ExhaustiveDraw {
+            tenpai,
+            nagashi_mangan,
+        });
+    }
+
+    /// Adds the dora and, for riichi, the ura dora to a win checked by
+    /// [`legal`].
+    fn with_dora(&self, mut win: Win) -> Win {
+        win.dora = self.wall.dora();
+
+        if win.riichi {
+            win.ura_dora = self.wall.ura_dora();
+        }
+
+        return win;
+    }
+}
+
+fn remove_tiles(concealed: &mut Vec<Tile>, tiles: &[Tile]) {
+    for tile in tiles {
+        let i = concealed.iter().position(|t| t == tile).unwrap();
+        concealed.remove(i);
+    }
+}
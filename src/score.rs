@@ -0,0 +1,73 @@
+//! Point tables: han and fu to base points, and base points to payments.
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Limit {
+    Mangan,
+    Haneman,
+    Baiman,
+    Sanbaiman,
+    KazoeYakuman,
+    /// The number of yakuman, e.g. 2 for a double yakuman.
+    Yakuman(u8),
+}
+
+impl Limit {
+    pub fn base_points(self) -> u32 {
+        match self {
+            Limit::Mangan => 2000,
+            Limit::Haneman => 3000,
+            Limit::Baiman => 4000,
+            Limit::Sanbaiman => 6000,
+            Limit::KazoeYakuman => 8000,
+            Limit::Yakuman(n) => 8000 * n as u32,
+        }
+    }
+}
+
+/// The limit reached by `han` and `fu`, if any. 4 han 30 fu and 3 han 60 fu
+/// are not rounded up to mangan.
+pub fn limit(han: u8, fu: u8) -> Option<Limit> {
+    match han {
+        0..=4 => {
+            if fu as u32 * (1 << (han + 2)) > 2000 {
+                Some(Limit::Mangan)
+            } else {
+                None
+            }
+        }
+        5 => Some(Limit::Mangan),
+        6..=7 => Some(Limit::Haneman),
+        8..=10 => Some(Limit::Baiman),
+        11..=12 => Some(Limit::Sanbaiman),
+        _ => Some(Limit::KazoeYakuman),
+    }
+}
+
+/// fu × 2^(han + 2), capped by the limits.
+pub fn base_points(han: u8, fu: u8) -> u32 {
+    match limit(han, fu) {
+        Some(limit) => limit.base_points(),
+        None => fu as u32 * (1 << (han + 2)),
+    }
+}
+
+fn round_up_100(points: u32) -> u32 {
+    points.div_ceil(100) * 100
+}
+
+/// What the discarder pays, without honba.
+pub fn ron_payment(base: u32, dealer: bool) -> u32 {
+    round_up_100(base * if dealer { 6 } else { 4 })
+}
+
+/// What each non-dealer and the dealer pay for a tsumo, without honba. For a
+/// dealer's tsumo everyone pays the first value.
+pub fn tsumo_payments(base: u32, dealer: bool) -> (u32, u32) {
+    if dealer {
+        let each = round_up_100(base * 2);
+        (each, each)
+    } else {
+        (round_up_100(base), round_up_100(base * 2))
+    }
+}
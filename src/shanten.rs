@@ -1,13 +1,77 @@
-use rustc_hash::{FxHashMap, FxHashSet};
-use std::sync::OnceLock;
-use std::{
+use crate::meld::Meld;
+pub use crate::table::SuuhaiTable;
+use alloc::{string::ToString, vec, vec::Vec};
+use core::{
     ops::{Index, IndexMut},
     str::FromStr,
 };
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// The single-suit distance table, built on first use. This used to be an
+/// `FxHashMap` keyed by `(SuuhaiHand::hash(), k)`; use [`SuuhaiTable::get`]
+/// in its place.
+#[cfg(feature = "std")]
+pub static SUUHAI_DICT: OnceLock<SuuhaiTable> = OnceLock::new();
+
+#[cfg(feature = "precomputed-table")]
+pub static PRECOMPUTED_SUUHAI_TABLE: SuuhaiTable = SuuhaiTable::from_static(include_bytes!(
+    concat!(env!("OUT_DIR"), "/suuhai_table.bin")
+));
+
+/// The table used by the methods that take no table argument: the
+/// precomputed one if enabled, otherwise [`SUUHAI_DICT`] built on first use.
+#[cfg(any(feature = "std", feature = "precomputed-table"))]
+pub fn suuhai_table() -> &'static SuuhaiTable {
+    #[cfg(feature = "precomputed-table")]
+    return &PRECOMPUTED_SUUHAI_TABLE;
+
+    #[cfg(not(feature = "precomputed-table"))]
+    return SUUHAI_DICT.get_or_init(SuuhaiTable::new);
+}
+
+pub const YAOCHUU_TILES: [usize; 13] = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+
+/// How exhaustive-draw tenpai is judged when every wait is unavailable.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TenpaiRule {
+    /// Any hand shape that waits on some tile is tenpai.
+    AllowKaraten,
+    /// Not tenpai if every remaining copy of every wait is in the player's own
+    /// hand or melds (jun-karaten).
+    DenyJunKaraten,
+    /// Not tenpai if no copy of any wait can still be drawn.
+    DenyKaraten,
+}
 
-pub static SUUHAI_DICT: OnceLock<FxHashMap<(u32, u8), u8>> = OnceLock::new();
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TenpaiReason {
+    Noten,
+    Waiting,
+    JunKaraten,
+    Karaten,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ukeire {
+    pub shanten: i8,
+    pub tiles: Vec<usize>,
+    pub count: u32,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TenpaiStatus {
+    pub tenpai: bool,
+    pub reason: TenpaiReason,
+    pub waits: Vec<usize>,
+}
 
 #[derive(Default, PartialEq, Eq, Debug, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hand {
     m: SuuhaiHand,
     p: SuuhaiHand,
@@ -118,44 +182,68 @@ impl Hand {
         return 13 - 2 * toitsu_count - isolated_tile_count;
     }
 
+    #[cfg(any(feature = "std", feature = "precomputed-table"))]
     pub fn shanten_standard(&self) -> i8 {
+        self.shanten_standard_with(suuhai_table())
+    }
+
+    pub fn shanten_standard_with(&self, table: &SuuhaiTable) -> i8 {
         let pattern = [0, 3, 6, 9, 12, 2, 5, 8, 11, 14];
 
+        let target = self.count() / 3 * 3 + 2;
+
         let mut ret = 13;
         let has_toitsu = |k| if k % 3 == 2 { 1 } else { 0 };
         let check = |c, k| if c >= 5 { k >= 2 } else { true } && c * 3 >= k;
 
+        // Distances for every feasible size of each suit, looked up once
+        // rather than for every combination.
+        let dists = |count: u8, dist: &dyn Fn(u8) -> u8| {
+            let mut ret = [None; 15];
+
+            for k in pattern {
+                if check(count, k) {
+                    ret[k as usize] = Some(dist(k));
+                }
+            }
+
+            return ret;
+        };
+        let m = dists(self.m.count(), &|k| SuuhaiHand::dist_with(&self.m, table, k));
+        let s = dists(self.s.count(), &|k| SuuhaiHand::dist_with(&self.s, table, k));
+        let p = dists(self.p.count(), &|k| SuuhaiHand::dist_with(&self.p, table, k));
+        let z = dists(self.z.count(), &|k| JihaiHand::dist(&self.z, k));
+
         for mc in pattern {
-            if !check(self.m.count(), mc) {
+            let Some(md) = m[mc as usize] else {
                 continue;
-            }
+            };
 
             for sc in pattern {
-                if !check(self.s.count(), sc) {
+                let Some(sd) = s[sc as usize] else {
                     continue;
-                }
+                };
 
                 for pc in pattern {
-                    if !check(self.p.count(), pc) {
+                    let Some(pd) = p[pc as usize] else {
                         continue;
-                    }
+                    };
 
-                    for jc in pattern {
-                        if !check(self.z.count(), jc) {
-                            continue;
-                        }
+                    // The honor size is fixed by the others.
+                    let Some(jc) = target.checked_sub(mc + sc + pc) else {
+                        continue;
+                    };
+                    let Some(Some(jd)) = z.get(jc as usize) else {
+                        continue;
+                    };
 
-                        let toitsu_count =
-                            has_toitsu(mc) + has_toitsu(sc) + has_toitsu(pc) + has_toitsu(jc);
+                    let toitsu_count =
+                        has_toitsu(mc) + has_toitsu(sc) + has_toitsu(pc) + has_toitsu(jc);
 
-                        if toitsu_count == 1 && mc + sc + pc + jc == 14 {
-                            let c = SuuhaiHand::dist(&self.m, mc)
-                                + SuuhaiHand::dist(&self.s, sc)
-                                + SuuhaiHand::dist(&self.p, pc)
-                                + JihaiHand::dist(&self.z, jc);
+                    if toitsu_count == 1 {
+                        let c = md + sd + pd + jd;
 
-                            ret = std::cmp::min(ret, c as i8 - 1);
-                        }
+                        ret = core::cmp::min(ret, c as i8 - 1);
                     }
                 }
             }
@@ -164,9 +252,195 @@ impl Hand {
         return ret;
     }
 
+    #[cfg(any(feature = "std", feature = "precomputed-table"))]
+    pub fn shanten(&self) -> i8 {
+        self.shanten_with(suuhai_table())
+    }
+
+    pub fn shanten_with(&self, table: &SuuhaiTable) -> i8 {
+        let standard_shanten = self.shanten_standard_with(table);
+
+        if self.count() < 13 {
+            return standard_shanten;
+        }
+
+        standard_shanten
+            .min(self.shanten_chiitoitsu())
+            .min(self.shanten_kokushimusou())
+    }
+
     pub fn count(&self) -> u8 {
         self.m.count() + self.p.count() + self.s.count() + self.z.count()
     }
+
+    pub fn counts(&self) -> [u8; 34] {
+        let mut counts = [0; 34];
+
+        for (i, c) in counts.iter_mut().enumerate() {
+            *c = self[i];
+        }
+
+        return counts;
+    }
+
+    pub fn is_agari(&self) -> bool {
+        is_agari_counts(&self.counts())
+    }
+
+    /// Tiles that lower the shanten of a 3n+1 hand. `count` is the number of
+    /// copies left after the hand and `visible` tiles are taken out.
+    #[cfg(any(feature = "std", feature = "precomputed-table"))]
+    pub fn ukeire(&self, visible: &[u8; 34]) -> Ukeire {
+        self.ukeire_with(suuhai_table(), visible)
+    }
+
+    pub fn ukeire_with(&self, table: &SuuhaiTable, visible: &[u8; 34]) -> Ukeire {
+        let shanten = self.shanten_with(table);
+
+        if self.count() % 3 != 1 {
+            return Ukeire {
+                shanten,
+                tiles: vec![],
+                count: 0,
+            };
+        }
+
+        let mut hand = self.clone();
+        let mut tiles = vec![];
+        let mut count = 0;
+
+        for t in 0..34 {
+            if hand[t] >= 4 {
+                continue;
+            }
+
+            hand[t] += 1;
+
+            if hand.shanten_with(table) < shanten {
+                tiles.push(t);
+                count += 4u32.saturating_sub(self[t] as u32 + visible[t] as u32);
+            }
+
+            hand[t] -= 1;
+        }
+
+        return Ukeire {
+            shanten,
+            tiles,
+            count,
+        };
+    }
+
+    /// Tiles that complete the hand by shape. A tile the hand already holds
+    /// four of is never a wait, since no fifth copy exists to draw.
+    pub fn waits(&self) -> Vec<usize> {
+        if self.count() % 3 != 1 {
+            return vec![];
+        }
+
+        let counts = self.counts();
+
+        (0..34)
+            .filter(|&t| {
+                if counts[t] >= 4 {
+                    return false;
+                }
+
+                let mut next_counts = counts;
+                next_counts[t] += 1;
+                is_agari_counts(&next_counts)
+            })
+            .collect()
+    }
+
+    /// `melds` are the player's own melds and `visible` the number of copies
+    /// of each tile seen elsewhere: discards, other players' melds and dora
+    /// indicators.
+    pub fn is_tenpai(&self, rule: TenpaiRule, melds: &[Meld], visible: &[u8; 34]) -> TenpaiStatus {
+        let waits = self.waits();
+        let mut own = self.counts();
+
+        for tile in melds.iter().flat_map(|m| m.tiles.iter()) {
+            own[tile.kind()] += 1;
+        }
+
+        let reason = if waits.is_empty() {
+            TenpaiReason::Noten
+        } else if waits.iter().all(|&t| own[t] >= 4) {
+            TenpaiReason::JunKaraten
+        } else if waits.iter().all(|&t| own[t] + visible[t] >= 4) {
+            TenpaiReason::Karaten
+        } else {
+            TenpaiReason::Waiting
+        };
+
+        let tenpai = match reason {
+            TenpaiReason::Noten => false,
+            TenpaiReason::Waiting => true,
+            TenpaiReason::JunKaraten => rule == TenpaiRule::AllowKaraten,
+            TenpaiReason::Karaten => rule != TenpaiRule::DenyKaraten,
+        };
+
+        return TenpaiStatus {
+            tenpai,
+            reason,
+            waits,
+        };
+    }
+}
+
+fn is_agari_counts(counts: &[u8; 34]) -> bool {
+    is_agari_standard(counts) || is_agari_chiitoitsu(counts) || is_agari_kokushimusou(counts)
+}
+
+fn is_agari_standard(counts: &[u8; 34]) -> bool {
+    if counts.iter().map(|&c| c as usize).sum::<usize>() % 3 != 2 {
+        return false;
+    }
+
+    for i in 0..34 {
+        if counts[i] < 2 {
+            continue;
+        }
+
+        let mut rest = *counts;
+        rest[i] -= 2;
+
+        if is_all_mentsu(&mut rest) {
+            return true;
+        }
+    }
+
+    return false;
+}
+
+fn is_all_mentsu(counts: &mut [u8; 34]) -> bool {
+    for i in 0..34 {
+        let r = counts[i] % 3;
+
+        if r > 0 {
+            if i >= 27 || i % 9 > 6 || counts[i + 1] < r || counts[i + 2] < r {
+                return false;
+            }
+
+            counts[i + 1] -= r;
+            counts[i + 2] -= r;
+        }
+
+        counts[i] = 0;
+    }
+
+    return true;
+}
+
+fn is_agari_chiitoitsu(counts: &[u8; 34]) -> bool {
+    counts.iter().filter(|&&c| c == 2).count() == 7 && counts.iter().all(|&c| c == 0 || c == 2)
+}
+
+fn is_agari_kokushimusou(counts: &[u8; 34]) -> bool {
+    counts.iter().map(|&c| c as usize).sum::<usize>() == 14
+        && YAOCHUU_TILES.iter().all(|&t| counts[t] >= 1)
+        && YAOCHUU_TILES.iter().map(|&t| counts[t] as usize).sum::<usize>() == 14
 }
 
 impl Index<usize> for Hand {
@@ -211,13 +485,12 @@ impl FromStr for Hand {
         for i in 0..length {
             let n = chars[i];
 
-            if ('0'..='9').contains(&n) {
+            if n.is_ascii_digit() {
                 let mut value = n.to_digit(10).unwrap() as u8;
 
                 let tile_type = (i + 1..length)
                     .map(|j| chars[j])
-                    .filter(|&c| c == 'm' || c == 'p' || c == 's' || c == 'z')
-                    .next();
+                    .find(|&c| c == 'm' || c == 'p' || c == 's' || c == 'z');
 
                 if tile_type.is_none() {
                     return Err(());
@@ -227,8 +500,8 @@ impl FromStr for Hand {
 
                 match tile_type {
                     'z' => {
-                        if 1 <= value && value < 8 {
-                            hand.z[value as usize] += 1;
+                        if (1..8).contains(&value) {
+                            hand.z[value as usize - 1] += 1;
                         } else {
                             return Err(());
                         }
@@ -266,82 +539,24 @@ impl FromStr for Hand {
 }
 
 #[derive(Default, PartialEq, Eq, Debug, PartialOrd, Ord, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SuuhaiHand([u8; 9]);
 #[derive(Default, PartialEq, Eq, Debug, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct JihaiHand([u8; 7]);
 
 impl SuuhaiHand {
     const LENGTH: usize = 9;
 
     pub fn check(&self) -> bool {
-        (0..Self::LENGTH).filter(|&i| self[i] > 4).next().is_none()
+        !(0..Self::LENGTH).any(|i| self[i] > 4)
     }
 
     pub fn all_partly_agari_pattern() -> Vec<Self> {
-        let mut ret = vec![];
-
-        let mut mentsu = vec![None];
-        let mut toitsu = vec![None];
-
-        for i in 0..Self::LENGTH - 2 {
-            mentsu.push(Some([i, i + 1, i + 2]));
-        }
-
-        for i in 0..Self::LENGTH {
-            mentsu.push(Some([i, i, i]));
-            toitsu.push(Some([i, i]));
-        }
-
-        for m1 in mentsu.iter() {
-            for m2 in mentsu.iter() {
-                for m3 in mentsu.iter() {
-                    for m4 in mentsu.iter() {
-                        for t1 in toitsu.iter() {
-                            let mut hand = Self::default();
-
-                            if let Some(m1) = m1 {
-                                for &t in m1 {
-                                    hand[t] += 1;
-                                }
-                            }
-
-                            if let Some(m2) = m2 {
-                                for &t in m2 {
-                                    hand[t] += 1;
-                                }
-                            }
-
-                            if let Some(m3) = m3 {
-                                for &t in m3 {
-                                    hand[t] += 1;
-                                }
-                            }
-
-                            if let Some(m4) = m4 {
-                                for &t in m4 {
-                                    hand[t] += 1;
-                                }
-                            }
-
-                            if let Some(t1) = t1 {
-                                for &t in t1 {
-                                    hand[t] += 1;
-                                }
-                            }
-
-                            if hand.check() && hand.count() > 0 {
-                                ret.push(hand);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        ret.sort();
-        ret.dedup();
-
-        return ret;
+        crate::table::partly_agari_patterns()
+            .iter()
+            .map(Self::from)
+            .collect()
     }
 
     pub fn count(&self) -> u8 {
@@ -360,91 +575,21 @@ impl SuuhaiHand {
         return h;
     }
 
-    pub fn calc_shanten_to_all_partly_pattern() -> FxHashMap<(u32, u8), u8> {
-        let mut hash = FxHashMap::default();
-        let mut q = std::collections::VecDeque::default();
-        let mut seen = FxHashSet::default();
-
-        for partly_agari_pattern in Self::all_partly_agari_pattern() {
-            let c = partly_agari_pattern.count();
-            hash.insert((partly_agari_pattern.hash(), c), 0);
-            q.push_front((partly_agari_pattern, c));
-        }
-
-        let check = |c, k| if c >= 5 { k >= 2 } else { true } && c * 3 >= k;
-
-        while let Some((pattern, c)) = q.pop_front() {
-            if seen.contains(&(pattern.clone(), c)) {
-                continue;
-            }
-
-            seen.insert((pattern.clone(), c));
-
-            let d = *hash.get(&(pattern.hash(), c)).unwrap();
-
-            if pattern.count() < 14 {
-                for i in 0..Self::LENGTH {
-                    if pattern[i] == 4 {
-                        continue;
-                    }
-
-                    let next_pattern = {
-                        let mut next_pattern = pattern.clone();
-                        next_pattern[i] += 1;
-                        next_pattern
-                    };
-
-                    if !check(next_pattern.count(), c) {
-                        continue;
-                    }
-
-                    if let Some(&prev_dist) = hash.get(&(next_pattern.hash(), c)) {
-                        if prev_dist > d {
-                            hash.insert((next_pattern.hash(), c), d);
-                            q.push_front((next_pattern, c));
-                        }
-                    } else {
-                        hash.insert((next_pattern.hash(), c), d);
-                        q.push_front((next_pattern, c));
-                    }
-                }
-            }
-
-            if pattern.count() > 0 {
-                for i in 0..Self::LENGTH {
-                    if pattern[i] == 0 {
-                        continue;
-                    }
-
-                    let next_pattern = {
-                        let mut next_pattern = pattern.clone();
-                        next_pattern[i] -= 1;
-                        next_pattern
-                    };
-
-                    if !check(next_pattern.count(), c) {
-                        continue;
-                    }
-
-                    if !hash.contains_key(&(next_pattern.hash(), c)) {
-                        hash.insert((next_pattern.hash(), c), d + 1);
-                        q.push_back((next_pattern, c));
-                    }
-                }
-            }
-        }
-
-        return hash;
+    pub fn calc_shanten_to_all_partly_pattern() -> SuuhaiTable {
+        SuuhaiTable::new()
     }
 
+    #[cfg(any(feature = "std", feature = "precomputed-table"))]
     pub fn dist(&self, k: u8) -> u8 {
+        self.dist_with(suuhai_table(), k)
+    }
+
+    pub fn dist_with(&self, table: &SuuhaiTable, k: u8) -> u8 {
         if k == 0 {
             return 0;
         }
 
-        SUUHAI_DICT.get_or_init(SuuhaiHand::calc_shanten_to_all_partly_pattern);
-
-        return *SUUHAI_DICT.get().unwrap().get(&(self.hash(), k)).unwrap();
+        return table.get(&self.0, k).unwrap();
     }
 }
 
@@ -452,7 +597,7 @@ impl JihaiHand {
     const LENGTH: usize = 7;
 
     pub fn check(&self) -> bool {
-        (0..Self::LENGTH).filter(|&i| self[i] > 4).next().is_none()
+        !(0..Self::LENGTH).any(|i| self[i] > 4)
     }
 
     pub fn count(&self) -> u8 {
@@ -467,9 +612,8 @@ impl JihaiHand {
         let mentsu_count = k / 3;
         let toitsu_count = if k % 3 == 2 { 1 } else { 0 };
 
-        let mut counts = self.0.iter().map(|&v| v).collect::<Vec<_>>();
-        counts.sort();
-        counts.reverse();
+        let mut counts = self.0;
+        counts.sort_unstable_by(|a, b| b.cmp(a));
 
         let mut ret = 0;
 
@@ -489,21 +633,21 @@ impl JihaiHand {
     }
 }
 
-impl std::ops::Index<usize> for SuuhaiHand {
+impl core::ops::Index<usize> for SuuhaiHand {
     type Output = u8;
     fn index(&self, index: usize) -> &Self::Output {
         &self.0[index]
     }
 }
 
-impl std::ops::IndexMut<usize> for SuuhaiHand {
+impl core::ops::IndexMut<usize> for SuuhaiHand {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.0[index]
     }
 }
 
-impl std::fmt::Display for SuuhaiHand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SuuhaiHand {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{}",
@@ -525,21 +669,21 @@ impl From<&[u8; 9]> for SuuhaiHand {
     }
 }
 
-impl std::ops::Index<usize> for JihaiHand {
+impl core::ops::Index<usize> for JihaiHand {
     type Output = u8;
     fn index(&self, index: usize) -> &Self::Output {
         &self.0[index]
     }
 }
 
-impl std::ops::IndexMut<usize> for JihaiHand {
+impl core::ops::IndexMut<usize> for JihaiHand {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.0[index]
     }
 }
 
-impl std::fmt::Display for JihaiHand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for JihaiHand {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{}",
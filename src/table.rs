@@ -0,0 +1,240 @@
+//! Distance from every single-suit hand of at most 14 tiles to the nearest
+//! partial agari shape of each size, stored densely so that it can be built
+//! without hashing and embedded as bytes.
+//!
+//! This file only depends on `core` and `alloc`; `build.rs` includes it to
+//! precompute the table for the `precomputed-table` feature.
+
+use alloc::{borrow::Cow, collections::VecDeque, vec, vec::Vec};
+
+const LENGTH: usize = 9;
+const MAX_TILES: usize = 14;
+
+pub const TARGET_SIZES: [u8; 9] = [2, 3, 5, 6, 8, 9, 11, 12, 14];
+
+const UNKNOWN: u8 = 0xF;
+
+// WAYS[i][s]: number of ways to fill positions i.. with at most 4 tiles each
+// and at most s tiles in total.
+const WAYS: [[u32; MAX_TILES + 1]; LENGTH + 1] = {
+    let mut ways = [[0; MAX_TILES + 1]; LENGTH + 1];
+    let mut s = 0;
+
+    while s <= MAX_TILES {
+        ways[LENGTH][s] = 1;
+        s += 1;
+    }
+
+    let mut i = LENGTH;
+
+    while i > 0 {
+        i -= 1;
+        let mut s = 0;
+
+        while s <= MAX_TILES {
+            let mut v = 0;
+
+            while v <= 4 && v <= s {
+                ways[i][s] += ways[i + 1][s - v];
+                v += 1;
+            }
+
+            s += 1;
+        }
+    }
+
+    ways
+};
+
+pub const HAND_COUNT: usize = WAYS[0][MAX_TILES] as usize;
+
+fn rank(counts: &[u8; LENGTH]) -> usize {
+    let mut rank = 0;
+    let mut rest = MAX_TILES;
+
+    for i in 0..LENGTH {
+        for v in 0..counts[i] as usize {
+            rank += WAYS[i + 1][rest - v] as usize;
+        }
+
+        rest -= counts[i] as usize;
+    }
+
+    return rank;
+}
+
+fn index(counts: &[u8; LENGTH], k: u8) -> Option<usize> {
+    if counts.iter().any(|&c| c > 4) || counts.iter().sum::<u8>() as usize > MAX_TILES {
+        return None;
+    }
+
+    let k_index = TARGET_SIZES.iter().position(|&t| t == k)?;
+
+    return Some(rank(counts) * TARGET_SIZES.len() + k_index);
+}
+
+fn check(c: u8, k: u8) -> bool {
+    (c < 5 || k >= 2) && c * 3 >= k
+}
+
+pub fn partly_agari_patterns() -> Vec<[u8; LENGTH]> {
+    let mut ret = vec![];
+
+    let mut mentsu = vec![None];
+    let mut toitsu = vec![None];
+
+    for i in 0..LENGTH - 2 {
+        mentsu.push(Some([i, i + 1, i + 2]));
+    }
+
+    for i in 0..LENGTH {
+        mentsu.push(Some([i, i, i]));
+        toitsu.push(Some([i, i]));
+    }
+
+    for m1 in mentsu.iter() {
+        for m2 in mentsu.iter() {
+            for m3 in mentsu.iter() {
+                for m4 in mentsu.iter() {
+                    for t1 in toitsu.iter() {
+                        let mut counts = [0u8; LENGTH];
+
+                        for m in [m1, m2, m3, m4].into_iter().flatten() {
+                            for &t in m {
+                                counts[t] += 1;
+                            }
+                        }
+
+                        if let Some(t1) = t1 {
+                            for &t in t1 {
+                                counts[t] += 1;
+                            }
+                        }
+
+                        if counts.iter().all(|&c| c <= 4) && counts.iter().any(|&c| c > 0) {
+                            ret.push(counts);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    ret.sort();
+    ret.dedup();
+
+    return ret;
+}
+
+pub struct SuuhaiTable {
+    dist: Cow<'static, [u8]>,
+}
+
+impl SuuhaiTable {
+    /// Runs a 0-1 BFS outward from every partial agari shape: adding a tile
+    /// keeps the distance, removing one costs a tile.
+    pub fn new() -> Self {
+        let mut dist = vec![UNKNOWN; HAND_COUNT * TARGET_SIZES.len()];
+        let mut seen = vec![false; HAND_COUNT * TARGET_SIZES.len()];
+        let mut q = VecDeque::new();
+
+        for pattern in partly_agari_patterns() {
+            let c = pattern.iter().sum::<u8>();
+            dist[index(&pattern, c).unwrap()] = 0;
+            q.push_front((pattern, c));
+        }
+
+        while let Some((pattern, c)) = q.pop_front() {
+            let i = index(&pattern, c).unwrap();
+
+            if seen[i] {
+                continue;
+            }
+
+            seen[i] = true;
+
+            let d = dist[i];
+            let count = pattern.iter().sum::<u8>();
+
+            if (count as usize) < MAX_TILES {
+                for t in 0..LENGTH {
+                    if pattern[t] == 4 {
+                        continue;
+                    }
+
+                    let mut next_pattern = pattern;
+                    next_pattern[t] += 1;
+
+                    if !check(count + 1, c) {
+                        continue;
+                    }
+
+                    let j = index(&next_pattern, c).unwrap();
+
+                    if dist[j] == UNKNOWN || dist[j] > d {
+                        dist[j] = d;
+                        q.push_front((next_pattern, c));
+                    }
+                }
+            }
+
+            if count > 0 {
+                for t in 0..LENGTH {
+                    if pattern[t] == 0 {
+                        continue;
+                    }
+
+                    let mut next_pattern = pattern;
+                    next_pattern[t] -= 1;
+
+                    if !check(count - 1, c) {
+                        continue;
+                    }
+
+                    let j = index(&next_pattern, c).unwrap();
+
+                    if dist[j] == UNKNOWN {
+                        dist[j] = d + 1;
+                        q.push_back((next_pattern, c));
+                    }
+                }
+            }
+        }
+
+        let packed = dist
+            .chunks(2)
+            .map(|pair| pair[0] | pair.get(1).copied().unwrap_or(UNKNOWN) << 4)
+            .collect::<Vec<_>>();
+
+        return Self {
+            dist: Cow::Owned(packed),
+        };
+    }
+
+    /// Wraps bytes previously produced by [`SuuhaiTable::as_bytes`].
+    pub const fn from_static(bytes: &'static [u8]) -> Self {
+        Self {
+            dist: Cow::Borrowed(bytes),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.dist
+    }
+
+    /// Fewest tiles missing from `counts` to contain a partial agari shape of
+    /// `k` tiles, or `None` if `k` is not a shape size or the pair was never
+    /// reached by the search.
+    pub fn get(&self, counts: &[u8; LENGTH], k: u8) -> Option<u8> {
+        let i = index(counts, k)?;
+        let d = self.dist.get(i / 2)? >> (i % 2 * 4) & 0xF;
+
+        if d == UNKNOWN { None } else { Some(d) }
+    }
+}
+
+impl Default for SuuhaiTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,1118 @@
+//! tenhou.net/6 JSON logs, the format the Tenhou replay viewer opens from
+//! `https://tenhou.net/6/#json=...`.
+//!
+//! Tiles are two-digit codes: 11-19, 21-29 and 31-39 for manzu, pinzu and
+//! souzu, 41-47 for the honours in the crate's order, and 51-53 for the red
+//! fives. [`TenhouRound::to_round`] rebuilds the wall from a log and replays it
+//! as a [`Round`]; [`TenhouRound::from_round`] records one.
+
+use crate::game::{Game, settle};
+use crate::meld::{Meld, MeldKind};
+use crate::round::{
+    self, AbortiveDraw, Action, Phase, Round, RoundEnd, RoundError, RoundSetup, Rules, Win,
+};
+use crate::score::{self, Limit};
+use crate::tile::Tile;
+use crate::wall::{DEAD_WALL_SIZE, MAX_DORA_INDICATOR_COUNT, MAX_KAN_COUNT, Wall, WallConfig};
+use crate::yaku::{self, Yaku};
+use serde_json::{Value, json};
+use std::fmt;
+
+/// The discard code for tsumogiri.
+pub const TSUMOGIRI: u8 = 60;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum TenhouError {
+    Json(String),
+    /// A value that is not a tile code, a meld string or the expected shape.
+    Invalid(String),
+    /// The log's tiles do not fit in one set, e.g. five copies of a kind.
+    InconsistentTiles,
+    /// A draw that differs from the tile the rebuilt wall gives.
+    Mismatch {
+        seat: usize,
+    },
+    /// An action of the log the round rejects.
+    Round(RoundError),
+}
+
+impl fmt::Display for TenhouError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TenhouError::Json(e) => write!(f, "invalid JSON: {}", e),
+            TenhouError::Invalid(s) => write!(f, "invalid log entry {}", s),
+            TenhouError::InconsistentTiles => write!(f, "the tiles do not fit in one set"),
+            TenhouError::Mismatch { seat } => write!(f, "the draws of seat {} do not match", seat),
+            TenhouError::Round(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TenhouError {}
+
+pub fn tile_from_code(code: u8) -> Option<Tile> {
+    match code {
+        11..=19 | 21..=29 | 31..=39 => Some(Tile::new(
+            (code / 10 - 1) as usize * 9 + (code % 10 - 1) as usize,
+        )),
+        41..=47 => Some(Tile::new(27 + (code - 41) as usize)),
+        51..=53 => Some(Tile::red((code - 51) as usize * 9 + 4)),
+        _ => None,
+    }
+}
+
+pub fn tile_code(tile: Tile) -> u8 {
+    let kind = tile.kind();
+
+    if tile.is_red() {
+        return 51 + (kind / 9) as u8;
+    }
+
+    if tile.is_jihai() {
+        return 41 + (kind - 27) as u8;
+    }
+
+    return (kind / 9 + 1) as u8 * 10 + (kind % 9 + 1) as u8;
+}
+
+/// An entry of a player's draw column.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Take {
+    Draw(Tile),
+    /// A chi, pon or daiminkan.
+    Call(Meld),
+}
+
+/// An entry of a player's discard column.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Cut {
+    /// `tile` is `None` for tsumogiri.
+    Discard {
+        tile: Option<Tile>,
+        riichi: bool,
+    },
+    Ankan(Meld),
+    Kakan {
+        meld: Meld,
+        added: Tile,
+    },
+    /// The placeholder after a daiminkan, which takes no discard.
+    Skip,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct AgariInfo {
+    pub seat: usize,
+    /// The discarder; the winner for tsumo.
+    pub from: usize,
+    /// The player liable for the hand; the winner when nobody is.
+    pub pao: usize,
+    pub deltas: Vec<i32>,
+    /// Such as `30符2飜2000点` or `満貫2000-4000点`.
+    pub text: String,
+    /// Such as `立直(1飜)` or `国士無双(役満)`.
+    pub yaku: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Outcome {
+    /// `和了`, `流局`, `九種九牌` and so on.
+    pub name: String,
+    /// Score changes of a draw.
+    pub deltas: Vec<i32>,
+    /// One entry per win.
+    pub agari: Vec<AgariInfo>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TenhouRound {
+    /// 0 for East 1, 4 for South 1, and so on.
+    pub kyoku: usize,
+    pub honba: u32,
+    pub riichi_sticks: u32,
+    /// Scores at the start of the round.
+    pub scores: Vec<i32>,
+    pub dora_indicators: Vec<Tile>,
+    pub ura_dora_indicators: Vec<Tile>,
+    pub hands: Vec<Vec<Tile>>,
+    pub takes: Vec<Vec<Take>>,
+    pub cuts: Vec<Vec<Cut>>,
+    pub outcome: Outcome,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TenhouLog {
+    pub title: Vec<String>,
+    pub names: Vec<String>,
+    /// Short rule description such as `般南喰赤`.
+    pub rule: String,
+    /// Red fives are in play.
+    pub aka: bool,
+    pub rounds: Vec<TenhouRound>,
+}
+
+impl TenhouLog {
+    pub fn from_rounds(names: &[&str], rounds: &[Round]) -> Self {
+        Self {
+            title: vec![String::new(), String::new()],
+            names: names.iter().map(|n| n.to_string()).collect(),
+            rule: String::new(),
+            aka: rounds
+                .first()
+                .is_none_or(|r| r.wall().config().red_fives != [0; 3]),
+            rounds: rounds.iter().map(TenhouRound::from_round).collect(),
+        }
+    }
+
+    pub fn from_game(names: &[&str], game: &Game) -> Self {
+        let rounds = game
+            .history()
+            .iter()
+            .map(|r| r.round.clone())
+            .collect::<Vec<_>>();
+
+        return Self::from_rounds(names, &rounds);
+    }
+
+    pub fn parse(json: &str) -> Result<Self, TenhouError> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| TenhouError::Json(e.to_string()))?;
+        let strings = |key: &str| -> Vec<String> {
+            value[key]
+                .as_array()
+                .map(|a| a.iter().map(|v| v.as_str().unwrap_or("").into()).collect())
+                .unwrap_or_default()
+        };
+
+        let rounds = array(&value["log"])?
+            .iter()
+            .map(TenhouRound::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        return Ok(Self {
+            title: strings("title"),
+            names: strings("name"),
+            rule: value["rule"]["disp"].as_str().unwrap_or("").into(),
+            aka: value["rule"]["aka"].as_i64() != Some(0),
+            rounds,
+        });
+    }
+
+    pub fn to_json(&self) -> String {
+        let value = json!({
+            "title": self.title,
+            "name": self.names,
+            "rule": {"disp": self.rule, "aka": self.aka as u8},
+            "log": self.rounds.iter().map(TenhouRound::to_value).collect::<Vec<_>>(),
+        });
+
+        return value.to_string();
+    }
+
+    /// Replays every round. See [`TenhouRound::to_round`].
+    pub fn to_rounds(&self, rules: Rules) -> Result<Vec<Round>, TenhouError> {
+        self.rounds
+            .iter()
+            .map(|r| r.to_round(rules, self.aka))
+            .collect()
+    }
+}
+
+impl TenhouRound {
+    pub fn dealer(&self) -> usize {
+        self.kyoku % 4
+    }
+
+    /// Records a round, finished or not. Scores are those before any riichi
+    /// deposit of the round, as the viewer takes the deposits itself.
+    pub fn from_round(round: &Round) -> Self {
+        let n = round.player_count();
+        let mut scores = round.scores().to_vec();
+        let mut accepted = 0;
+        let mut takes = vec![vec![]; n];
+        let mut cuts = vec![vec![]; n];
+        let mut pons: Vec<Vec<Meld>> = vec![vec![]; n];
+
+        for e in round.events() {
+            match e {
+                round::Event::Draw { seat, tile, .. } => takes[*seat].push(Take::Draw(*tile)),
+                round::Event::Discard {
+                    seat,
+                    tile,
+                    tsumogiri,
+                    riichi,
+                } => cuts[*seat].push(Cut::Discard {
+                    tile: if *tsumogiri { None } else { Some(*tile) },
+                    riichi: *riichi,
+                }),
+                round::Event::RiichiAccepted { seat } => {
+                    scores[*seat] += 1000;
+                    accepted += 1;
+                }
+                round::Event::Meld { seat, meld } => match meld.kind {
+                    MeldKind::Chi => takes[*seat].push(Take::Call(meld.clone())),
+                    MeldKind::Pon => {
+                        pons[*seat].push(meld.clone());
+                        takes[*seat].push(Take::Call(meld.clone()));
+                    }
+                    MeldKind::Daiminkan => {
+                        takes[*seat].push(Take::Call(meld.clone()));
+                        cuts[*seat].push(Cut::Skip);
+                    }
+                    MeldKind::Kakan => {
+                        let pon = pons[*seat]
+                            .iter()
+                            .find(|p| p.base() == meld.base())
+                            .unwrap();
+                        let mut added = meld.tiles.clone();
+                        for t in pon.tiles.iter() {
+                            let i = added.iter().position(|a| a == t).unwrap();
+                            added.remove(i);
+                        }
+
+                        cuts[*seat].push(Cut::Kakan {
+                            meld: meld.clone(),
+                            added: added[0],
+                        });
+                    }
+                    MeldKind::Ankan => cuts[*seat].push(Cut::Ankan(meld.clone())),
+                },
+                round::Event::Dora { .. } => {}
+            }
+        }
+
+        let riichi_win = match round.result() {
+            Some(RoundEnd::Tsumo(win)) => win.riichi,
+            Some(RoundEnd::Ron(wins)) => wins.iter().any(|w| w.riichi),
+            _ => false,
+        };
+
+        return Self {
+            kyoku: (round.round_wind() - 27) * 4 + round.dealer(),
+            honba: round.honba(),
+            riichi_sticks: round.riichi_sticks() - accepted,
+            scores,
+            dora_indicators: round.wall().dora_indicators().to_vec(),
+            ura_dora_indicators: if riichi_win {
+                round.wall().ura_dora_indicators().to_vec()
+            } else {
+                vec![]
+            },
+            hands: round.initial_hands().to_vec(),
+            takes,
+            cuts,
+            outcome: outcome(round),
+        };
+    }
+
+    /// Rebuilds the wall from the hands, draws and indicators of the log,
+    /// filling the tiles nobody saw in kind order, and replays the round. Calls
+    /// and wins come from the log; everything else is up to `rules`.
+    pub fn to_round(&self, rules: Rules, aka: bool) -> Result<Round, TenhouError> {
+        let n = self.hands.len();
+        let dealer = self.dealer();
+        let config = WallConfig {
+            red_fives: if aka { [1; 3] } else { [0; 3] },
+            ..WallConfig::default()
+        };
+
+        if n != config.players() || self.hands.iter().any(|h| h.len() != 13) {
+            return Err(TenhouError::Invalid("hands".into()));
+        }
+
+        for (name, len) in [
+            ("scores", self.scores.len()),
+            ("takes", self.takes.len()),
+            ("cuts", self.cuts.len()),
+        ] {
+            if len != n {
+                return Err(TenhouError::Invalid(name.into()));
+            }
+        }
+
+        // East 1 to North 4.
+        if self.kyoku >= 16 {
+            return Err(TenhouError::Invalid(format!("kyoku {}", self.kyoku)));
+        }
+
+        let (live, rinshan) = self.draws()?;
+        let mut order = vec![];
+
+        for i in 0..3 {
+            for k in 0..n {
+                order.extend_from_slice(&self.hands[(dealer + k) % n][i * 4..i * 4 + 4]);
+            }
+        }
+
+        for k in 0..n {
+            order.push(self.hands[(dealer + k) % n][12]);
+        }
+
+        order.extend(live);
+
+        let mut pool = config.tiles();
+        let live_size = pool.len() - DEAD_WALL_SIZE;
+        let mut dead = [None; DEAD_WALL_SIZE];
+
+        if order.len() > live_size
+            || rinshan.len() > MAX_KAN_COUNT
+            || self.dora_indicators.len() > MAX_DORA_INDICATOR_COUNT
+            || self.ura_dora_indicators.len() > MAX_DORA_INDICATOR_COUNT
+        {
+            return Err(TenhouError::InconsistentTiles);
+        }
+
+        for (i, &t) in rinshan.iter().enumerate() {
+            dead[i] = Some(t);
+        }
+
+        for (i, &t) in self.dora_indicators.iter().enumerate() {
+            dead[MAX_KAN_COUNT + i] = Some(t);
+        }
+
+        for (i, &t) in self.ura_dora_indicators.iter().enumerate() {
+            dead[MAX_KAN_COUNT + MAX_DORA_INDICATOR_COUNT + i] = Some(t);
+        }
+
+        for t in order.iter().chain(dead.iter().flatten()) {
+            let i = pool
+                .iter()
+                .position(|p| p == t)
+                .ok_or(TenhouError::InconsistentTiles)?;
+            pool.remove(i);
+        }
+
+        let mut pool = pool.into_iter();
+        order.resize_with(live_size, || pool.next().unwrap());
+        order.extend(
+            dead.iter()
+                .map(|t| t.unwrap_or_else(|| pool.next().unwrap())),
+        );
+
+        let wall = Wall::from_tiles(config, order).ok_or(TenhouError::InconsistentTiles)?;
+        let setup = RoundSetup {
+            round_wind: 27 + self.kyoku / 4,
+            dealer,
+            honba: self.honba,
+            riichi_sticks: self.riichi_sticks,
+            scores: self.scores.clone(),
+        };
+        let mut round = Round::new(rules, wall, setup);
+        let mut ti = vec![0; n];
+        let mut ci = vec![0; n];
+        let mut seen = 0;
+
+        loop {
+            for e in round.events()[seen..].iter() {
+                match e {
+                    round::Event::Draw { seat, tile, .. } => {
+                        if self.takes[*seat].get(ti[*seat]) != Some(&Take::Draw(*tile)) {
+                            return Err(TenhouError::Mismatch { seat: *seat });
+                        }
+                        ti[*seat] += 1;
+                    }
+                    round::Event::Discard { seat, .. } => ci[*seat] += 1,
+                    round::Event::Meld { seat, meld } => match meld.kind {
+                        MeldKind::Chi | MeldKind::Pon | MeldKind::Daiminkan => ti[*seat] += 1,
+                        MeldKind::Kakan | MeldKind::Ankan => ci[*seat] += 1,
+                    },
+                    _ => {}
+                }
+            }
+            seen = round.events().len();
+
+            let (seat, action) = match round.phase() {
+                Phase::Ended(_) => break,
+                Phase::Turn { seat, .. } => {
+                    let seat = *seat;
+
+                    if self.cuts[seat].get(ci[seat]) == Some(&Cut::Skip) {
+                        ci[seat] += 1;
+                        continue;
+                    }
+
+                    (seat, self.turn_action(&round, seat, ci[seat])?)
+                }
+                Phase::Calls {
+                    discarder, tile, ..
+                }
+                | Phase::Chankan {
+                    seat: discarder,
+                    tile,
+                    ..
+                } => {
+                    let seat = round.waiting_for()[0];
+                    (seat, self.response(seat, *discarder, *tile, &ti, &ci))
+                }
+            };
+
+            round.apply(seat, action).map_err(TenhouError::Round)?;
+        }
+
+        if (0..n).any(|s| ti[s] != self.takes[s].len() || ci[s] != self.cuts[s].len()) {
+            return Err(TenhouError::Invalid(
+                "entries after the end of the round".into(),
+            ));
+        }
+
+        return Ok(round);
+    }
+
+    /// Draws from the live wall and from the dead wall in the order they
+    /// happen.
+    fn draws(&self) -> Result<(Vec<Tile>, Vec<Tile>), TenhouError> {
+        let n = self.hands.len();
+        let mut ti = vec![0; n];
+        let mut ci = vec![0; n];
+        let mut live = vec![];
+        let mut rinshan = vec![];
+        let mut seat = self.dealer();
+        let mut after_kan = false;
+
+        while let Some(take) = self.takes[seat].get(ti[seat]) {
+            ti[seat] += 1;
+
+            let mut drawn = None;
+            if let Take::Draw(t) = take {
+                if after_kan {
+                    rinshan.push(*t);
+                } else {
+                    live.push(*t);
+                }
+                drawn = Some(*t);
+            }
+            after_kan = false;
+
+            let Some(cut) = self.cuts[seat].get(ci[seat]) else {
+                break;
+            };
+            ci[seat] += 1;
+
+            let Cut::Discard { tile, .. } = cut else {
+                // A kan: the same player draws a replacement.
+                after_kan = true;
+                continue;
+            };
+            let tile = tile
+                .or(drawn)
+                .ok_or_else(|| TenhouError::Invalid("tsumogiri after a call".into()))?;
+
+            seat = (1..n)
+                .map(|i| (seat + i) % n)
+                .filter(|&s| self.next_call(s, seat, tile, &ti).is_some())
+                .min_by_key(|&s| self.next_call(s, seat, tile, &ti).unwrap().kind == MeldKind::Chi)
+                .unwrap_or((seat + 1) % n);
+        }
+
+        return Ok((live, rinshan));
+    }
+
+    /// The call `seat` makes next, if it is on `tile` from `discarder`.
+    fn next_call(&self, seat: usize, discarder: usize, tile: Tile, ti: &[usize]) -> Option<&Meld> {
+        match self.takes[seat].get(ti[seat]) {
+            Some(Take::Call(meld))
+                if (seat + meld.from) % self.hands.len() == discarder
+                    && meld.called.map(|c| c.kind()) == Some(tile.kind()) =>
+            {
+                Some(meld)
+            }
+            _ => None,
+        }
+    }
+
+    fn turn_action(&self, round: &Round, seat: usize, ci: usize) -> Result<Action, TenhouError> {
+        let action = match self.cuts[seat].get(ci) {
+            Some(Cut::Discard { tile, riichi }) => {
+                let tile = tile
+                    .or(round.player(seat).drawn)
+                    .ok_or_else(|| TenhouError::Invalid("tsumogiri after a call".into()))?;
+
+                if *riichi {
+                    Action::Riichi(tile)
+                } else {
+                    Action::Discard(tile)
+                }
+            }
+            Some(Cut::Ankan(meld)) => Action::Ankan(meld.base()),
+            Some(Cut::Kakan { added, .. }) => Action::Kakan(*added),
+            Some(Cut::Skip) => unreachable!(),
+            None if self
+                .outcome
+                .agari
+                .iter()
+                .any(|a| a.seat == seat && a.from == seat) =>
+            {
+                Action::Tsumo
+            }
+            None if self.outcome.name == "九種九牌" => Action::KyuushuKyuuhai,
+            None => return Err(TenhouError::Invalid(format!("no action for seat {}", seat))),
+        };
+
+        return Ok(action);
+    }
+
+    fn response(
+        &self,
+        seat: usize,
+        discarder: usize,
+        tile: Tile,
+        ti: &[usize],
+        ci: &[usize],
+    ) -> Action {
+        let last = ci[discarder] == self.cuts[discarder].len();
+
+        if last
+            && self
+                .outcome
+                .agari
+                .iter()
+                .any(|a| a.seat == seat && a.from == discarder)
+        {
+            return Action::Ron;
+        }
+
+        let Some(meld) = self.next_call(seat, discarder, tile, ti) else {
+            return Action::Pass;
+        };
+        let mut own = meld.tiles.clone();
+        let i = own.iter().position(|&t| Some(t) == meld.called).unwrap();
+        own.remove(i);
+
+        match meld.kind {
+            MeldKind::Chi => Action::Chi([own[0], own[1]]),
+            MeldKind::Pon => Action::Pon([own[0], own[1]]),
+            _ => Action::Daiminkan,
+        }
+    }
+
+    fn parse(value: &Value) -> Result<Self, TenhouError> {
+        let entries = array(value)?;
+
+        if entries.len() != 17 {
+            return Err(TenhouError::Invalid(value.to_string()));
+        }
+
+        let head = numbers(&entries[0])?;
+        // The kyoku runs from East 1 (0) to North 4 (15).
+        if head.len() != 3 || !(0..16).contains(&head[0]) {
+            return Err(TenhouError::Invalid(entries[0].to_string()));
+        }
+
+        let mut hands = vec![];
+        let mut takes = vec![];
+        let mut cuts = vec![];
+
+        for seat in 0..4 {
+            hands.push(tiles(&entries[4 + seat * 3])?);
+            takes.push(
+                array(&entries[5 + seat * 3])?
+                    .iter()
+                    .map(parse_take)
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+            cuts.push(
+                array(&entries[6 + seat * 3])?
+                    .iter()
+                    .map(parse_cut)
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        return Ok(Self {
+            kyoku: head[0] as usize,
+            honba: u32::try_from(head[1]).map_err(|_| invalid(&entries[0]))?,
+            riichi_sticks: u32::try_from(head[2]).map_err(|_| invalid(&entries[0]))?,
+            scores: numbers(&entries[1])?
+                .iter()
+                .map(|&s| i32::try_from(s).map_err(|_| invalid(&entries[1])))
+                .collect::<Result<_, _>>()?,
+            dora_indicators: tiles(&entries[2])?,
+            ura_dora_indicators: tiles(&entries[3])?,
+            hands,
+            takes,
+            cuts,
+            outcome: parse_outcome(&entries[16])?,
+        });
+    }
+
+    fn to_value(&self) -> Value {
+        let codes = |tiles: &[Tile]| tiles.iter().map(|&t| tile_code(t)).collect::<Vec<_>>();
+        let mut entries = vec![
+            json!([self.kyoku, self.honba, self.riichi_sticks]),
+            json!(self.scores),
+            json!(codes(&self.dora_indicators)),
+            json!(codes(&self.ura_dora_indicators)),
+        ];
+
+        for seat in 0..self.hands.len() {
+            let takes = self.takes[seat]
+                .iter()
+                .map(|t| match t {
+                    Take::Draw(tile) => json!(tile_code(*tile)),
+                    Take::Call(meld) => json!(meld_string(meld, None)),
+                })
+                .collect::<Vec<_>>();
+            let cuts = self.cuts[seat]
+                .iter()
+                .map(|c| match c {
+                    Cut::Discard { tile, riichi } => {
+                        let code = tile.map_or(TSUMOGIRI, tile_code);
+                        if *riichi {
+                            json!(format!("r{}", code))
+                        } else {
+                            json!(code)
+                        }
+                    }
+                    Cut::Ankan(meld) => json!(meld_string(meld, None)),
+                    Cut::Kakan { meld, added } => json!(meld_string(meld, Some(*added))),
+                    Cut::Skip => json!(0),
+                })
+                .collect::<Vec<_>>();
+
+            entries.push(json!(codes(&self.hands[seat])));
+            entries.push(Value::Array(takes));
+            entries.push(Value::Array(cuts));
+        }
+
+        let mut outcome = vec![json!(self.outcome.name)];
+        if self.outcome.agari.is_empty() {
+            if !self.outcome.deltas.is_empty() {
+                outcome.push(json!(self.outcome.deltas));
+            }
+        } else {
+            for a in self.outcome.agari.iter() {
+                let mut info = vec![json!(a.seat), json!(a.from), json!(a.pao), json!(a.text)];
+                info.extend(a.yaku.iter().map(|y| json!(y)));
+
+                outcome.push(json!(a.deltas));
+                outcome.push(Value::Array(info));
+            }
+        }
+        entries.push(Value::Array(outcome));
+
+        return Value::Array(entries);
+    }
+}
+
+fn invalid(value: &Value) -> TenhouError {
+    TenhouError::Invalid(value.to_string())
+}
+
+fn array(value: &Value) -> Result<&Vec<Value>, TenhouError> {
+    value.as_array().ok_or_else(|| invalid(value))
+}
+
+fn numbers(value: &Value) -> Result<Vec<i64>, TenhouError> {
+    array(value)?
+        .iter()
+        .map(|v| v.as_i64().ok_or_else(|| invalid(v)))
+        .collect()
+}
+
+fn code(value: &Value) -> Result<Tile, TenhouError> {
+    value
+        .as_u64()
+        .and_then(|c| u8::try_from(c).ok())
+        .and_then(tile_from_code)
+        .ok_or_else(|| invalid(value))
+}
+
+fn tiles(value: &Value) -> Result<Vec<Tile>, TenhouError> {
+    array(value)?.iter().map(code).collect()
+}
+
+fn parse_take(value: &Value) -> Result<Take, TenhouError> {
+    if let Some(s) = value.as_str() {
+        let (meld, _) = parse_meld(s)?;
+
+        if !matches!(
+            meld.kind,
+            MeldKind::Chi | MeldKind::Pon | MeldKind::Daiminkan
+        ) {
+            return Err(invalid(value));
+        }
+
+        return Ok(Take::Call(meld));
+    }
+
+    return Ok(Take::Draw(code(value)?));
+}
+
+fn parse_cut(value: &Value) -> Result<Cut, TenhouError> {
+    if let Some(s) = value.as_str() {
+        if let Some(rest) = s.strip_prefix('r') {
+            let tile = match rest.parse::<u8>() {
+                Ok(TSUMOGIRI) => None,
+                Ok(c) => Some(tile_from_code(c).ok_or_else(|| invalid(value))?),
+                Err(_) => return Err(invalid(value)),
+            };
+
+            return Ok(Cut::Discard { tile, riichi: true });
+        }
+
+        return match parse_meld(s)? {
+            (meld, None) if meld.kind == MeldKind::Ankan => Ok(Cut::Ankan(meld)),
+            (meld, Some(added)) => Ok(Cut::Kakan { meld, added }),
+            _ => Err(invalid(value)),
+        };
+    }
+
+    return match value.as_u64() {
+        Some(0) => Ok(Cut::Skip),
+        Some(c) if c == TSUMOGIRI as u64 => Ok(Cut::Discard {
+            tile: None,
+            riichi: false,
+        }),
+        _ => Ok(Cut::Discard {
+            tile: Some(code(value)?),
+            riichi: false,
+        }),
+    };
+}
+
+/// Where the marker of a pon or a kakan goes: before the called tile, at the
+/// position of the discarder counted from the left.
+fn pon_position(from: usize) -> usize {
+    3 - from
+}
+
+/// As [`pon_position`] for a daiminkan, whose tile from the right goes last.
+fn daiminkan_position(from: usize) -> usize {
+    [0, 3, 1, 0][from]
+}
+
+/// Writes a meld as Tenhou does: `c` before the called tile of a chi, `p` and
+/// `m` before the called tile of a pon and a daiminkan, `k` before the added
+/// tile of a kakan (followed by the called one), and `a` before the last tile
+/// of an ankan.
+pub fn meld_string(meld: &Meld, added: Option<Tile>) -> String {
+    let mut own = meld.tiles.clone();
+    for t in meld.called.iter().chain(added.iter()) {
+        let i = own.iter().position(|o| o == t).unwrap();
+        own.remove(i);
+    }
+
+    let mut parts = own
+        .iter()
+        .map(|&t| tile_code(t).to_string())
+        .collect::<Vec<_>>();
+    let called = meld.called.map(tile_code);
+
+    match meld.kind {
+        MeldKind::Chi => parts.insert(0, format!("c{}", called.unwrap())),
+        MeldKind::Pon => parts.insert(pon_position(meld.from), format!("p{}", called.unwrap())),
+        MeldKind::Daiminkan => parts.insert(
+            daiminkan_position(meld.from),
+            format!("m{}", called.unwrap()),
+        ),
+        MeldKind::Kakan => {
+            let at = pon_position(meld.from);
+            parts.insert(at, called.unwrap().to_string());
+            parts.insert(at, format!("k{}", tile_code(added.unwrap())));
+        }
+        MeldKind::Ankan => {
+            let last = parts.pop().unwrap();
+            parts.push(format!("a{}", last));
+        }
+    }
+
+    return parts.concat();
+}
+
+/// Reads a meld written by [`meld_string`], with the added tile of a kakan.
+pub fn parse_meld(s: &str) -> Result<(Meld, Option<Tile>), TenhouError> {
+    let invalid = || TenhouError::Invalid(s.to_string());
+    let mut tiles = vec![];
+    let mut marker = None;
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        if let Some(c) = rest.chars().next().filter(char::is_ascii_lowercase) {
+            if marker.is_some() {
+                return Err(invalid());
+            }
+
+            marker = Some((tiles.len(), c));
+            rest = &rest[1..];
+            continue;
+        }
+
+        let tile = rest
+            .get(..2)
+            .and_then(|c| c.parse::<u8>().ok())
+            .and_then(tile_from_code)
+            .ok_or_else(invalid)?;
+        tiles.push(tile);
+        rest = &rest[2..];
+    }
+
+    let (at, c) = marker.ok_or_else(invalid)?;
+    let mut sorted = tiles.clone();
+    sorted.sort();
+
+    let same_kind = sorted.iter().all(|t| t.kind() == sorted[0].kind());
+    let sequence = !sorted[0].is_jihai()
+        && sorted
+            .windows(2)
+            .all(|w| w[1].kind() == w[0].kind() + 1 && w[1].suit() == w[0].suit());
+
+    let (kind, called, added, from) = match (c, tiles.len()) {
+        ('c', 3) if at == 0 && sequence => (MeldKind::Chi, Some(tiles[0]), None, 3),
+        ('p', 3) if same_kind && at < 3 => (MeldKind::Pon, Some(tiles[at]), None, 3 - at),
+        ('m', 4) if same_kind && matches!(at, 0 | 1 | 3) => {
+            (MeldKind::Daiminkan, Some(tiles[at]), None, [3, 2, 0, 1][at])
+        }
+        ('k', 4) if same_kind && at < 3 => (
+            MeldKind::Kakan,
+            Some(tiles[at + 1]),
+            Some(tiles[at]),
+            3 - at,
+        ),
+        ('a', 4) if same_kind && at == 3 => (MeldKind::Ankan, None, None, 0),
+        _ => return Err(invalid()),
+    };
+
+    let meld = Meld {
+        kind,
+        tiles: sorted,
+        called,
+        from,
+    };
+
+    return Ok((meld, added));
+}
+
+fn parse_outcome(value: &Value) -> Result<Outcome, TenhouError> {
+    let entries = array(value)?;
+    let name = entries
+        .first()
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid(value))?
+        .to_string();
+    let deltas = |v: &Value| -> Result<Vec<i32>, TenhouError> {
+        Ok(numbers(v)?.iter().map(|&d| d as i32).collect())
+    };
+
+    if name != "和了" {
+        return Ok(Outcome {
+            deltas: entries.get(1).map_or(Ok(vec![]), deltas)?,
+            name,
+            agari: vec![],
+        });
+    }
+
+    let mut agari = vec![];
+
+    for pair in entries[1..].chunks(2) {
+        let [d, info] = pair else {
+            return Err(invalid(value));
+        };
+        let info = array(info)?;
+        let seat = |i: usize| {
+            info.get(i)
+                .and_then(Value::as_u64)
+                .map(|s| s as usize)
+                .ok_or_else(|| invalid(value))
+        };
+
+        agari.push(AgariInfo {
+            seat: seat(0)?,
+            from: seat(1)?,
+            pao: seat(2)?,
+            deltas: deltas(d)?,
+            text: info.get(3).and_then(Value::as_str).unwrap_or("").into(),
+            yaku: info[4.min(info.len())..]
+                .iter()
+                .map(|y| y.as_str().unwrap_or("").into())
+                .collect(),
+        });
+    }
+
+    return Ok(Outcome {
+        name,
+        deltas: vec![],
+        agari,
+    });
+}
+
+fn outcome(round: &Round) -> Outcome {
+    let Some(end) = round.result() else {
+        return Outcome {
+            name: String::new(),
+            deltas: vec![],
+            agari: vec![],
+        };
+    };
+    let settlement = settle(round).unwrap();
+    let n = round.player_count();
+    let dealer = round.dealer();
+
+    let name = match end {
+        RoundEnd::Tsumo(_) | RoundEnd::Ron(_) => "和了",
+        RoundEnd::ExhaustiveDraw {
+            tenpai,
+            nagashi_mangan,
+        } => {
+            if !nagashi_mangan.is_empty() {
+                "流し満貫"
+            } else if tenpai.iter().all(|&t| t) {
+                "全員聴牌"
+            } else if tenpai.iter().all(|&t| !t) {
+                "全員不聴"
+            } else {
+                "流局"
+            }
+        }
+        RoundEnd::Abortive(AbortiveDraw::KyuushuKyuuhai { .. }) => "九種九牌",
+        RoundEnd::Abortive(AbortiveDraw::SuufonRenda) => "四風連打",
+        RoundEnd::Abortive(AbortiveDraw::SuuchaRiichi) => "四家立直",
+        RoundEnd::Abortive(AbortiveDraw::SuukanSanra) => "四槓散了",
+        RoundEnd::Abortive(AbortiveDraw::Sanchahou) => "三家和了",
+    };
+
+    let agari = match end {
+        RoundEnd::Tsumo(win) => vec![agari_info(
+            win,
+            &settlement.agari[0],
+            dealer,
+            settlement.deltas.clone(),
+        )],
+        RoundEnd::Ron(wins) => wins
+            .iter()
+            .zip(settlement.agari.iter())
+            .enumerate()
+            .map(|(i, (win, agari))| {
+                let mut deltas = vec![0; n];
+                let points = score::ron_payment(agari.base_points(), win.seat == dealer) as i32
+                    + round.honba() as i32 * 300;
+
+                deltas[win.seat] += points;
+                deltas[win.from.unwrap()] -= points;
+                if i == 0 {
+                    deltas[win.seat] += round.riichi_sticks() as i32 * 1000;
+                }
+
+                agari_info(win, agari, dealer, deltas)
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    return Outcome {
+        name: name.into(),
+        deltas: if agari.is_empty() {
+            settlement.deltas
+        } else {
+            vec![]
+        },
+        agari,
+    };
+}
+
+fn agari_info(win: &Win, agari: &yaku::Agari, dealer: usize, deltas: Vec<i32>) -> AgariInfo {
+    let base = agari.base_points();
+    let is_dealer = win.seat == dealer;
+    let points = if win.is_tsumo() {
+        let (non_dealer, dealer_pays) = score::tsumo_payments(base, is_dealer);
+        if is_dealer {
+            format!("{}点∀", non_dealer)
+        } else {
+            format!("{}-{}点", non_dealer, dealer_pays)
+        }
+    } else {
+        format!("{}点", score::ron_payment(base, is_dealer))
+    };
+    let text = match agari.limit() {
+        None => format!("{}符{}飜{}", agari.fu, agari.han, points),
+        Some(Limit::Mangan) => format!("満貫{}", points),
+        Some(Limit::Haneman) => format!("跳満{}", points),
+        Some(Limit::Baiman) => format!("倍満{}", points),
+        Some(Limit::Sanbaiman) => format!("三倍満{}", points),
+        Some(Limit::KazoeYakuman | Limit::Yakuman(_)) => format!("役満{}", points),
+    };
+
+    let closed = win.melds.iter().all(|m| !m.is_open());
+    let mut yaku = agari
+        .yaku
+        .iter()
+        .map(|&y| {
+            if y.is_yakuman() {
+                format!("{}(役満)", yaku_name(y, win))
+            } else {
+                format!("{}({}飜)", yaku_name(y, win), y.han(closed))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if agari.yakuman == 0 {
+        for (name, han) in [
+            ("ドラ", agari.dora),
+            ("赤ドラ", agari.aka_dora),
+            ("裏ドラ", agari.ura_dora),
+        ] {
+            if han > 0 || (name == "裏ドラ" && win.riichi) {
+                yaku.push(format!("{}({}飜)", name, han));
+            }
+        }
+    }
+
+    return AgariInfo {
+        seat: win.seat,
+        from: win.from.unwrap_or(win.seat),
+        pao: win.seat,
+        deltas,
+        text,
+        yaku,
+    };
+}
+
+fn yaku_name(yaku: Yaku, win: &Win) -> String {
+    let wind = |kind: usize| ["東", "南", "西", "北"][kind - 27];
+
+    let name = match yaku {
+        Yaku::Riichi => "立直",
+        Yaku::DoubleRiichi => "両立直",
+        Yaku::Ippatsu => "一発",
+        Yaku::MenzenTsumo => "門前清自摸和",
+        Yaku::Pinfu => "平和",
+        Yaku::Tanyao => "断幺九",
+        Yaku::Iipeikou => "一盃口",
+        Yaku::Haku => "役牌 白",
+        Yaku::Hatsu => "役牌 發",
+        Yaku::Chun => "役牌 中",
+        Yaku::SeatWind => return format!("自風 {}", wind(win.seat_wind)),
+        Yaku::RoundWind => return format!("場風 {}", wind(win.round_wind)),
+        Yaku::Haitei => "海底摸月",
+        Yaku::Houtei => "河底撈魚",
+        Yaku::Rinshan => "嶺上開花",
+        Yaku::Chankan => "槍槓",
+        Yaku::Chanta => "混全帯幺九",
+        Yaku::Ittsu => "一気通貫",
+        Yaku::SanshokuDoujun => "三色同順",
+        Yaku::SanshokuDoukou => "三色同刻",
+        Yaku::Toitoi => "対々和",
+        Yaku::Sanankou => "三暗刻",
+        Yaku::Sankantsu => "三槓子",
+        Yaku::Chiitoitsu => "七対子",
+        Yaku::Shousangen => "小三元",
+        Yaku::Honroutou => "混老頭",
+        Yaku::Honitsu => "混一色",
+        Yaku::Junchan => "純全帯幺九",
+        Yaku::Ryanpeikou => "二盃口",
+        Yaku::Chinitsu => "清一色",
+        Yaku::Tenhou => "天和",
+        Yaku::Chiihou => "地和",
+        Yaku::Kokushimusou => "国士無双",
+        Yaku::Suuankou => "四暗刻",
+        Yaku::Daisangen => "大三元",
+        Yaku::Tsuuiisou => "字一色",
+        Yaku::Ryuuiisou => "緑一色",
+        Yaku::Chinroutou => "清老頭",
+        Yaku::Shousuushii => "小四喜",
+        Yaku::Daisuushii => "大四喜",
+        Yaku::Suukantsu => "四槓子",
+        Yaku::Chuurenpoutou => "九蓮宝燈",
+    };
+
+    return name.to_string();
+}
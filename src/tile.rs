@@ -0,0 +1,188 @@
+use crate::shanten::Hand;
+use alloc::{string::String, vec, vec::Vec};
+use core::{fmt, str::FromStr};
+
+/// A physical tile: one of the 34 kinds in the same order as [`Hand`]'s
+/// indices, plus whether it is a red five.
+#[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tile {
+    kind: u8,
+    red: bool,
+}
+
+impl Tile {
+    pub const fn new(kind: usize) -> Self {
+        assert!(kind < 34);
+
+        Self {
+            kind: kind as u8,
+            red: false,
+        }
+    }
+
+    pub const fn red(kind: usize) -> Self {
+        assert!(kind < 27 && kind % 9 == 4);
+
+        Self {
+            kind: kind as u8,
+            red: true,
+        }
+    }
+
+    pub const fn kind(self) -> usize {
+        self.kind as usize
+    }
+
+    pub const fn is_red(self) -> bool {
+        self.red
+    }
+
+    pub const fn is_jihai(self) -> bool {
+        self.kind >= 27
+    }
+
+    pub const fn is_yaochuu(self) -> bool {
+        self.is_jihai() || self.kind.is_multiple_of(9) || self.kind % 9 == 8
+    }
+
+    /// 1-9 for suited tiles, 1-7 for honors.
+    pub const fn number(self) -> u8 {
+        if self.is_jihai() {
+            self.kind - 26
+        } else {
+            self.kind % 9 + 1
+        }
+    }
+
+    pub const fn suit(self) -> char {
+        match self.kind / 9 {
+            0 => 'm',
+            1 => 'p',
+            2 => 's',
+            _ => 'z',
+        }
+    }
+}
+
+/// The dora indicated by `kind`. In sanma 1m indicates 9m, since 2m-8m are
+/// not used.
+pub const fn dora_from_indicator(kind: usize, sanma: bool) -> usize {
+    if kind < 27 {
+        if sanma && kind == 0 {
+            8
+        } else if kind % 9 == 8 {
+            kind - 8
+        } else {
+            kind + 1
+        }
+    } else if kind < 31 {
+        27 + (kind - 27 + 1) % 4
+    } else {
+        31 + (kind - 31 + 1) % 3
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.red {
+            write!(f, "0{}", self.suit())
+        } else {
+            write!(f, "{}{}", self.number(), self.suit())
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ParseTileError;
+
+impl fmt::Display for ParseTileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid mpsz tile notation")
+    }
+}
+
+impl FromStr for Tile {
+    type Err = ParseTileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_tiles(s)?.as_slice() {
+            &[tile] => Ok(tile),
+            _ => Err(ParseTileError),
+        }
+    }
+}
+
+/// Parses mpsz notation such as `"123m0p55z"` into tiles, keeping their
+/// order and red fives.
+pub fn parse_tiles(s: &str) -> Result<Vec<Tile>, ParseTileError> {
+    let mut tiles = vec![];
+    let mut numbers = vec![];
+
+    for c in s.chars() {
+        if let Some(n) = c.to_digit(10) {
+            numbers.push(n as usize);
+            continue;
+        }
+
+        let offset = match c {
+            'm' => 0,
+            'p' => 9,
+            's' => 18,
+            'z' => 27,
+            _ => continue,
+        };
+
+        for n in numbers.drain(..) {
+            if offset == 27 {
+                if !(1..8).contains(&n) {
+                    return Err(ParseTileError);
+                }
+
+                tiles.push(Tile::new(offset + n - 1));
+            } else if n == 0 {
+                tiles.push(Tile::red(offset + 4));
+            } else {
+                tiles.push(Tile::new(offset + n - 1));
+            }
+        }
+    }
+
+    if !numbers.is_empty() {
+        return Err(ParseTileError);
+    }
+
+    return Ok(tiles);
+}
+
+/// Formats tiles in compact mpsz notation, e.g. `"123m0p55z"`. Tiles are
+/// grouped by consecutive suit in the given order.
+pub fn tiles_to_string(tiles: &[Tile]) -> String {
+    let mut ret = String::new();
+
+    for (i, tile) in tiles.iter().enumerate() {
+        if tile.is_red() {
+            ret.push('0');
+        } else {
+            ret.push(char::from(b'0' + tile.number()));
+        }
+
+        if tiles.get(i + 1).map(|t| t.suit()) != Some(tile.suit()) {
+            ret.push(tile.suit());
+        }
+    }
+
+    return ret;
+}
+
+impl FromIterator<Tile> for Hand {
+    fn from_iter<I: IntoIterator<Item = Tile>>(iter: I) -> Self {
+        let mut hand = Hand::default();
+
+        for tile in iter {
+            hand[tile.kind()] += 1;
+        }
+
+        return hand;
+    }
+}
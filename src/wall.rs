@@ -0,0 +1,214 @@
+use crate::rng::Rng;
+use crate::shanten::Hand;
+use crate::tile::{Tile, dora_from_indicator};
+use alloc::{vec, vec::Vec};
+
+pub const DEAD_WALL_SIZE: usize = 14;
+pub const MAX_KAN_COUNT: usize = 4;
+pub const MAX_DORA_INDICATOR_COUNT: usize = 5;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WallConfig {
+    /// Three-player mahjong: 2m-8m are removed, leaving 108 tiles.
+    pub sanma: bool,
+    /// Number of red fives in manzu, pinzu and souzu.
+    pub red_fives: [u8; 3],
+}
+
+impl Default for WallConfig {
+    fn default() -> Self {
+        Self {
+            sanma: false,
+            red_fives: [1, 1, 1],
+        }
+    }
+}
+
+impl WallConfig {
+    pub fn players(&self) -> usize {
+        if self.sanma { 3 } else { 4 }
+    }
+
+    pub fn uses_kind(&self, kind: usize) -> bool {
+        !(self.sanma && (1..8).contains(&kind))
+    }
+
+    /// Every tile of the set in kind order, red fives first.
+    pub fn tiles(&self) -> Vec<Tile> {
+        let mut tiles = vec![];
+
+        for kind in (0..34).filter(|&k| self.uses_kind(k)) {
+            let red_count = if kind < 27 && kind % 9 == 4 {
+                self.red_fives[kind / 9].min(4) as usize
+            } else {
+                0
+            };
+
+            for i in 0..4 {
+                if i < red_count {
+                    tiles.push(Tile::red(kind));
+                } else {
+                    tiles.push(Tile::new(kind));
+                }
+            }
+        }
+
+        return tiles;
+    }
+}
+
+/// The tiles of one round. Draws are taken from the front; the last
+/// [`DEAD_WALL_SIZE`] tiles are the dead wall, laid out as four rinshan tiles,
+/// five dora indicators and five ura dora indicators. Each kan moves the end of
+/// the live wall back by one so that the dead wall keeps its size.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wall {
+    config: WallConfig,
+    tiles: Vec<Tile>,
+    drawn: usize,
+    rinshan_drawn: usize,
+    dora_revealed: usize,
+}
+
+impl Wall {
+    pub fn new(config: WallConfig, seed: u64) -> Self {
+        let mut tiles = config.tiles();
+        Rng::new(seed).shuffle(&mut tiles);
+
+        return Self::from_tiles_unchecked(config, tiles);
+    }
+
+    /// Uses a fixed arrangement, e.g. one restored from a log. Returns `None`
+    /// unless `tiles` is a permutation of `config.tiles()`.
+    pub fn from_tiles(config: WallConfig, tiles: Vec<Tile>) -> Option<Self> {
+        let mut expected = config.tiles();
+        let mut actual = tiles.clone();
+        expected.sort();
+        actual.sort();
+
+        if expected != actual {
+            return None;
+        }
+
+        return Some(Self::from_tiles_unchecked(config, tiles));
+    }
+
+    fn from_tiles_unchecked(config: WallConfig, tiles: Vec<Tile>) -> Self {
+        Self {
+            config,
+            tiles,
+            drawn: 0,
+            rinshan_drawn: 0,
+            dora_revealed: 1,
+        }
+    }
+
+    pub fn config(&self) -> &WallConfig {
+        &self.config
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    fn dead_wall(&self) -> &[Tile] {
+        &self.tiles[self.tiles.len() - DEAD_WALL_SIZE..]
+    }
+
+    /// Deals 13 tiles to each player, dealer first, four at a time for three
+    /// rounds and then one each. Must be called before any other draw.
+    pub fn deal_tiles(&mut self) -> Vec<Vec<Tile>> {
+        assert_eq!(self.drawn, 0);
+
+        let players = self.config.players();
+        let mut hands = vec![vec![]; players];
+
+        for _ in 0..3 {
+            for hand in hands.iter_mut() {
+                for _ in 0..4 {
+                    hand.push(self.draw().unwrap());
+                }
+            }
+        }
+
+        for hand in hands.iter_mut() {
+            hand.push(self.draw().unwrap());
+        }
+
+        return hands;
+    }
+
+    pub fn deal(&mut self) -> Vec<Hand> {
+        self.deal_tiles()
+            .into_iter()
+            .map(|tiles| tiles.into_iter().collect())
+            .collect()
+    }
+
+    /// Number of tiles that can still be drawn from the live wall.
+    pub fn remaining(&self) -> usize {
+        self.tiles.len() - DEAD_WALL_SIZE - self.drawn - self.rinshan_drawn
+    }
+
+    pub fn draw(&mut self) -> Option<Tile> {
+        if self.remaining() == 0 {
+            return None;
+        }
+
+        self.drawn += 1;
+
+        return Some(self.tiles[self.drawn - 1]);
+    }
+
+    pub fn kan_count(&self) -> usize {
+        self.rinshan_drawn
+    }
+
+    pub fn draw_rinshan(&mut self) -> Option<Tile> {
+        if self.rinshan_drawn == MAX_KAN_COUNT || self.remaining() == 0 {
+            return None;
+        }
+
+        self.rinshan_drawn += 1;
+
+        return Some(self.dead_wall()[self.rinshan_drawn - 1]);
+    }
+
+    /// Flips the next dora indicator after a kan.
+    pub fn reveal_dora(&mut self) -> Option<Tile> {
+        if self.dora_revealed == MAX_DORA_INDICATOR_COUNT {
+            return None;
+        }
+
+        self.dora_revealed += 1;
+
+        return Some(self.dora_indicators()[self.dora_revealed - 1]);
+    }
+
+    pub fn dora_indicators(&self) -> &[Tile] {
+        &self.dead_wall()[MAX_KAN_COUNT..MAX_KAN_COUNT + self.dora_revealed]
+    }
+
+    pub fn ura_dora_indicators(&self) -> &[Tile] {
+        let start = MAX_KAN_COUNT + MAX_DORA_INDICATOR_COUNT;
+
+        &self.dead_wall()[start..start + self.dora_revealed]
+    }
+
+    /// Dora kinds of the revealed indicators, one entry per indicator.
+    pub fn dora(&self) -> Vec<usize> {
+        self.dora_indicators()
+            .iter()
+            .map(|t| dora_from_indicator(t.kind(), self.config.sanma))
+            .collect()
+    }
+
+    pub fn ura_dora(&self) -> Vec<usize> {
+        self.ura_dora_indicators()
+            .iter()
+            .map(|t| dora_from_indicator(t.kind(), self.config.sanma))
+            .collect()
+    }
+}
@@ -0,0 +1,707 @@
+//! Yaku, fu and dora of a winning hand.
+
+use crate::meld::MeldKind;
+use crate::round::Win;
+use crate::score::{self, Limit};
+use crate::shanten::Hand;
+use alloc::{vec, vec::Vec};
+
+const HAKU: usize = 31;
+const HATSU: usize = 32;
+const CHUN: usize = 33;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Yaku {
+    Riichi,
+    DoubleRiichi,
+    Ippatsu,
+    MenzenTsumo,
+    Pinfu,
+    Tanyao,
+    Iipeikou,
+    Haku,
+    Hatsu,
+    Chun,
+    SeatWind,
+    RoundWind,
+    Haitei,
+    Houtei,
+    Rinshan,
+    Chankan,
+    Chanta,
+    Ittsu,
+    SanshokuDoujun,
+    SanshokuDoukou,
+    Toitoi,
+    Sanankou,
+    Sankantsu,
+    Chiitoitsu,
+    Shousangen,
+    Honroutou,
+    Honitsu,
+    Junchan,
+    Ryanpeikou,
+    Chinitsu,
+    Tenhou,
+    Chiihou,
+    Kokushimusou,
+    Suuankou,
+    Daisangen,
+    Tsuuiisou,
+    Ryuuiisou,
+    Chinroutou,
+    Shousuushii,
+    Daisuushii,
+    Suukantsu,
+    Chuurenpoutou,
+}
+
+impl Yaku {
+    pub fn is_yakuman(self) -> bool {
+        self >= Yaku::Tenhou
+    }
+
+    /// Han of the yaku, 13 for yakuman.
+    pub fn han(self, closed: bool) -> u8 {
+        let open_penalty = if closed { 0 } else { 1 };
+
+        match self {
+            Yaku::Riichi
+            | Yaku::Ippatsu
+            | Yaku::MenzenTsumo
+            | Yaku::Pinfu
+            | Yaku::Tanyao
+            | Yaku::Iipeikou
+            | Yaku::Haku
+            | Yaku::Hatsu
+            | Yaku::Chun
+            | Yaku::SeatWind
+            | Yaku::RoundWind
+            | Yaku::Haitei
+            | Yaku::Houtei
+            | Yaku::Rinshan
+            | Yaku::Chankan => 1,
+            Yaku::Chanta | Yaku::Ittsu | Yaku::SanshokuDoujun => 2 - open_penalty,
+            Yaku::DoubleRiichi
+            | Yaku::SanshokuDoukou
+            | Yaku::Toitoi
+            | Yaku::Sanankou
+            | Yaku::Sankantsu
+            | Yaku::Chiitoitsu
+            | Yaku::Shousangen
+            | Yaku::Honroutou => 2,
+            Yaku::Honitsu | Yaku::Junchan => 3 - open_penalty,
+            Yaku::Ryanpeikou => 3,
+            Yaku::Chinitsu => 6 - open_penalty,
+            _ => 13,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Wait {
+    Ryanmen,
+    Kanchan,
+    Penchan,
+    Shanpon,
+    Tanki,
+}
+
+/// The best scoring interpretation of a winning hand.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Agari {
+    pub yaku: Vec<Yaku>,
+    /// Total han including dora; 0 for yakuman.
+    pub han: u8,
+    pub fu: u8,
+    pub dora: u8,
+    pub aka_dora: u8,
+    pub ura_dora: u8,
+    pub yakuman: u8,
+    pub wait: Wait,
+}
+
+impl Agari {
+    pub fn limit(&self) -> Option<Limit> {
+        if self.yakuman > 0 {
+            Some(Limit::Yakuman(self.yakuman))
+        } else {
+            score::limit(self.han, self.fu)
+        }
+    }
+
+    pub fn base_points(&self) -> u32 {
+        match self.limit() {
+            Some(limit) => limit.base_points(),
+            None => score::base_points(self.han, self.fu),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum GroupKind {
+    Shuntsu,
+    Koutsu,
+    Kantsu,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+struct Group {
+    kind: GroupKind,
+    base: usize,
+    /// Called, or a koutsu completed by ron.
+    open: bool,
+    /// Part of the concealed hand rather than a declared meld.
+    concealed: bool,
+}
+
+impl Group {
+    fn is_koutsu(&self) -> bool {
+        self.kind != GroupKind::Shuntsu
+    }
+
+    fn has_yaochuu(&self) -> bool {
+        if self.kind == GroupKind::Shuntsu {
+            self.base.is_multiple_of(9) || self.base % 9 == 6
+        } else {
+            is_yaochuu(self.base)
+        }
+    }
+
+    fn kinds(&self) -> [usize; 3] {
+        if self.kind == GroupKind::Shuntsu {
+            [self.base, self.base + 1, self.base + 2]
+        } else {
+            [self.base; 3]
+        }
+    }
+}
+
+fn is_yaochuu(kind: usize) -> bool {
+    kind >= 27 || kind.is_multiple_of(9) || kind % 9 == 8
+}
+
+/// Splits `counts` into mentsu in every possible way.
+fn decompose(
+    counts: &mut [u8; 34],
+    start: usize,
+    groups: &mut Vec<Group>,
+    out: &mut Vec<Vec<Group>>,
+) {
+    let Some(i) = (start..34).find(|&i| counts[i] > 0) else {
+        out.push(groups.clone());
+        return;
+    };
+
+    if counts[i] >= 3 {
+        counts[i] -= 3;
+        groups.push(Group {
+            kind: GroupKind::Koutsu,
+            base: i,
+            open: false,
+            concealed: true,
+        });
+        decompose(counts, i, groups, out);
+        groups.pop();
+        counts[i] += 3;
+    }
+
+    if i < 27 && i % 9 <= 6 && counts[i + 1] > 0 && counts[i + 2] > 0 {
+        counts[i] -= 1;
+        counts[i + 1] -= 1;
+        counts[i + 2] -= 1;
+        groups.push(Group {
+            kind: GroupKind::Shuntsu,
+            base: i,
+            open: false,
+            concealed: true,
+        });
+        decompose(counts, i, groups, out);
+        groups.pop();
+        counts[i] += 1;
+        counts[i + 1] += 1;
+        counts[i + 2] += 1;
+    }
+}
+
+struct Context<'a> {
+    win: &'a Win,
+    closed: bool,
+    counts: [u8; 34],
+}
+
+impl Context<'_> {
+    fn is_yakuhai(&self, kind: usize) -> bool {
+        kind >= HAKU || kind == self.win.seat_wind || kind == self.win.round_wind
+    }
+
+    fn situational(&self) -> Vec<Yaku> {
+        let win = self.win;
+        let mut yaku = vec![];
+
+        if win.double_riichi {
+            yaku.push(Yaku::DoubleRiichi);
+        } else if win.riichi {
+            yaku.push(Yaku::Riichi);
+        }
+
+        let flags = [
+            (win.ippatsu, Yaku::Ippatsu),
+            (self.closed && win.is_tsumo(), Yaku::MenzenTsumo),
+            (win.haitei, Yaku::Haitei),
+            (win.houtei, Yaku::Houtei),
+            (win.rinshan, Yaku::Rinshan),
+            (win.chankan, Yaku::Chankan),
+        ];
+
+        for (flag, y) in flags {
+            if flag {
+                yaku.push(y);
+            }
+        }
+
+        return yaku;
+    }
+
+    /// Yaku that only depend on which tiles the hand contains.
+    fn color_yaku(&self, yaku: &mut Vec<Yaku>) {
+        let counts = &self.counts;
+        let has = |mut range: core::ops::Range<usize>| range.any(|k| counts[k] > 0);
+        let suits = (0..3).filter(|&s| has(s * 9..s * 9 + 9)).count();
+        let honors = has(27..34);
+
+        if (0..34).all(|k| counts[k] == 0 || !is_yaochuu(k)) {
+            yaku.push(Yaku::Tanyao);
+        }
+
+        // Only terminals or only honors would already be a yakuman.
+        if (0..34).all(|k| counts[k] == 0 || is_yaochuu(k)) {
+            yaku.push(Yaku::Honroutou);
+        }
+
+        if suits == 1 {
+            yaku.push(if honors {
+                Yaku::Honitsu
+            } else {
+                Yaku::Chinitsu
+            });
+        }
+    }
+
+    fn yakuman_by_tiles(&self, yaku: &mut Vec<Yaku>) {
+        let counts = &self.counts;
+
+        if (0..27).all(|k| counts[k] == 0) {
+            yaku.push(Yaku::Tsuuiisou);
+        }
+
+        if (0..34).all(|k| counts[k] == 0 || [19, 20, 21, 23, 25, HATSU].contains(&k)) {
+            yaku.push(Yaku::Ryuuiisou);
+        }
+
+        if (0..34).all(|k| counts[k] == 0 || (k < 27 && is_yaochuu(k))) {
+            yaku.push(Yaku::Chinroutou);
+        }
+    }
+
+    fn first_draw(&self, yaku: &mut Vec<Yaku>) {
+        if self.win.tenhou {
+            yaku.push(Yaku::Tenhou);
+        }
+
+        if self.win.chiihou {
+            yaku.push(Yaku::Chiihou);
+        }
+    }
+
+    fn evaluate_kokushi(&self) -> Option<Agari> {
+        let mut yaku = vec![];
+        self.first_draw(&mut yaku);
+        yaku.push(Yaku::Kokushimusou);
+
+        return Some(self.finish(yaku, 0, Wait::Tanki));
+    }
+
+    fn evaluate_chiitoitsu(&self) -> Option<Agari> {
+        let mut yakuman = vec![];
+        self.first_draw(&mut yakuman);
+        self.yakuman_by_tiles(&mut yakuman);
+
+        if !yakuman.is_empty() {
+            return Some(self.finish(yakuman, 25, Wait::Tanki));
+        }
+
+        let mut yaku = self.situational();
+        yaku.push(Yaku::Chiitoitsu);
+        self.color_yaku(&mut yaku);
+
+        return Some(self.finish(yaku, 25, Wait::Tanki));
+    }
+
+    fn evaluate_standard(&self, groups: &[Group], pair: usize, wait: Wait) -> Option<Agari> {
+        let win = self.win;
+        let shuntsu = groups
+            .iter()
+            .filter(|g| g.kind == GroupKind::Shuntsu)
+            .collect::<Vec<_>>();
+        let koutsu = groups.iter().filter(|g| g.is_koutsu()).collect::<Vec<_>>();
+        let kans = groups
+            .iter()
+            .filter(|g| g.kind == GroupKind::Kantsu)
+            .count();
+        let ankou = koutsu.iter().filter(|g| !g.open).count();
+        let dragons = koutsu.iter().filter(|g| g.base >= HAKU).count();
+        let winds = koutsu.iter().filter(|g| (27..31).contains(&g.base)).count();
+
+        let mut yakuman = vec![];
+        self.first_draw(&mut yakuman);
+        self.yakuman_by_tiles(&mut yakuman);
+
+        if ankou == 4 {
+            yakuman.push(Yaku::Suuankou);
+        }
+
+        if dragons == 3 {
+            yakuman.push(Yaku::Daisangen);
+        }
+
+        if winds == 4 {
+            yakuman.push(Yaku::Daisuushii);
+        } else if winds == 3 && (27..31).contains(&pair) {
+            yakuman.push(Yaku::Shousuushii);
+        }
+
+        if kans == 4 {
+            yakuman.push(Yaku::Suukantsu);
+        }
+
+        if self.closed && win.melds.is_empty() && self.is_chuuren() {
+            yakuman.push(Yaku::Chuurenpoutou);
+        }
+
+        if !yakuman.is_empty() {
+            return Some(self.finish(yakuman, 0, wait));
+        }
+
+        let mut yaku = self.situational();
+
+        let pinfu = self.closed
+            && win.melds.is_empty()
+            && shuntsu.len() == 4
+            && !self.is_yakuhai(pair)
+            && wait == Wait::Ryanmen;
+
+        if pinfu {
+            yaku.push(Yaku::Pinfu);
+        }
+
+        if self.closed {
+            let mut peikou = 0;
+            let mut used = [false; 4];
+
+            for i in 0..shuntsu.len() {
+                for j in i + 1..shuntsu.len() {
+                    if !used[i] && !used[j] && shuntsu[i].base == shuntsu[j].base {
+                        used[i] = true;
+                        used[j] = true;
+                        peikou += 1;
+                    }
+                }
+            }
+
+            if peikou == 2 {
+                yaku.push(Yaku::Ryanpeikou);
+            } else if peikou == 1 {
+                yaku.push(Yaku::Iipeikou);
+            }
+        }
+
+        for g in koutsu.iter() {
+            match g.base {
+                HAKU => yaku.push(Yaku::Haku),
+                HATSU => yaku.push(Yaku::Hatsu),
+                CHUN => yaku.push(Yaku::Chun),
+                _ => {}
+            }
+
+            if g.base == win.seat_wind {
+                yaku.push(Yaku::SeatWind);
+            }
+
+            if g.base == win.round_wind {
+                yaku.push(Yaku::RoundWind);
+            }
+        }
+
+        if groups.iter().all(|g| g.has_yaochuu()) && is_yaochuu(pair) && !shuntsu.is_empty() {
+            let honors = pair >= 27 || koutsu.iter().any(|g| g.base >= 27);
+            yaku.push(if honors { Yaku::Chanta } else { Yaku::Junchan });
+        }
+
+        for suit in 0..3 {
+            if [0, 3, 6]
+                .iter()
+                .all(|&n| shuntsu.iter().any(|g| g.base == suit * 9 + n))
+            {
+                yaku.push(Yaku::Ittsu);
+            }
+        }
+
+        for n in 0..9 {
+            if (0..3).all(|suit| shuntsu.iter().any(|g| g.base == suit * 9 + n)) {
+                yaku.push(Yaku::SanshokuDoujun);
+            }
+
+            if (0..3).all(|suit| koutsu.iter().any(|g| g.base == suit * 9 + n)) {
+                yaku.push(Yaku::SanshokuDoukou);
+            }
+        }
+
+        if koutsu.len() == 4 {
+            yaku.push(Yaku::Toitoi);
+        }
+
+        if ankou == 3 {
+            yaku.push(Yaku::Sanankou);
+        }
+
+        if kans == 3 {
+            yaku.push(Yaku::Sankantsu);
+        }
+
+        if dragons == 2 && pair >= HAKU {
+            yaku.push(Yaku::Shousangen);
+        }
+
+        self.color_yaku(&mut yaku);
+
+        let fu = self.fu(groups, pair, wait, pinfu);
+
+        return Some(self.finish(yaku, fu, wait));
+    }
+
+    fn is_chuuren(&self) -> bool {
+        let counts = &self.counts;
+
+        (0..3).any(|suit| {
+            let suited = &counts[suit * 9..suit * 9 + 9];
+            let total = suited.iter().map(|&c| c as usize).sum::<usize>();
+
+            total == 14
+                && suited
+                    .iter()
+                    .enumerate()
+                    .all(|(n, &c)| c >= if n == 0 || n == 8 { 3 } else { 1 })
+        })
+    }
+
+    fn fu(&self, groups: &[Group], pair: usize, wait: Wait, pinfu: bool) -> u8 {
+        let win = self.win;
+
+        if pinfu && win.is_tsumo() {
+            return 20;
+        }
+
+        let mut fu: u8 = 20;
+
+        if self.closed && !win.is_tsumo() {
+            fu += 10;
+        }
+
+        if win.is_tsumo() {
+            fu += 2;
+        }
+
+        for g in groups.iter().filter(|g| g.is_koutsu()) {
+            let mut f = 2;
+
+            if is_yaochuu(g.base) {
+                f *= 2;
+            }
+
+            if !g.open {
+                f *= 2;
+            }
+
+            if g.kind == GroupKind::Kantsu {
+                f *= 4;
+            }
+
+            fu += f;
+        }
+
+        if pair >= HAKU {
+            fu += 2;
+        }
+
+        if pair == win.seat_wind {
+            fu += 2;
+        }
+
+        if pair == win.round_wind {
+            fu += 2;
+        }
+
+        if matches!(wait, Wait::Kanchan | Wait::Penchan | Wait::Tanki) {
+            fu += 2;
+        }
+
+        let fu = fu.div_ceil(10) * 10;
+
+        if fu == 20 && !self.closed {
+            return 30;
+        }
+
+        return fu;
+    }
+
+    fn finish(&self, yaku: Vec<Yaku>, fu: u8, wait: Wait) -> Agari {
+        let win = self.win;
+        let yakuman = yaku.iter().filter(|y| y.is_yakuman()).count() as u8;
+
+        if yakuman > 0 {
+            return Agari {
+                yaku: yaku.into_iter().filter(|y| y.is_yakuman()).collect(),
+                han: 0,
+                fu,
+                dora: 0,
+                aka_dora: 0,
+                ura_dora: 0,
+                yakuman,
+                wait,
+            };
+        }
+
+        let count_of =
+            |kinds: &[usize]| -> u8 { kinds.iter().map(|&k| self.counts[k]).sum::<u8>() };
+        let dora = count_of(&win.dora);
+        let ura_dora = count_of(&win.ura_dora);
+        let aka_dora = win
+            .concealed
+            .iter()
+            .chain(win.melds.iter().flat_map(|m| m.tiles.iter()))
+            .filter(|t| t.is_red())
+            .count() as u8;
+
+        let han = yaku.iter().map(|y| y.han(self.closed)).sum::<u8>() + dora + ura_dora + aka_dora;
+
+        return Agari {
+            yaku,
+            han,
+            fu,
+            dora,
+            aka_dora,
+            ura_dora,
+            yakuman: 0,
+            wait,
+        };
+    }
+}
+
+/// Scores a winning hand, or returns `None` if it is not complete or has no
+/// yaku. Dora alone do not count as a yaku. When the hand can be read in
+/// several ways the one worth the most points is used.
+pub fn evaluate(win: &Win) -> Option<Agari> {
+    let concealed: Hand = win.concealed.iter().copied().collect();
+    let mut counts = concealed.counts();
+    let concealed_counts = counts;
+
+    for meld in win.melds.iter() {
+        for tile in meld.tiles.iter() {
+            counts[tile.kind()] += 1;
+        }
+    }
+
+    let context = Context {
+        win,
+        closed: win.melds.iter().all(|m| !m.is_open()),
+        counts,
+    };
+
+    let mut candidates = vec![];
+
+    if win.melds.is_empty() && concealed.count() == 14 {
+        if concealed.shanten_kokushimusou() == -1 {
+            candidates.extend(context.evaluate_kokushi());
+        }
+
+        if concealed_counts.iter().filter(|&&c| c == 2).count() == 7 {
+            candidates.extend(context.evaluate_chiitoitsu());
+        }
+    }
+
+    let melds = win
+        .melds
+        .iter()
+        .map(|m| Group {
+            kind: match m.kind {
+                MeldKind::Chi => GroupKind::Shuntsu,
+                MeldKind::Pon => GroupKind::Koutsu,
+                _ => GroupKind::Kantsu,
+            },
+            base: m.base(),
+            open: m.is_open(),
+            concealed: false,
+        })
+        .collect::<Vec<_>>();
+
+    let w = win.tile.kind();
+
+    for pair in 0..34 {
+        if concealed_counts[pair] < 2 {
+            continue;
+        }
+
+        let mut rest = concealed_counts;
+        rest[pair] -= 2;
+
+        let mut decompositions = vec![];
+        decompose(&mut rest, 0, &mut vec![], &mut decompositions);
+
+        for decomposition in decompositions {
+            let mut groups = decomposition;
+            groups.extend_from_slice(&melds);
+
+            // Every place the winning tile could have completed.
+            let mut readings = vec![];
+
+            if pair == w {
+                readings.push((groups.clone(), Wait::Tanki));
+            }
+
+            for (i, g) in groups.iter().enumerate() {
+                if !g.concealed || !g.kinds().contains(&w) {
+                    continue;
+                }
+
+                let mut groups = groups.clone();
+                let wait = if g.is_koutsu() {
+                    groups[i].open = !win.is_tsumo();
+                    Wait::Shanpon
+                } else if w == g.base + 1 {
+                    Wait::Kanchan
+                } else if (w == g.base + 2 && g.base.is_multiple_of(9))
+                    || (w == g.base && g.base % 9 == 6)
+                {
+                    Wait::Penchan
+                } else {
+                    Wait::Ryanmen
+                };
+
+                readings.push((groups, wait));
+            }
+
+            for (groups, wait) in readings {
+                candidates.extend(context.evaluate_standard(&groups, pair, wait));
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|a| !a.yaku.is_empty())
+        .max_by_key(|a| (a.base_points(), a.han, a.fu))
+}
@@ -0,0 +1,127 @@
+#![cfg(any(feature = "std", feature = "precomputed-table"))]
+
+use shanten_count::agent::{
+    Agent, GreedyAgent, Observation, RandomAgent, agent_by_name, play_game, self_play,
+};
+use shanten_count::game::{GameConfig, GameLength};
+use shanten_count::round::{Action, Round, RoundSetup, Rules};
+use shanten_count::wall::{Wall, WallConfig};
+
+mod common;
+
+use common::{QUIET, stacked_wall};
+
+#[test]
+fn legal_actions_are_accepted() {
+    for seed in 0..30 {
+        let setup = RoundSetup {
+            round_wind: 27,
+            dealer: seed as usize % 4,
+            honba: 0,
+            riichi_sticks: 0,
+            scores: vec![25000; 4],
+        };
+        let mut round = Round::new(
+            Rules::default(),
+            Wall::new(WallConfig::default(), seed),
+            setup,
+        );
+        let mut agent = RandomAgent::new(seed);
+
+        while !round.is_over() {
+            let seat = round.waiting_for()[0];
+            let legal = round.legal_actions(seat);
+            assert!(!legal.is_empty());
+
+            for &action in legal.iter() {
+                assert_eq!(round.clone().apply(seat, action), Ok(()), "{:?}", action);
+            }
+
+            for tile in round.player(seat).concealed.clone() {
+                for action in [Action::Discard(tile), Action::Riichi(tile)] {
+                    if !legal.contains(&action) {
+                        assert!(round.clone().apply(seat, action).is_err());
+                    }
+                }
+            }
+
+            let action = agent.act(&Observation::new(&round, seat));
+            round.apply(seat, action).unwrap();
+        }
+    }
+}
+
+#[test]
+fn greedy_takes_the_win() {
+    let wall = stacked_wall(["123m456m789m1122p", QUIET[0], QUIET[1], QUIET[2]], "1p");
+    let setup = RoundSetup {
+        round_wind: 27,
+        dealer: 0,
+        honba: 0,
+        riichi_sticks: 0,
+        scores: vec![25000; 4],
+    };
+    let round = Round::new(Rules::default(), wall, setup);
+    let observation = Observation::new(&round, 0);
+
+    assert!(
+        observation
+            .legal_actions
+            .contains(&Action::Discard(common::tile("1p")))
+    );
+    assert_eq!(GreedyAgent.act(&observation), Action::Tsumo);
+}
+
+#[test]
+fn random_agents_finish_games() {
+    let config = GameConfig {
+        length: GameLength::Tonpuusen,
+        ..GameConfig::default()
+    };
+
+    for seed in 0..5 {
+        let mut agents = (0..4)
+            .map(|i| agent_by_name("random", seed * 4 + i).unwrap())
+            .collect::<Vec<_>>();
+        let game = play_game(config.clone(), &mut agents, seed).unwrap();
+
+        assert!(game.is_over());
+    }
+}
+
+#[test]
+fn self_play_report() {
+    let config = GameConfig {
+        length: GameLength::Tonpuusen,
+        ..GameConfig::default()
+    };
+    let mut agents = ["greedy", "greedy", "random", "random"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| agent_by_name(name, i as u64).unwrap())
+        .collect::<Vec<_>>();
+
+    let report = self_play(&config, &mut agents, 4, 1).unwrap();
+
+    assert_eq!(report.agents.len(), 4);
+    assert!(report.agents.iter().all(|s| s.games == 4));
+    assert!(
+        report
+            .agents
+            .iter()
+            .all(|s| s.rounds == report.agents[0].rounds)
+    );
+    assert_eq!(
+        report.agents.iter().map(|s| s.placement_sum).sum::<usize>(),
+        4 * (1 + 2 + 3 + 4)
+    );
+    assert!(report.agents[0].win_rate() > report.agents[2].win_rate());
+    assert!(
+        report
+            .to_string()
+            .lines()
+            .nth(1)
+            .unwrap()
+            .contains("greedy")
+    );
+}
@@ -0,0 +1,164 @@
+#![cfg(any(feature = "std", feature = "precomputed-table"))]
+
+use shanten_count::analysis::analyze;
+use shanten_count::meld::{Meld, MeldKind, parse_hand};
+use shanten_count::shanten::Hand;
+use shanten_count::tile::parse_tiles;
+
+mod common;
+
+use common::tile;
+
+#[test]
+fn meld_notation() {
+    let (tiles, melds) = parse_hand("23m456p11z[1m32m] (7777s) [505p]").unwrap();
+
+    assert_eq!(tiles, parse_tiles("23m456p11z").unwrap());
+    assert_eq!(
+        melds,
+        vec![
+            Meld {
+                kind: MeldKind::Chi,
+                tiles: parse_tiles("123m").unwrap(),
+                called: Some(tile("1m")),
+                from: 3,
+            },
+            Meld {
+                kind: MeldKind::Ankan,
+                tiles: parse_tiles("7777s").unwrap(),
+                called: None,
+                from: 0,
+            },
+            Meld {
+                kind: MeldKind::Pon,
+                tiles: parse_tiles("550p").unwrap(),
+                called: Some(tile("5p")),
+                from: 3,
+            },
+        ]
+    );
+    assert_eq!(
+        melds.iter().map(|m| m.to_string()).collect::<Vec<_>>(),
+        ["[123m]", "(7777s)", "[550p]"]
+    );
+    assert_eq!("[5555z]".parse::<Meld>().unwrap().kind, MeldKind::Daiminkan);
+
+    // A mark puts the called tile where the discarder sits.
+    let toimen = "[55'0p]".parse::<Meld>().unwrap();
+    assert_eq!((toimen.called, toimen.from), (Some(tile("5p")), 2));
+    let shimocha = "[5555'm]".parse::<Meld>().unwrap();
+    assert_eq!((shimocha.kind, shimocha.from), (MeldKind::Daiminkan, 1));
+
+    let kakan = "[77'7'7s]".parse::<Meld>().unwrap();
+    assert_eq!(
+        kakan,
+        Meld {
+            kind: MeldKind::Kakan,
+            tiles: parse_tiles("7777s").unwrap(),
+            called: Some(tile("7s")),
+            from: 2,
+        }
+    );
+    assert_eq!(kakan.arrangement(), parse_tiles("7777s").unwrap());
+    assert_eq!(kakan.called_position(), Some(1));
+
+    for notation in ["[3'24m]", "[550'p]", "[5'5'55z]", "[555'5'z]", "[5'55z]"] {
+        let meld = notation.parse::<Meld>().unwrap();
+        assert_eq!(
+            meld.to_string().parse::<Meld>().unwrap(),
+            meld,
+            "{}",
+            notation
+        );
+    }
+
+    assert_eq!("[50'5p]".parse::<Meld>().unwrap().to_string(), "[50'5p]");
+    assert_eq!("[5'55z]".parse::<Meld>().unwrap().to_string(), "[555z]");
+
+    for invalid in [
+        "[124m]",
+        "[123z]",
+        "(555z)",
+        "[55z]",
+        "123m [555z",
+        "1m]",
+        "[8m9m1p]",
+        "[3455m']",
+        "['123m]",
+        "[1'2'3m]",
+        // A chi is called from the left only.
+        "[12'3m]",
+        "[123'm]",
+        "[5'55'5z]",
+        "(5'555z)",
+    ] {
+        assert!(
+            parse_hand(invalid).is_err() && invalid.parse::<Meld>().is_err(),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn forms_ukeire_and_waits() {
+    let hand = "19m19p19s1234567z".parse::<Hand>().unwrap();
+    let analysis = analyze(&hand, &[], &[0; 34]);
+
+    assert_eq!(analysis.shanten, 0);
+    assert_eq!(analysis.kokushimusou, Some(0));
+    assert_eq!(analysis.chiitoitsu, Some(6));
+    assert_eq!(analysis.waits.len(), 13);
+    assert_eq!(analysis.ukeire.unwrap().count, 13 * 4 - 13);
+    assert!(analysis.discards.is_empty());
+
+    // The pon holds the other copies of the tanki wait and rules out the
+    // closed forms.
+    let (tiles, melds) = parse_hand("123m456p789s5z [555z]").unwrap();
+    let hand = tiles.into_iter().collect::<Hand>();
+    let analysis = analyze(&hand, &melds, &[0; 34]);
+
+    assert_eq!(analysis.count, 10);
+    assert_eq!((analysis.chiitoitsu, analysis.kokushimusou), (None, None));
+    assert_eq!(analysis.waits, vec![31]);
+    assert_eq!(analysis.ukeire.unwrap().count, 0);
+}
+
+#[test]
+fn discards_best_first() {
+    let hand = "123m456p789s11z5z24m".parse::<Hand>().unwrap();
+    let analysis = analyze(&hand, &[], &[0; 34]);
+    let best = analysis.best_discards();
+
+    assert_eq!(analysis.count, 14);
+    assert!(analysis.ukeire.is_none());
+    assert_eq!(best.len(), 1);
+    assert_eq!((best[0].tile, best[0].shanten, best[0].ukeire), (31, 0, 3));
+    assert_eq!(best[0].waits, vec![2]);
+    assert!(
+        analysis
+            .discards
+            .windows(2)
+            .all(|w| (w[0].shanten, std::cmp::Reverse(w[0].ukeire))
+                <= (w[1].shanten, std::cmp::Reverse(w[1].ukeire)))
+    );
+}
+
+#[test]
+fn discarded_tile_is_not_counted_as_drawable() {
+    let hand = "123p456p789s1z5566m".parse::<Hand>().unwrap();
+    let analysis = analyze(&hand, &[], &[0; 34]);
+    let option = analysis
+        .discards
+        .iter()
+        .find(|d| d.tile == tile("1p").kind())
+        .unwrap();
+
+    // Only three copies of 1p are left to draw after discarding one.
+    let after = "23p456p789s1z5566m".parse::<Hand>().unwrap();
+    let ukeire = after.ukeire(&[0; 34]);
+
+    assert!(option.tiles.contains(&tile("1p").kind()));
+    assert_eq!(option.ukeire, ukeire.count - 1);
+    assert_eq!(option.ukeire, 14);
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+
+use proptest::prelude::*;
+use shanten_count::batch::{Batch, BatchConfig, Evaluation, evaluate};
+use shanten_count::shanten::Hand;
+
+const SMALL: BatchConfig = BatchConfig {
+    threads: 3,
+    chunk_size: 4,
+    max_chunks: 2,
+};
+
+fn hand(kinds: &[usize]) -> Hand {
+    let mut hand = Hand::default();
+
+    for &k in kinds {
+        hand[k] += 1;
+    }
+
+    hand
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn results_in_input_order(
+        hands in prop::collection::vec(prop::sample::subsequence((0..136).collect::<Vec<_>>(), 13), 0..60)
+    ) {
+        let hands = hands
+            .iter()
+            .map(|tiles| hand(&tiles.iter().map(|t| t / 4).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let expected = hands.iter().map(Evaluation::new).collect::<Vec<_>>();
+        let mut batch = evaluate(hands.clone(), SMALL);
+        let results = batch.by_ref().collect::<Vec<_>>();
+
+        prop_assert_eq!(results, expected);
+        prop_assert_eq!(batch.throughput().items, hands.len() as u64);
+    }
+}
+
+#[test]
+fn evaluation() {
+    let tenpai = "123m456p789s1122z".parse::<Hand>().unwrap();
+    let e = Evaluation::new(&tenpai);
+
+    assert_eq!((e.standard, e.chiitoitsu, e.kokushimusou), (0, 4, 8));
+    assert_eq!(e.ukeire.unwrap().tiles, vec![27, 28]);
+
+    let agari = "123m456p789s11122z".parse::<Hand>().unwrap();
+
+    assert_eq!(Evaluation::new(&agari).shanten, -1);
+    assert_eq!(Evaluation::new(&agari).ukeire, None);
+}
+
+#[test]
+fn bounded_read_ahead() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static READ: AtomicUsize = AtomicUsize::new(0);
+
+    let input = (0..).inspect(|_| {
+        READ.fetch_add(1, Ordering::SeqCst);
+    });
+    let mut batch = Batch::new(input, SMALL, |i: u64| i * 2);
+
+    assert_eq!(
+        batch.by_ref().take(10).collect::<Vec<_>>(),
+        (0..20).step_by(2).collect::<Vec<_>>()
+    );
+    // Reading stops `max_chunks` chunks past the last fully consumed one.
+    assert!(READ.load(Ordering::SeqCst) <= 4 * 4);
+
+    // Dropping an unfinished batch stops the workers.
+    drop(batch);
+}
+
+#[test]
+fn worker_panic_reaches_the_consumer() {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let good = "123m456p789s1122z".parse::<Hand>().unwrap();
+    // A fifth copy panics in the shanten table lookup.
+    let bad = "11111m2345678m99p".parse::<Hand>().unwrap();
+    let config = BatchConfig {
+        threads: 2,
+        chunk_size: 1,
+        max_chunks: 4,
+    };
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let result =
+            std::panic::catch_unwind(|| evaluate([good.clone(), bad, good], config).count());
+        sender.send(result.is_err()).unwrap();
+    });
+
+    // Before the panic was forwarded, the consumer waited forever.
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(60)), Ok(true));
+}
@@ -0,0 +1,133 @@
+use shanten_count::chinitsu::{Decomposition, Mentsu, random_tenpai, waits};
+use shanten_count::rng::Rng;
+use shanten_count::shanten::{Hand, SuuhaiHand};
+use shanten_count::yaku::Wait;
+
+fn suuhai(s: &str) -> SuuhaiHand {
+    let mut hand = SuuhaiHand::default();
+
+    for c in s.chars() {
+        hand[c.to_digit(10).unwrap() as usize - 1] += 1;
+    }
+
+    hand
+}
+
+fn wait_tiles(hand: &SuuhaiHand) -> Vec<usize> {
+    waits(hand).iter().map(|w| w.tile).collect()
+}
+
+#[test]
+fn decompositions() {
+    let chuuren = waits(&suuhai("1112345678999"));
+
+    assert_eq!(chuuren.len(), 9);
+    assert_eq!(
+        chuuren[2].decompositions,
+        vec![
+            Decomposition::Standard {
+                pair: 0,
+                mentsu: vec![
+                    Mentsu::Shuntsu(0),
+                    Mentsu::Shuntsu(2),
+                    Mentsu::Shuntsu(5),
+                    Mentsu::Koutsu(8)
+                ],
+                wait: Wait::Penchan,
+            },
+            Decomposition::Standard {
+                pair: 0,
+                mentsu: vec![
+                    Mentsu::Shuntsu(0),
+                    Mentsu::Shuntsu(2),
+                    Mentsu::Shuntsu(5),
+                    Mentsu::Koutsu(8)
+                ],
+                wait: Wait::Ryanmen,
+            },
+        ]
+    );
+
+    let texts = waits(&suuhai("2233445566778"))
+        .iter()
+        .map(|w| {
+            let texts = w.decompositions.iter().map(|d| d.to_string());
+            (w.tile, texts.collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        texts,
+        vec![
+            (
+                1,
+                vec![
+                    "22 234 345 567 678 (tanki)".to_string(),
+                    "22 234 345 567 678 (ryanmen)".to_string()
+                ]
+            ),
+            (
+                4,
+                vec![
+                    "22 345 345 567 678 (ryanmen)".to_string(),
+                    "55 234 234 567 678 (tanki)".to_string(),
+                    "55 234 234 567 678 (ryanmen)".to_string()
+                ]
+            ),
+            (
+                7,
+                vec![
+                    "22 345 345 678 678 (ryanmen)".to_string(),
+                    "55 234 234 678 678 (ryanmen)".to_string(),
+                    "88 234 234 567 567 (tanki)".to_string(),
+                    "22 33 44 55 66 77 88 (chiitoitsu)".to_string()
+                ]
+            ),
+        ]
+    );
+
+    // 1111 cannot wait on a fifth 1.
+    assert_eq!(wait_tiles(&suuhai("1111234")), vec![3]);
+    assert!(waits(&suuhai("11112")).iter().all(|w| w.tile != 0));
+    assert!(waits(&suuhai("12345678")).is_empty());
+}
+
+#[test]
+fn agrees_with_hand_waits() {
+    let mut rng = Rng::new(5);
+
+    for _ in 0..200 {
+        let mut tiles = (0..36).map(|i| i / 4).collect::<Vec<_>>();
+        rng.shuffle(&mut tiles);
+
+        let mut suuhai = SuuhaiHand::default();
+        let mut hand = Hand::default();
+
+        for &t in tiles[..13].iter() {
+            suuhai[t] += 1;
+            hand[t] += 1;
+        }
+
+        let expected = hand
+            .waits()
+            .into_iter()
+            .filter(|&t| hand[t] < 4)
+            .collect::<Vec<_>>();
+
+        assert_eq!(wait_tiles(&suuhai), expected, "{}", suuhai);
+    }
+}
+
+#[test]
+fn random_tenpai_hands() {
+    let mut rng = Rng::new(0);
+
+    for min_waits in [1, 4, 9] {
+        let hand = random_tenpai(&mut rng, min_waits).unwrap();
+
+        assert_eq!(hand.count(), 13);
+        assert!(waits(&hand).len() >= min_waits);
+    }
+
+    assert_eq!(random_tenpai(&mut rng, 10), None);
+}
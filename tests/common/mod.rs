@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+use shanten_count::tile::{Tile, parse_tiles};
+use shanten_count::wall::{Wall, WallConfig};
+use std::str::FromStr;
+
+/// Hands that do not react to the tiles the tests play.
+pub const QUIET: [&str; 3] = [
+    "345p678p9p666z777z",
+    "123456789s1112z",
+    "234m567m999p333z4z",
+];
+
+pub fn tile(s: &str) -> Tile {
+    Tile::from_str(s).unwrap()
+}
+
+/// A wall dealing `hands` (dealer first) and then `draws`, with the rest of
+/// the set in kind order.
+pub fn stacked_wall(hands: [&str; 4], draws: &str) -> Wall {
+    let hands = hands.map(|h| parse_tiles(h).unwrap());
+    let mut tiles = vec![];
+
+    for i in 0..3 {
+        for hand in hands.iter() {
+            tiles.extend_from_slice(&hand[i * 4..i * 4 + 4]);
+        }
+    }
+
+    for hand in hands.iter() {
+        tiles.push(hand[12]);
+    }
+
+    tiles.extend(parse_tiles(draws).unwrap());
+
+    let mut pool = WallConfig::default().tiles();
+    for t in tiles.iter() {
+        let i = pool.iter().position(|p| p == t).unwrap();
+        pool.remove(i);
+    }
+    tiles.extend(pool);
+
+    return Wall::from_tiles(WallConfig::default(), tiles).unwrap();
+}
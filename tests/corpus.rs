@@ -0,0 +1,92 @@
+#![cfg(any(feature = "std", feature = "precomputed-table"))]
+
+use shanten_count::shanten::Hand;
+use std::str::FromStr;
+
+const INPUT: &str = include_str!("data/input.txt");
+const ANSWER: &str = include_str!("data/answer.txt");
+const HANDS: &str = include_str!("data/hands.txt");
+
+fn shanten_line(hand: &Hand) -> String {
+    format!(
+        "{} {} {}",
+        hand.shanten_standard(),
+        hand.shanten_kokushimusou(),
+        hand.shanten_chiitoitsu()
+    )
+}
+
+#[test]
+fn sample_corpus() {
+    let mut lines = INPUT.lines();
+    let n = lines.next().unwrap().trim().parse::<usize>().unwrap();
+    let answers = ANSWER.lines().collect::<Vec<_>>();
+
+    assert_eq!(answers.len(), n);
+
+    for (i, (line, answer)) in lines.zip(answers).enumerate() {
+        let mut hand = Hand::default();
+
+        for t in line.split_whitespace() {
+            hand[t.parse::<usize>().unwrap()] += 1;
+        }
+
+        assert_eq!(shanten_line(&hand), answer, "line {}: {}", i + 1, line);
+    }
+}
+
+#[test]
+fn mpsz_corpus() {
+    for line in HANDS.lines().filter(|l| !l.starts_with('#')) {
+        let (hand, answer) = line.split_once(' ').unwrap();
+
+        assert_eq!(shanten_line(&Hand::from_str(hand).unwrap()), answer, "{}", hand);
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn testcase_verify() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_shanten"))
+        .args([
+            "--testcase",
+            "--verify",
+            &format!("{}/tests/data/answer.txt", manifest_dir),
+        ])
+        .stdin(std::fs::File::open(format!("{}/tests/data/input.txt", manifest_dir)).unwrap())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "400/400 matched\n");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn testcase_verify_reports_mismatch() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    // One file per process, so concurrent test runs don't share it.
+    let answer_path = std::env::temp_dir().join(format!(
+        "shanten_count_wrong_answer_{}.txt",
+        std::process::id()
+    ));
+
+    let mut answer = ANSWER.lines().map(|l| l.to_string()).collect::<Vec<_>>();
+    answer[2] = "9 9 9".to_string();
+    std::fs::write(&answer_path, answer.join("\n")).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_shanten"))
+        .args(["--testcase", "--verify", answer_path.to_str().unwrap()])
+        .stdin(std::fs::File::open(format!("{}/tests/data/input.txt", manifest_dir)).unwrap())
+        .output()
+        .unwrap();
+    std::fs::remove_file(&answer_path).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout.starts_with("line 3: expected 9 9 9, got "));
+    assert!(stdout.ends_with("399/400 matched\n"));
+}
@@ -0,0 +1,76 @@
+use shanten_count::furiten::{Furiten, furiten};
+use shanten_count::round::{Action, Round, RoundEnd, RoundError, RoundSetup, Rules};
+use shanten_count::shanten::Hand;
+use shanten_count::tile::{Tile, parse_tiles};
+use std::str::FromStr;
+
+mod common;
+
+use common::{QUIET, stacked_wall, tile};
+
+fn tiles(s: &str) -> Vec<Tile> {
+    parse_tiles(s).unwrap()
+}
+
+#[test]
+fn standalone() {
+    // Waits on 1p and 4p.
+    let hand = Hand::from_str("123m456m789m23p55s").unwrap();
+
+    assert_eq!(furiten(&hand, &tiles("9s1z"), &tiles("7m"), false), None);
+    assert_eq!(
+        furiten(&hand, &tiles("9s4p"), &[], false),
+        Some(Furiten::Discard)
+    );
+    assert_eq!(
+        furiten(&hand, &[], &tiles("3s1p"), false),
+        Some(Furiten::Temporary)
+    );
+    assert_eq!(
+        furiten(&hand, &[], &tiles("1p"), true),
+        Some(Furiten::Riichi)
+    );
+    assert_eq!(
+        furiten(&hand, &tiles("1p"), &tiles("4p"), true),
+        Some(Furiten::Discard)
+    );
+
+    let noten = Hand::from_str("123m456m789m19p55s").unwrap();
+    assert_eq!(furiten(&noten, &tiles("1p"), &tiles("9p"), true), None);
+}
+
+#[test]
+fn temporary_furiten_in_a_round() {
+    // North (seat 3) holds an ittsuu waiting on 1p and 2p.
+    let hands = [QUIET[0], QUIET[1], QUIET[2], "123m456m789m1122p"];
+    let setup = RoundSetup {
+        round_wind: 27,
+        dealer: 0,
+        honba: 0,
+        riichi_sticks: 0,
+        scores: vec![25000; 4],
+    };
+    let mut r = Round::new(Rules::default(), stacked_wall(hands, "1p9m2p5z1p"), setup);
+
+    r.apply(0, Action::Discard(tile("1p"))).unwrap();
+    assert!(r.legal_actions(3).contains(&Action::Ron));
+    r.apply(3, Action::Pass).unwrap();
+    assert_eq!(r.player(3).furiten(), Some(Furiten::Temporary));
+
+    r.apply(1, Action::Discard(tile("9m"))).unwrap();
+    r.apply(2, Action::Discard(tile("2p"))).unwrap();
+    assert_eq!(
+        r.legal_actions(3),
+        vec![Action::Pon([tile("2p"), tile("2p")]), Action::Pass]
+    );
+    assert_eq!(r.apply(3, Action::Ron), Err(RoundError::Furiten));
+    r.apply(3, Action::Pass).unwrap();
+
+    // Discarding ends the temporary furiten.
+    r.apply(3, Action::Discard(tile("5z"))).unwrap();
+    assert_eq!(r.player(3).furiten(), None);
+    r.apply(0, Action::Discard(tile("1p"))).unwrap();
+    r.apply(3, Action::Ron).unwrap();
+
+    assert!(matches!(r.result(), Some(RoundEnd::Ron(_))));
+}
@@ -0,0 +1,164 @@
+use shanten_count::game::{Game, GameConfig, GameLength, settle};
+use shanten_count::round::{Action, Phase, Round, RoundEnd, RoundSetup, Rules};
+use shanten_count::tile::Tile;
+use shanten_count::wall::{Wall, WallConfig};
+
+mod common;
+
+use common::{QUIET, stacked_wall, tile};
+
+fn setup(honba: u32, riichi_sticks: u32) -> RoundSetup {
+    RoundSetup {
+        round_wind: 27,
+        dealer: 0,
+        honba,
+        riichi_sticks,
+        scores: vec![25000; 4],
+    }
+}
+
+/// Everyone discards what they draw and never calls.
+fn tsumogiri(
+    phase: &Phase,
+    waiting_for: &[usize],
+    drawn: impl Fn(usize) -> Tile,
+) -> Vec<(usize, Action)> {
+    match phase {
+        Phase::Turn { seat, .. } => vec![(*seat, Action::Discard(drawn(*seat)))],
+        _ => waiting_for.iter().map(|&s| (s, Action::Pass)).collect(),
+    }
+}
+
+#[test]
+fn dealer_tsumo_yakuman() {
+    let wall = stacked_wall(["123m456m789m1122p", QUIET[0], QUIET[1], QUIET[2]], "1p");
+    let mut round = Round::new(Rules::default(), wall, setup(1, 2));
+    round.apply(0, Action::Tsumo).unwrap();
+
+    let settlement = settle(&round).unwrap();
+    assert_eq!(
+        settlement.deltas,
+        vec![48300 + 2000, -16100, -16100, -16100]
+    );
+    assert!(settlement.renchan && settlement.riichi_sticks_taken);
+}
+
+#[test]
+fn ron_with_honba_and_sticks() {
+    let hands = [
+        "123m456m789m1122p",
+        "345p678p55z66z44z9p",
+        "123456789s1112z",
+        "234m567m999p777z5z",
+    ];
+    let mut round = Round::new(Rules::default(), stacked_wall(hands, "5z"), setup(2, 1));
+    round.apply(0, Action::Discard(tile("5z"))).unwrap();
+    round.apply(1, Action::Pass).unwrap();
+    round.apply(3, Action::Ron).unwrap();
+
+    let settlement = settle(&round).unwrap();
+    // chun, 50 fu: 1600 + 600 honba + 1000 riichi stick
+    assert_eq!((settlement.agari[0].han, settlement.agari[0].fu), (1, 50));
+    assert_eq!(settlement.deltas, vec![-2200, 0, 0, 3200]);
+    assert!(!settlement.renchan);
+}
+
+#[test]
+fn noten_payments() {
+    for seed in 0..20 {
+        let rules = Rules {
+            abortive_draws: false,
+            ..Rules::default()
+        };
+        let mut round = Round::new(rules, Wall::new(WallConfig::default(), seed), setup(0, 0));
+
+        while !round.is_over() {
+            for (seat, action) in tsumogiri(round.phase(), &round.waiting_for(), |s| {
+                round.player(s).drawn.unwrap()
+            }) {
+                round.apply(seat, action).unwrap();
+            }
+        }
+
+        let Some(RoundEnd::ExhaustiveDraw { tenpai, .. }) = round.result() else {
+            unreachable!();
+        };
+        let settlement = settle(&round).unwrap();
+        let count = tenpai.iter().filter(|&&t| t).count();
+
+        assert_eq!(settlement.deltas.iter().sum::<i32>(), 0);
+        assert_eq!(settlement.renchan, tenpai[0]);
+
+        for (seat, &t) in tenpai.iter().enumerate() {
+            let expected = match (count, t) {
+                (0, _) | (4, _) => 0,
+                (c, true) => 3000 / c as i32,
+                (c, false) => -3000 / (4 - c as i32),
+            };
+            assert_eq!(settlement.deltas[seat], expected);
+        }
+    }
+}
+
+fn play(config: GameConfig, seed: u64) -> Game {
+    let mut game = Game::new(config, seed);
+
+    while !game.is_over() {
+        let round = game.round();
+        let actions = tsumogiri(round.phase(), &round.waiting_for(), |s| {
+            round.player(s).drawn.unwrap()
+        });
+
+        for (seat, action) in actions {
+            game.apply(seat, action).unwrap();
+        }
+    }
+
+    return game;
+}
+
+#[test]
+fn hanchan_progression() {
+    let game = play(GameConfig::default(), 3);
+    let history = game.history();
+
+    assert!(history.len() >= 8);
+    assert_eq!(history[0].round_wind, 27);
+    assert_eq!(history[0].dealer, 0);
+
+    for pair in history.windows(2) {
+        let renchan = pair[0].settlement.renchan;
+        let (a, b) = (&pair[0], &pair[1]);
+
+        if renchan {
+            assert_eq!((b.dealer, b.round_wind), (a.dealer, a.round_wind));
+        } else {
+            assert_eq!(b.dealer, (a.dealer + 1) % 4);
+        }
+
+        // Every round is a draw, so honba keep piling up.
+        assert_eq!(b.honba, a.honba + 1);
+    }
+
+    let placements = game.placements().unwrap();
+    let last = history.last().unwrap();
+
+    assert!(last.round_wind <= 29);
+    assert_eq!(placements.iter().map(|p| p.score).sum::<i32>(), 100000);
+    assert_eq!(placements.iter().map(|p| p.points).sum::<i32>(), 0);
+    assert!(placements.windows(2).all(|p| p[0].score >= p[1].score));
+}
+
+#[test]
+fn tonpuusen_without_extension_ends_after_east() {
+    let config = GameConfig {
+        length: GameLength::Tonpuusen,
+        extension: false,
+        ..GameConfig::default()
+    };
+    let mut game = play(config, 5);
+
+    assert!(game.history().iter().all(|r| r.round_wind == 27));
+    assert_eq!(game.history().last().unwrap().dealer, 3);
+    assert!(game.apply(0, Action::Pass).is_err());
+}
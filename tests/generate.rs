@@ -0,0 +1,99 @@
+#![cfg(feature = "std")]
+
+use shanten_count::chinitsu;
+use shanten_count::generate::{Constraints, Form, HandGenerator};
+use shanten_count::shanten::{Hand, SuuhaiHand};
+
+fn hands(constraints: &Constraints, seed: u64, n: usize) -> Vec<Hand> {
+    HandGenerator::new(constraints.clone(), seed)
+        .take(n)
+        .map(|tiles| tiles.into_iter().collect())
+        .collect()
+}
+
+#[test]
+fn seeded_and_constrained() {
+    let constraints = Constraints {
+        tiles: 14,
+        shanten: Some(1),
+        ..Constraints::default()
+    };
+    let generated = hands(&constraints, 3, 20);
+
+    assert_eq!(generated.len(), 20);
+    assert_eq!(generated, hands(&constraints, 3, 20));
+    assert_ne!(generated, hands(&constraints, 4, 20));
+
+    for hand in generated.iter() {
+        assert_eq!((hand.count(), hand.shanten()), (14, 1));
+    }
+}
+
+#[test]
+fn chinitsu_with_waits() {
+    let constraints = Constraints {
+        shanten: Some(0),
+        suits: [false, false, true, false],
+        min_waits: 3,
+        ..Constraints::default()
+    };
+
+    for hand in hands(&constraints, 0, 10) {
+        let souzu = SuuhaiHand::from(&core::array::from_fn(|i| hand[18 + i]));
+
+        assert!(souzu.count() == 13);
+        assert!(hand.waits().len() >= 3, "{:?}", hand);
+        assert_eq!(hand.waits().len(), chinitsu::waits(&souzu).len());
+    }
+
+    // 1m would complete the shape only as a fifth copy, so 2m is the one
+    // wait.
+    let hand = "1111222m567p789s".parse::<Hand>().unwrap();
+    let waits = |min_waits| Constraints {
+        tiles: 13,
+        min_waits,
+        ..Constraints::default()
+    };
+
+    assert!(waits(1).accepts(&hand));
+    assert!(!waits(2).accepts(&hand));
+}
+
+#[test]
+fn forms() {
+    for form in [Form::Standard, Form::Chiitoitsu, Form::Kokushimusou] {
+        let constraints = Constraints {
+            shanten: Some(0),
+            form: Some(form),
+            ..Constraints::default()
+        };
+
+        for hand in hands(&constraints, 1, 5) {
+            assert_eq!(form.shanten(&hand), 0);
+            assert_eq!(hand.shanten(), 0);
+        }
+    }
+
+    let seven = Constraints {
+        tiles: 7,
+        form: Some(Form::Chiitoitsu),
+        ..Constraints::default()
+    };
+
+    assert_eq!(HandGenerator::new(seven, 0).generate(), None);
+}
+
+#[test]
+fn gives_up() {
+    // Honors wait on at most two kinds: a tanki or a shanpon.
+    let constraints = Constraints {
+        suits: [false, false, false, true],
+        shanten: Some(0),
+        min_waits: 3,
+        ..Constraints::default()
+    };
+    let mut generator = HandGenerator::new(constraints, 0);
+    generator.max_attempts = 1000;
+
+    assert_eq!(generator.generate(), None);
+}
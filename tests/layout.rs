@@ -0,0 +1,161 @@
+use shanten_count::layout::{LayoutItem, Picture, parse_layout, tile_image};
+use shanten_count::tile::Tile;
+
+#[test]
+fn layout_syntax() {
+    assert_eq!(
+        parse_layout("12m 0p_?7z"),
+        vec![
+            LayoutItem::Tile(Tile::new(0)),
+            LayoutItem::Tile(Tile::new(1)),
+            LayoutItem::Gap,
+            LayoutItem::Tile(Tile::red(13)),
+            LayoutItem::Back,
+            LayoutItem::Blank,
+            LayoutItem::Tile(Tile::new(33)),
+        ]
+    );
+
+    // A number takes the next suit letter, even past other items.
+    assert_eq!(
+        parse_layout("1_2s"),
+        vec![
+            LayoutItem::Tile(Tile::new(18)),
+            LayoutItem::Back,
+            LayoutItem::Tile(Tile::new(19)),
+        ]
+    );
+
+    assert_eq!(
+        parse_layout("8z x 9"),
+        vec![LayoutItem::Gap, LayoutItem::Gap]
+    );
+    assert_eq!(
+        [Tile::red(22), Tile::new(31)].map(tile_image),
+        ["sozu0.png", "jihai5.png"]
+    );
+}
+
+#[test]
+fn sideways_and_melds() {
+    let m = |kind| Tile::new(kind);
+
+    assert_eq!(
+        parse_layout("1'2m3'3'z"),
+        vec![
+            LayoutItem::Sideways(m(0)),
+            LayoutItem::Tile(m(1)),
+            LayoutItem::Stacked(m(29), m(29)),
+        ]
+    );
+
+    assert_eq!(
+        parse_layout("1z[3'24m] [5'0'55p](7777z)"),
+        vec![
+            LayoutItem::Tile(m(27)),
+            LayoutItem::Sideways(m(2)),
+            LayoutItem::Tile(m(1)),
+            LayoutItem::Tile(m(3)),
+            LayoutItem::Gap,
+            LayoutItem::Stacked(m(13), Tile::red(13)),
+            LayoutItem::Tile(m(13)),
+            LayoutItem::Tile(m(13)),
+            LayoutItem::Back,
+            LayoutItem::Tile(m(33)),
+            LayoutItem::Tile(m(33)),
+            LayoutItem::Back,
+        ]
+    );
+
+    // Unmarked melds are called from the left, and an invalid or unclosed
+    // bracket leaves loose tiles.
+    assert_eq!(parse_layout("[555z]")[0], LayoutItem::Sideways(m(31)));
+    assert_eq!(parse_layout("[124m] [12"), parse_layout("124m 12"));
+}
+
+#[test]
+fn placement_and_svg() {
+    let picture = Picture::new(&parse_layout("1m 2m"));
+
+    assert_eq!((picture.width, picture.height), (90 + 40 + 90, 120));
+    assert_eq!(
+        picture
+            .placements
+            .iter()
+            .map(|p| (p.image.as_str(), p.x, p.width))
+            .collect::<Vec<_>>(),
+        vec![
+            ("manzu1.png", 0, 90),
+            ("empty.png", 90, 40),
+            ("manzu2.png", 130, 90)
+        ]
+    );
+
+    let svg = picture.to_svg(|image| format!("images/{}", image));
+
+    assert!(
+        svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"220\" height=\"120\"")
+    );
+    assert!(svg.contains(
+        "<image href=\"images/manzu2.png\" x=\"130\" y=\"0\" width=\"90\" height=\"120\""
+    ));
+    assert_eq!(svg.matches("<image ").count(), 3);
+
+    assert_eq!(Picture::new(&[]), Picture::default());
+
+    // Everything stands on the bottom edge; a rotated image is drawn upright
+    // and turned into its box.
+    let picture = Picture::new(&parse_layout("[5'5'55z]1m"));
+
+    assert_eq!((picture.width, picture.height), (120 + 90 * 3, 180));
+    assert_eq!(
+        picture
+            .placements
+            .iter()
+            .map(|p| (p.x, p.y, p.width, p.height, p.rotated))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, 90, 120, 90, true),
+            (0, 0, 120, 90, true),
+            (120, 60, 90, 120, false),
+            (210, 60, 90, 120, false),
+            (300, 60, 90, 120, false),
+        ]
+    );
+    assert!(picture.to_svg(|image| image.to_string()).contains(
+        "<image href=\"jihai5.png\" x=\"0\" y=\"0\" width=\"90\" height=\"120\" preserveAspectRatio=\"none\" transform=\"translate(0 90) rotate(-90)\"/>"
+    ));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn tile_image_command() {
+    let images = format!("{}/images", env!("CARGO_MANIFEST_DIR"));
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_tile_image"))
+            .args(["--images", &images])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let png = run(&["--height", "60", "1m_ ?"]);
+    assert!(png.status.success());
+    assert_eq!(&png.stdout[..8], b"\x89PNG\r\n\x1a\n");
+    // The IHDR chunk holds the width and the height.
+    assert_eq!(
+        &png.stdout[16..24],
+        [0, 0, 0, 45 * 2 + 20 + 45, 0, 0, 0, 60]
+    );
+
+    let meld = run(&["--height", "60", "[1'23m] (5555z)"]);
+    let size = [60 + 45 * 2 + 20 + 45 * 4, 60u32].map(u32::to_be_bytes);
+    assert_eq!(&meld.stdout[16..24], size.concat());
+
+    let svg = run(&["--format", "svg", "5z"]);
+    let svg = String::from_utf8(svg.stdout).unwrap();
+    assert!(svg.contains("<image href=\"data:image/png;base64,iVBORw0KGgo"));
+
+    assert_eq!(run(&["x"]).status.code(), Some(1));
+    assert_eq!(run(&[]).status.code(), Some(2));
+}
@@ -0,0 +1,197 @@
+use shanten_count::legal::{Prompt, Situation, legal_actions};
+use shanten_count::meld::{Meld, MeldKind};
+use shanten_count::round::{Action, Discard, Player, Rules};
+use shanten_count::tile::parse_tiles;
+
+mod common;
+
+use common::tile;
+
+/// South in a four-player round, mid-game.
+fn situation() -> Situation {
+    Situation {
+        rules: Rules::default(),
+        seat: 1,
+        players: 4,
+        round_wind: 27,
+        seat_wind: 28,
+        score: 25000,
+        remaining: 50,
+        kans: 0,
+        uninterrupted: false,
+    }
+}
+
+/// A player holding `concealed` and, during their turn, `drawn` on top.
+fn player(concealed: &str, drawn: Option<&str>) -> Player {
+    let mut concealed = parse_tiles(concealed).unwrap();
+    let drawn = drawn.map(tile);
+    concealed.extend(drawn);
+
+    Player {
+        concealed,
+        drawn,
+        ..Player::default()
+    }
+}
+
+fn discard(tile: &str) -> Discard {
+    Discard {
+        tile: common::tile(tile),
+        tsumogiri: true,
+        riichi: true,
+        called: false,
+    }
+}
+
+const TURN: Prompt = Prompt::Turn {
+    after_call: false,
+    rinshan: false,
+};
+
+#[test]
+fn riichi_discards_keep_tenpai() {
+    let p = player("123m456m789m11p24p", Some("5s"));
+    let actions = legal_actions(&p, &situation(), TURN);
+    let riichi = actions
+        .iter()
+        .filter(|a| matches!(a, Action::Riichi(_)))
+        .collect::<Vec<_>>();
+
+    assert_eq!(riichi, vec![&Action::Riichi(tile("5s"))]);
+    assert_eq!(
+        actions
+            .iter()
+            .filter(|a| matches!(a, Action::Discard(_)))
+            .count(),
+        13
+    );
+
+    let poor = Situation {
+        score: 900,
+        ..situation()
+    };
+    assert!(
+        !legal_actions(&p, &poor, TURN)
+            .iter()
+            .any(|a| matches!(a, Action::Riichi(_)))
+    );
+}
+
+#[test]
+fn riichi_lock_and_ankan() {
+    let mut p = player("111m456m789m11p23p", Some("1m"));
+    p.discards = vec![discard("9s")];
+    p.riichi = Some(0);
+
+    assert_eq!(
+        legal_actions(&p, &situation(), TURN),
+        vec![Action::Discard(tile("1m")), Action::Ankan(0)]
+    );
+
+    // 1112m waits on 2m and 3m, but only on 2m once the 1m are a kan.
+    let mut p = player("1112m345p678p999s", Some("1m"));
+    p.discards = vec![discard("9s")];
+    p.riichi = Some(0);
+
+    assert_eq!(
+        legal_actions(&p, &situation(), TURN),
+        vec![Action::Discard(tile("1m"))]
+    );
+}
+
+#[test]
+fn tsumo_needs_yaku() {
+    let mut p = player("456p789s23s55m", Some("4s"));
+    p.melds = vec![Meld {
+        kind: MeldKind::Chi,
+        tiles: parse_tiles("123m").unwrap(),
+        called: Some(tile("1m")),
+        from: 3,
+    }];
+
+    assert!(!legal_actions(&p, &situation(), TURN).contains(&Action::Tsumo));
+
+    let p = player("123m456p789s23s55m", Some("4s"));
+    assert_eq!(legal_actions(&p, &situation(), TURN)[0], Action::Tsumo);
+}
+
+#[test]
+fn calls() {
+    let p = player("34m05m67m123p456s1z", None);
+    let from_kamicha = Prompt::Discard {
+        tile: tile("4m"),
+        from: 0,
+    };
+
+    assert_eq!(
+        legal_actions(&p, &situation(), from_kamicha),
+        vec![
+            Action::Chi([tile("3m"), tile("5m")]),
+            Action::Chi([tile("3m"), tile("0m")]),
+            Action::Chi([tile("5m"), tile("6m")]),
+            Action::Chi([tile("0m"), tile("6m")]),
+            Action::Pass,
+        ]
+    );
+
+    let from_toimen = Prompt::Discard {
+        tile: tile("5m"),
+        from: 3,
+    };
+    assert_eq!(
+        legal_actions(&p, &situation(), from_toimen),
+        vec![Action::Pon([tile("5m"), tile("0m")]), Action::Pass]
+    );
+
+    let mut riichi = p.clone();
+    riichi.riichi = Some(0);
+    assert_eq!(
+        legal_actions(&riichi, &situation(), from_toimen),
+        vec![Action::Pass]
+    );
+}
+
+#[test]
+fn kuikae_blocks_calls_leaving_nothing_to_discard() {
+    let mut p = player("1234m", None);
+    p.melds = ["567p", "567s", "678s"]
+        .iter()
+        .map(|tiles| Meld {
+            kind: MeldKind::Chi,
+            tiles: parse_tiles(tiles).unwrap(),
+            called: Some(parse_tiles(tiles).unwrap()[0]),
+            from: 3,
+        })
+        .collect();
+    let prompt = Prompt::Discard {
+        tile: tile("1m"),
+        from: 0,
+    };
+
+    assert_eq!(legal_actions(&p, &situation(), prompt), vec![Action::Pass]);
+
+    let lenient = Situation {
+        rules: Rules {
+            forbid_kuikae: false,
+            ..Rules::default()
+        },
+        ..situation()
+    };
+    assert_eq!(
+        legal_actions(&p, &lenient, prompt),
+        vec![Action::Chi([tile("2m"), tile("3m")]), Action::Pass]
+    );
+}
+
+#[test]
+fn kyuushu_kyuuhai() {
+    let p = player("19m19p19s1234z258m", Some("3p"));
+    let first = Situation {
+        uninterrupted: true,
+        ..situation()
+    };
+
+    assert!(legal_actions(&p, &first, TURN).contains(&Action::KyuushuKyuuhai));
+    assert!(!legal_actions(&p, &situation(), TURN).contains(&Action::KyuushuKyuuhai));
+}
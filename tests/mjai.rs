@@ -0,0 +1,209 @@
+#![cfg(feature = "mjai")]
+
+use shanten_count::agent::GreedyAgent;
+use shanten_count::mjai::{Bot, Event, MjaiError, Pai, State, parse_line, to_line};
+use shanten_count::round::{Action, Rules};
+use shanten_count::tile::Tile;
+
+mod common;
+
+use common::tile;
+
+/// Four rounds seen from seat 0, played by greedy agents in seats 0 and 3
+/// and random agents in seats 1 and 2. They include calls by others, a kakan,
+/// an ankan, riichi, ron and tsumo by seat 0, and exhaustive draws.
+const SESSION: &str = include_str!("data/mjai/session.jsonl");
+
+/// Two rounds seen from seat 1, written by hand with the fields a Mjai
+/// server sends rather than produced by this crate: no scores in
+/// `start_kyoku`, and `hora_tehais`, `yakus` and `hora_points` in `hora`.
+/// Seat 1 chis and then discards the suji of the called tile, and the
+/// scores were worked out by hand.
+const SERVER_SESSION: &str = include_str!("data/mjai/server.jsonl");
+
+fn session() -> Vec<Event> {
+    SESSION
+        .lines()
+        .flat_map(|line| parse_line(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn pai_notation() {
+    for (s, pai) in [
+        ("1m", Pai::Tile(tile("1m"))),
+        ("5pr", Pai::Tile(tile("0p"))),
+        ("9s", Pai::Tile(tile("9s"))),
+        ("E", Pai::Tile(tile("1z"))),
+        ("C", Pai::Tile(tile("7z"))),
+        ("?", Pai::Unknown),
+    ] {
+        assert_eq!(s.parse::<Pai>(), Ok(pai));
+        assert_eq!(pai.to_string(), s);
+    }
+
+    for s in ["0m", "5z", "4mr", "", "10p"] {
+        assert!(s.parse::<Pai>().is_err());
+    }
+}
+
+#[test]
+fn events_round_trip() {
+    for line in SESSION.lines() {
+        let events = parse_line(line).unwrap();
+        let original: serde_json::Value = serde_json::from_str(line).unwrap();
+        let emitted: serde_json::Value = serde_json::from_str(&to_line(&events[0])).unwrap();
+
+        assert_eq!(emitted, original);
+    }
+
+    let batch = format!(
+        "[{}]",
+        SESSION.lines().take(3).collect::<Vec<_>>().join(",")
+    );
+    assert_eq!(parse_line(&batch).unwrap(), session()[..3]);
+    assert!(matches!(
+        parse_line("{\"type\":\"dahai\"}"),
+        Err(MjaiError::Json(_))
+    ));
+}
+
+/// Whether `event` is something seat 0 did, and so the answer to the event
+/// before it.
+fn is_own(event: &Event) -> bool {
+    match event {
+        Event::Dahai { actor, .. }
+        | Event::Reach { actor }
+        | Event::Chi { actor, .. }
+        | Event::Pon { actor, .. }
+        | Event::Daiminkan { actor, .. }
+        | Event::Kakan { actor, .. }
+        | Event::Ankan { actor, .. }
+        | Event::Hora { actor, .. } => *actor == 0,
+        _ => false,
+    }
+}
+
+#[test]
+fn greedy_bot_replays_its_own_decisions() {
+    let events = session();
+    let mut bot = Bot::new(Box::new(GreedyAgent), Rules::default());
+    let mut answers = 0;
+
+    for pair in events.windows(2) {
+        let response = bot.react(&pair[0]).unwrap();
+
+        if !is_own(&pair[1]) {
+            assert_eq!(response, Event::None, "after {:?}", pair[0]);
+            continue;
+        }
+
+        answers += 1;
+
+        match (&response, &pair[1]) {
+            (
+                Event::Hora {
+                    actor, target, pai, ..
+                },
+                Event::Hora {
+                    actor: a,
+                    target: t,
+                    pai: p,
+                    ..
+                },
+            ) => assert_eq!((actor, target, pai), (a, t, p)),
+            _ => assert_eq!(&response, &pair[1]),
+        }
+    }
+
+    assert!(answers > 50);
+    assert_eq!(bot.state().scores, vec![33000, 23000, 21000, 23000]);
+}
+
+#[test]
+fn state_tracks_hand_and_calls() {
+    let lines = [
+        r#"{"type":"start_game","id":1}"#,
+        r#"{"type":"start_kyoku","bakaze":"E","kyoku":1,"honba":0,"kyotaku":0,"oya":0,"dora_marker":"1p","tehais":[["?","?","?","?","?","?","?","?","?","?","?","?","?"],["1m","2m","3m","4p","5pr","6p","7s","8s","9s","E","S","P","P"],["?","?","?","?","?","?","?","?","?","?","?","?","?"],["?","?","?","?","?","?","?","?","?","?","?","?","?"]]}"#,
+        r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+        r#"{"type":"dahai","actor":0,"pai":"P","tsumogiri":true}"#,
+    ];
+    let mut state = State::default();
+
+    for line in lines.iter() {
+        state.update(&parse_line(line).unwrap()[0]).unwrap();
+    }
+
+    assert_eq!(state.seat, 1);
+    assert_eq!(state.seat_wind(1), 28);
+    assert_eq!(state.remaining, 69);
+    assert_eq!(state.me().concealed.len(), 13);
+    assert!(state.me().concealed.contains(&Tile::red(13)));
+
+    let actions = state.observation().unwrap().legal_actions;
+    assert_eq!(
+        actions,
+        vec![Action::Pon([tile("5z"), tile("5z")]), Action::Pass]
+    );
+
+    let pon = r#"{"type":"pon","actor":1,"target":0,"pai":"P","consumed":["P","P"]}"#;
+    state.update(&parse_line(pon).unwrap()[0]).unwrap();
+
+    assert_eq!(state.me().concealed.len(), 11);
+    assert_eq!(state.me().melds[0].from, 3);
+    assert!(state.me().passed.is_empty());
+    assert!(state.players[0].discards[0].called);
+    assert!(!state.uninterrupted);
+
+    let bad = r#"{"type":"dahai","actor":1,"pai":"E","tsumogiri":false}"#;
+    assert_eq!(state.update(&parse_line(bad).unwrap()[0]), Ok(()));
+    assert_eq!(
+        state.update(&parse_line(bad).unwrap()[0]),
+        Err(MjaiError::TileNotInHand(tile("1z")))
+    );
+}
+
+#[test]
+fn state_follows_a_server_session() {
+    let mut state = State::default();
+    state.rules.forbid_kuikae = false;
+    let mut strict = State::default();
+
+    for line in SERVER_SESSION.lines() {
+        let event = &parse_line(line).unwrap()[0];
+
+        match event {
+            Event::Dahai { actor: 1, pai, .. } if *pai == Pai::Tile(tile("3m")) => {
+                // 3m after chiing 6m with 4m5m is kuikae, which only the
+                // default rules forbid.
+                let discard = Action::Discard(tile("3m"));
+                let observation = state.observation().unwrap();
+                assert!(observation.legal_actions.contains(&discard));
+                let observation = strict.observation().unwrap();
+                assert!(!observation.legal_actions.contains(&discard));
+            }
+            Event::Hora { actor: 1, .. } => {
+                // Ron on 7s, with the chi called from the dealer.
+                let observation = state.observation().unwrap();
+                assert!(observation.legal_actions.contains(&Action::Ron));
+                assert_eq!(state.me().melds[0].from, 3);
+            }
+            _ => {}
+        }
+
+        state.update(event).unwrap();
+        strict.update(event).unwrap();
+
+        if let Event::Hora { actor: 1, .. } = event {
+            // Neither the called 6m nor the winning tile was passed.
+            assert!(state.me().passed.is_empty());
+        }
+    }
+
+    assert_eq!(state.seat, 1);
+    assert_eq!(state.dealer, 1);
+    assert_eq!(state.scores, vec![24300, 25700, 26700, 23300]);
+    // The rules outlast start_game and reach the legal actions.
+    assert!(!state.rules.forbid_kuikae);
+    assert_eq!(state.situation().rules, state.rules);
+}
@@ -0,0 +1,349 @@
+use shanten_count::meld::{Meld, MeldKind};
+use shanten_count::mjlog::{MeldCode, Mjlog, MjlogError, MjlogEvent, tile};
+use shanten_count::round::Win;
+use shanten_count::score;
+use shanten_count::shanten::Hand;
+use shanten_count::tile::{Tile, dora_from_indicator};
+use shanten_count::yaku::evaluate;
+use std::str::FromStr;
+
+mod common;
+
+/// Two rounds. The first has an ankan, a chi, a pon, a daiminkan, a kakan,
+/// riichi and a ron; the second ends with kyuushu kyuuhai. The log packs
+/// every tag into a few events and is not a game that could be played: the
+/// riichi comes after calls and the winning hand is not complete.
+const SAMPLE: &str = include_str!("data/mjlog/sample.xml");
+
+/// The first two rounds of a game, written by hand in the layout of a
+/// Tenhou log rather than recorded: a chi and a riichi ron by a pinfu
+/// tanyao hand, then an open haku tsumo. The draws, calls and wins are legal
+/// and the scores were worked out by hand. The log stops without `owari`.
+const GAME: &str = include_str!("data/mjlog/game.xml");
+
+#[test]
+fn meld_codes() {
+    for (m, kind, ids, called, added, from) in [
+        (1071, MeldKind::Chi, vec![1, 5, 8], Some(5), None, 3),
+        (
+            51786,
+            MeldKind::Pon,
+            vec![132, 133, 135],
+            Some(135),
+            None,
+            2,
+        ),
+        (
+            51794,
+            MeldKind::Kakan,
+            vec![132, 133, 134, 135],
+            Some(135),
+            Some(134),
+            2,
+        ),
+        (
+            17665,
+            MeldKind::Daiminkan,
+            vec![68, 69, 70, 71],
+            Some(69),
+            None,
+            1,
+        ),
+        (
+            27648,
+            MeldKind::Ankan,
+            vec![108, 109, 110, 111],
+            None,
+            None,
+            0,
+        ),
+    ] {
+        let code = MeldCode {
+            kind,
+            ids,
+            called,
+            added,
+            from,
+        };
+
+        assert_eq!(MeldCode::decode(m), Ok(code.clone()));
+        assert_eq!(code.encode(), m);
+    }
+
+    // A three-player kita.
+    assert_eq!(
+        MeldCode::decode(0x20),
+        Err(MjlogError::UnsupportedMeld(0x20))
+    );
+}
+
+#[test]
+fn meld_codes_round_trip() {
+    let mut decoded = 0;
+
+    for m in 0..=u16::MAX as u32 {
+        let Ok(code) = MeldCode::decode(m) else {
+            continue;
+        };
+
+        decoded += 1;
+        assert_eq!(MeldCode::decode(code.encode()), Ok(code.clone()), "{}", m);
+
+        let meld = code.to_meld(true);
+        assert_eq!(meld.tiles.len(), code.ids.len());
+        if code.kind == MeldKind::Chi {
+            assert!(
+                meld.tiles
+                    .windows(2)
+                    .all(|w| w[1].kind() == w[0].kind() + 1)
+            );
+        } else {
+            assert!(meld.tiles.iter().all(|t| t.kind() == meld.tiles[0].kind()));
+        }
+    }
+
+    assert!(decoded > 10000);
+}
+
+#[test]
+fn parse_sample() {
+    let log = Mjlog::parse(SAMPLE).unwrap();
+
+    assert_eq!(log.names, vec!["あ", "B", "C", "D"]);
+    assert!(log.aka());
+    assert_eq!(log.rounds.len(), 2);
+    assert_eq!(log.final_scores, Some(vec![17000, 25000, 25000, 33000]));
+
+    let first = &log.rounds[0];
+    assert_eq!(first.round_wind(), 27);
+    assert_eq!(
+        (first.dealer, first.dice, first.dora_indicator),
+        (0, [3, 4], 0)
+    );
+    assert_eq!(first.scores, vec![25000; 4]);
+    assert_eq!(first.events.len(), 21);
+    assert_eq!(
+        first.events[4],
+        MjlogEvent::Discard {
+            seat: 0,
+            id: 5,
+            tsumogiri: false
+        }
+    );
+    assert_eq!(
+        first.events[11],
+        MjlogEvent::Discard {
+            seat: 2,
+            id: 43,
+            tsumogiri: true
+        }
+    );
+
+    let MjlogEvent::Agari(agari) = &first.events[20] else {
+        panic!("{:?}", first.events[20]);
+    };
+    assert_eq!((agari.seat, agari.from, agari.machi), (3, 0, 46));
+    assert_eq!((agari.fu, agari.points), (40, 8000));
+    assert_eq!(agari.yaku, vec![(1, 1), (18, 1), (52, 2)]);
+    assert_eq!(agari.melds[0].kind, MeldKind::Kakan);
+    assert_eq!(agari.ura_dora_indicators, vec![60, 61]);
+    assert_eq!(agari.deltas, vec![-8000, 0, 0, 9000]);
+
+    let second = &log.rounds[1];
+    assert_eq!((second.kyoku, second.dealer), (1, 1));
+    let MjlogEvent::Ryuukyoku(ryuukyoku) = &second.events[1] else {
+        panic!("{:?}", second.events[1]);
+    };
+    assert_eq!(ryuukyoku.reason.as_deref(), Some("yao9"));
+    assert_eq!(ryuukyoku.tenpai, vec![1]);
+}
+
+#[test]
+fn steps_reconstruct_hands() {
+    let log = Mjlog::parse(SAMPLE).unwrap();
+    let steps = log.rounds[0].steps().unwrap();
+    let last = steps.last().unwrap();
+
+    assert_eq!(steps.len(), log.rounds[0].events.len());
+    assert_eq!(steps[0].concealed[0].len(), 14);
+    assert_eq!(steps[1].concealed[0].len(), 10);
+    assert_eq!(steps[1].melds[0][0].kind, MeldKind::Ankan);
+
+    assert_eq!(last.hand(0), Hand::from_str("456789m1223p").unwrap());
+    assert!(last.concealed[0].contains(&Tile::red(4)));
+    assert_eq!(last.hand(1), Hand::from_str("123456789s2z").unwrap());
+    assert_eq!(last.hand(2), Hand::from_str("123456789m1p").unwrap());
+    assert_eq!(last.hand(3), Hand::from_str("1m223344556z").unwrap());
+
+    assert_eq!(
+        last.melds[1],
+        vec![Meld {
+            kind: MeldKind::Chi,
+            tiles: vec![tile(1, true), tile(5, true), tile(8, true)],
+            called: Some(tile(5, true)),
+            from: 3,
+        }]
+    );
+    // The kakan replaces the pon in place.
+    assert_eq!(last.melds[3].len(), 1);
+    assert_eq!(last.melds[3][0].kind, MeldKind::Kakan);
+    assert_eq!(last.melds[2][0].tiles.len(), 4);
+    assert_eq!(last.discards[3].len(), 2);
+    assert_eq!(last.discards[0], vec![tile(5, true), tile(46, true)]);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Mjlog::parse("<T12/>"), Err(MjlogError::NotStarted));
+    assert_eq!(
+        Mjlog::parse("<GO type=\"1\"/><INIT"),
+        Err(MjlogError::Syntax(14))
+    );
+    assert_eq!(
+        Mjlog::parse("<INIT oya=\"0\"/>"),
+        Err(MjlogError::MissingAttribute("seed"))
+    );
+
+    // Tile ids past the 136, seats past the players and dice past 6 are
+    // rejected before anything indexes with them.
+    for (from, to, error) in [
+        ("hai0=\"108,", "hai0=\"200,", MjlogError::InvalidTile(200)),
+        ("<T41/>", "<T136/>", MjlogError::InvalidTile(136)),
+        (
+            "<DORA hai=\"42\"",
+            "<DORA hai=\"999\"",
+            MjlogError::InvalidTile(999),
+        ),
+        ("<N who=\"0\"", "<N who=\"4\"", MjlogError::InvalidSeat(4)),
+        ("oya=\"1\"", "oya=\"7\"", MjlogError::InvalidSeat(7)),
+        (
+            "seed=\"0,0,0,2,3,0\"",
+            "seed=\"0,0,0,2,300,0\"",
+            MjlogError::InvalidDice(300),
+        ),
+        (
+            "seed=\"0,0,0,2,3,0\"",
+            "seed=\"0,0,0,2,3,140\"",
+            MjlogError::InvalidTile(140),
+        ),
+    ] {
+        assert!(SAMPLE.contains(from), "{}", from);
+        assert_eq!(
+            Mjlog::parse(&SAMPLE.replace(from, to)),
+            Err(error),
+            "{}",
+            to
+        );
+    }
+
+    // A three-player log has no fourth seat to draw.
+    let sanma = SAMPLE.replacen(
+        " hai3=\"132,133,69,113,114,116,117,120,121,124,125,128,3\"",
+        "",
+        1,
+    );
+    assert_eq!(Mjlog::parse(&sanma), Err(MjlogError::InvalidSeat(3)));
+
+    let log = Mjlog::parse(&SAMPLE.replace("<D5/>", "<D6/>")).unwrap();
+    assert_eq!(
+        log.rounds[0].steps(),
+        Err(MjlogError::TileNotInHand { seat: 0, id: 6 })
+    );
+}
+
+#[test]
+fn game_wins_score_as_logged() {
+    let log = Mjlog::parse(GAME).unwrap();
+
+    assert_eq!(log.rounds.len(), 2);
+    assert_eq!(log.final_scores, None);
+
+    for (i, round) in log.rounds.iter().enumerate() {
+        let steps = round.steps().unwrap();
+        let last = steps.last().unwrap();
+        let Some(MjlogEvent::Agari(agari)) = round.events.last() else {
+            panic!("{:?}", round.events.last());
+        };
+        let seat = agari.seat;
+        let riichi = round
+            .events
+            .iter()
+            .filter(|e| matches!(e, MjlogEvent::Riichi { step: 2, .. }))
+            .count() as i32;
+        let declared = round.events.contains(&MjlogEvent::Riichi { seat, step: 2 });
+
+        // The replayed hand and the winning tile are the hand the log shows.
+        let winning_tile = tile(agari.machi, round.aka);
+        let mut concealed = last.concealed[seat].clone();
+        if agari.from != seat {
+            concealed.push(winning_tile);
+        }
+        let mut shown = agari
+            .hand
+            .iter()
+            .map(|&id| tile(id, round.aka))
+            .collect::<Vec<_>>();
+        concealed.sort();
+        shown.sort();
+        assert_eq!(concealed, shown, "round {}", i);
+
+        let dora = |ids: &[u8]| {
+            ids.iter()
+                .map(|&id| dora_from_indicator(id as usize / 4, false))
+                .collect()
+        };
+        let win = Win {
+            seat,
+            from: (agari.from != seat).then_some(agari.from),
+            tile: winning_tile,
+            concealed,
+            melds: last.melds[seat].clone(),
+            round_wind: round.round_wind(),
+            seat_wind: 27 + (seat + 4 - round.dealer) % 4,
+            riichi: declared,
+            double_riichi: false,
+            ippatsu: false,
+            haitei: false,
+            houtei: false,
+            rinshan: false,
+            chankan: false,
+            tenhou: false,
+            chiihou: false,
+            dora: dora(&agari.dora_indicators),
+            ura_dora: dora(&agari.ura_dora_indicators),
+        };
+        let scored = evaluate(&win).unwrap();
+        let dealer = seat == round.dealer;
+        let points = match win.from {
+            Some(_) => score::ron_payment(scored.base_points(), dealer),
+            None => {
+                let (child, oya) = score::tsumo_payments(scored.base_points(), dealer);
+                if dealer { child * 3 } else { child * 2 + oya }
+            }
+        };
+        assert_eq!(
+            (scored.fu as u32, points),
+            (agari.fu, agari.points),
+            "round {}",
+            i
+        );
+
+        // The winner also takes the riichi sticks, and the next round starts
+        // from the scores after the deposits and payments.
+        let sticks = 1000 * (round.riichi_sticks as i32 + riichi);
+        assert_eq!(agari.deltas[seat], points as i32 + sticks);
+        assert_eq!(agari.deltas.iter().sum::<i32>(), sticks);
+
+        if let Some(next) = log.rounds.get(i + 1) {
+            let scores = (0..4)
+                .map(|s| {
+                    let deposit = round
+                        .events
+                        .contains(&MjlogEvent::Riichi { seat: s, step: 2 });
+                    round.scores[s] + agari.deltas[s] - 1000 * deposit as i32
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(next.scores, scores);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+#![cfg(any(feature = "std", feature = "precomputed-table"))]
+
+use proptest::prelude::*;
+use shanten_count::reference;
+use shanten_count::shanten::{Hand, YAOCHUU_TILES};
+
+fn hand_from_ids(ids: Vec<usize>) -> Hand {
+    let mut hand = Hand::default();
+
+    for id in ids {
+        hand[id / 4] += 1;
+    }
+
+    return hand;
+}
+
+fn any_hand(sizes: &'static [usize]) -> impl Strategy<Value = Hand> {
+    proptest::sample::select(sizes)
+        .prop_flat_map(|n| proptest::sample::subsequence((0..136).collect::<Vec<_>>(), n))
+        .prop_map(hand_from_ids)
+}
+
+// Draws from one suit and the honors only, which yields far more hands close
+// to tenpai than drawing from the whole wall.
+fn crowded_hand(sizes: &'static [usize]) -> impl Strategy<Value = Hand> {
+    (proptest::sample::select(sizes), 0..3usize)
+        .prop_flat_map(|(n, suit)| {
+            let ids = (suit * 36..suit * 36 + 36).chain(108..136).collect::<Vec<_>>();
+            proptest::sample::subsequence(ids, n)
+        })
+        .prop_map(hand_from_ids)
+}
+
+// Draws mostly terminals and honors so that kokushimusou hands near tenpai
+// show up regularly.
+fn yaochuu_hand(sizes: &'static [usize]) -> impl Strategy<Value = Hand> {
+    let ids = YAOCHUU_TILES
+        .iter()
+        .flat_map(|&t| t * 4..t * 4 + 4)
+        .chain(4..16)
+        .collect::<Vec<_>>();
+
+    proptest::sample::select(sizes)
+        .prop_flat_map(move |n| proptest::sample::subsequence(ids.clone(), n))
+        .prop_map(hand_from_ids)
+}
+
+const LEGAL_SIZES: &[usize] = &[1, 2, 4, 5, 7, 8, 10, 11, 13, 14];
+const CLOSED_SIZES: &[usize] = &[13, 14];
+const WAITING_SIZES: &[usize] = &[1, 4, 7, 10, 13];
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+
+    #[test]
+    fn standard_matches_reference(hand in any_hand(LEGAL_SIZES)) {
+        prop_assert_eq!(hand.shanten_standard(), reference::shanten_standard(&hand));
+    }
+
+    #[test]
+    fn standard_matches_reference_crowded(hand in crowded_hand(LEGAL_SIZES)) {
+        prop_assert_eq!(hand.shanten_standard(), reference::shanten_standard(&hand));
+    }
+
+    #[test]
+    fn chiitoitsu_matches_reference(hand in crowded_hand(CLOSED_SIZES)) {
+        prop_assert_eq!(hand.shanten_chiitoitsu(), reference::shanten_chiitoitsu(&hand));
+    }
+
+    #[test]
+    fn kokushimusou_matches_reference(hand in any_hand(CLOSED_SIZES)) {
+        prop_assert_eq!(hand.shanten_kokushimusou(), reference::shanten_kokushimusou(&hand));
+    }
+
+    #[test]
+    fn kokushimusou_matches_reference_yaochuu(hand in yaochuu_hand(CLOSED_SIZES)) {
+        prop_assert_eq!(hand.shanten_kokushimusou(), reference::shanten_kokushimusou(&hand));
+        prop_assert_eq!(hand.shanten(), reference::shanten(&hand));
+    }
+
+    #[test]
+    fn ukeire_matches_reference(
+        hand in crowded_hand(WAITING_SIZES),
+        seen in proptest::collection::vec(0..5u8, 34),
+    ) {
+        let mut visible = [0; 34];
+
+        for t in 0..34 {
+            visible[t] = seen[t].min(4 - hand[t]);
+        }
+
+        prop_assert_eq!(hand.ukeire(&visible), reference::ukeire(&hand, &visible));
+    }
+}
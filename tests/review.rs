@@ -0,0 +1,127 @@
+#![cfg(any(feature = "std", feature = "precomputed-table"))]
+
+use shanten_count::mjlog::Mjlog;
+use shanten_count::review::{ReviewReport, ReviewSummary};
+use shanten_count::round::Event;
+use shanten_count::tile::parse_tiles;
+
+mod common;
+
+use common::{QUIET, tile};
+
+#[test]
+fn lost_shanten_and_riichi() {
+    let hands = ["123m456m789m113p5z", QUIET[0], QUIET[1], QUIET[2]]
+        .map(|h| parse_tiles(h).unwrap())
+        .to_vec();
+    let draw = |seat, t| Event::Draw {
+        seat,
+        tile: tile(t),
+        rinshan: false,
+    };
+    let discard = |seat, t, riichi| Event::Discard {
+        seat,
+        tile: tile(t),
+        tsumogiri: false,
+        riichi,
+    };
+    let events = [
+        draw(1, "1z"),
+        discard(1, "4p", false),
+        draw(0, "2p"),
+        // Discarding 5z would leave a wait on 1p and 4p.
+        discard(0, "1m", false),
+        draw(0, "9s"),
+        discard(0, "9s", true),
+        // Forced after riichi, so not reviewed.
+        draw(0, "4s"),
+        discard(0, "4s", false),
+    ];
+
+    let mut report = ReviewReport::default();
+    report.add_round(0, &hands, tile("7z"), &events);
+
+    assert_eq!(report.discards.len(), 3);
+
+    let d = &report.discards[1];
+    assert_eq!(d.seat, 0);
+    assert_eq!(d.hand, parse_tiles("123456789m1123p5z").unwrap());
+    assert_eq!((d.shanten, d.best_shanten), (1, 0));
+    // Two 1p and three 4p are left.
+    assert_eq!((d.best.clone(), d.best_ukeire), (vec![31], 5));
+    assert!(d.lost_shanten());
+    assert!(!d.lost_efficiency());
+
+    let summaries = report.summaries();
+    assert_eq!(
+        summaries[0],
+        ReviewSummary {
+            discards: 2,
+            lost_efficiency: 0,
+            lost_shanten: 1,
+            ukeire_lost: 0,
+        }
+    );
+    assert_eq!(
+        summaries[0].to_string(),
+        "0 discards lost efficiency, 1 lost shanten"
+    );
+}
+
+#[test]
+fn discarded_tile_is_not_counted_as_drawable() {
+    let hands = ["123p456p789s1z566m", QUIET[0], QUIET[1], QUIET[2]]
+        .map(|h| parse_tiles(h).unwrap())
+        .to_vec();
+    let events = [
+        Event::Draw {
+            seat: 0,
+            tile: tile("5m"),
+            rinshan: false,
+        },
+        Event::Discard {
+            seat: 0,
+            tile: tile("1p"),
+            tsumogiri: false,
+            riichi: false,
+        },
+    ];
+
+    let mut report = ReviewReport::default();
+    report.add_round(0, &hands, tile("7z"), &events);
+
+    // Only three copies of 1p are left to draw after discarding one.
+    let d = &report.discards[0];
+    assert_eq!((d.shanten, d.ukeire), (1, 14));
+    assert_eq!(
+        (d.best.clone(), d.best_shanten, d.best_ukeire),
+        (vec![27], 0, 4)
+    );
+}
+
+#[test]
+fn mjlog_sample() {
+    let log = Mjlog::parse(include_str!("data/mjlog/sample.xml")).unwrap();
+    let report = ReviewReport::from_mjlog(&log);
+
+    assert_eq!(report.discards.len(), 6);
+
+    // After the ankan, seat 0 breaks the 2m tanki instead of the 2p.
+    let d = &report.discards[0];
+    assert_eq!((d.seat, d.discard), (0, tile("2m")));
+    assert_eq!((d.shanten, d.ukeire), (0, 1));
+    assert_eq!((d.best.clone(), d.best_ukeire), (vec![10], 3));
+    assert!(d.lost_efficiency());
+
+    // Seat 3's discard after riichi is left out.
+    assert_eq!(report.discards.iter().filter(|d| d.seat == 3).count(), 2);
+    assert_eq!(report.summaries()[0].lost_efficiency, 2);
+    assert_eq!(report.summaries()[0].ukeire_lost, 3);
+
+    let tsv = report.to_string();
+    assert_eq!(tsv.lines().count(), 7);
+    assert_eq!(
+        tsv.lines().nth(1),
+        Some("0\t0\t2406789m1223p\t2m\t0\t1\t2p\t0\t3")
+    );
+}
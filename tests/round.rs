@@ -0,0 +1,230 @@
+use shanten_count::meld::MeldKind;
+use shanten_count::round::{
+    AbortiveDraw, Action, Event, Phase, Round, RoundEnd, RoundError, RoundSetup, Rules,
+};
+use shanten_count::wall::{Wall, WallConfig};
+mod common;
+
+use common::{QUIET, stacked_wall, tile};
+
+fn round(hands: [&str; 4], draws: &str) -> Round {
+    let setup = RoundSetup {
+        round_wind: 27,
+        dealer: 0,
+        honba: 0,
+        riichi_sticks: 0,
+        scores: vec![25000; 4],
+    };
+
+    return Round::new(Rules::default(), stacked_wall(hands, draws), setup);
+}
+
+#[test]
+fn tenhou() {
+    let mut round = round(["123m456m789m1122p", QUIET[0], QUIET[1], QUIET[2]], "1p");
+
+    assert_eq!(round.apply(1, Action::Tsumo), Err(RoundError::NotYourTurn));
+    round.apply(0, Action::Tsumo).unwrap();
+
+    let Some(RoundEnd::Tsumo(win)) = round.result() else {
+        panic!("{:?}", round.phase());
+    };
+    assert!(win.tenhou && !win.chiihou);
+    assert_eq!(win.seat_wind, 27);
+    assert_eq!(win.concealed.len(), 14);
+    assert_eq!(
+        round.apply(0, Action::Discard(tile("1p"))),
+        Err(RoundError::RoundOver)
+    );
+}
+
+#[test]
+fn ron_beats_pon() {
+    let hands = [
+        "123m456m789m1122p",
+        "345p678p55z66z44z9p",
+        "123456789s1112z",
+        "234m567m999p777z5z",
+    ];
+
+    let mut r = round(hands, "5z");
+    assert_eq!(r.apply(0, Action::Tsumo), Err(RoundError::NotAgari));
+    r.apply(0, Action::Discard(tile("5z"))).unwrap();
+    assert_eq!(r.waiting_for(), vec![1, 3]);
+    assert_eq!(r.apply(2, Action::Ron), Err(RoundError::AlreadyResponded));
+
+    r.apply(1, Action::Pon([tile("5z"), tile("5z")])).unwrap();
+    r.apply(3, Action::Ron).unwrap();
+
+    let Some(RoundEnd::Ron(wins)) = r.result() else {
+        panic!("{:?}", r.phase());
+    };
+    assert_eq!(wins.len(), 1);
+    assert_eq!((wins[0].seat, wins[0].from), (3, Some(0)));
+    assert!(r.player(1).melds.is_empty());
+
+    let mut r = round(hands, "5z");
+    r.apply(0, Action::Discard(tile("5z"))).unwrap();
+    r.apply(3, Action::Pass).unwrap();
+    r.apply(1, Action::Pon([tile("5z"), tile("5z")])).unwrap();
+
+    assert_eq!(
+        *r.phase(),
+        Phase::Turn {
+            seat: 1,
+            after_call: true,
+            rinshan: false
+        }
+    );
+    assert_eq!(r.player(1).melds[0].kind, MeldKind::Pon);
+    assert_eq!(r.player(1).melds[0].from, 3);
+    assert!(r.player(0).discards[0].called);
+    assert_eq!(r.apply(1, Action::Tsumo), Err(RoundError::InvalidAction));
+}
+
+#[test]
+fn chi_and_kuikae() {
+    let mut r = round(["123m456m789m1122p", QUIET[0], QUIET[1], QUIET[2]], "6p");
+    r.apply(0, Action::Discard(tile("6p"))).unwrap();
+    assert_eq!(r.waiting_for(), vec![1]);
+    assert_eq!(
+        r.apply(1, Action::Chi([tile("3p"), tile("4p")])),
+        Err(RoundError::InvalidMeld)
+    );
+
+    r.apply(1, Action::Chi([tile("7p"), tile("8p")])).unwrap();
+    assert_eq!(
+        r.apply(1, Action::Discard(tile("6p"))),
+        Err(RoundError::Kuikae)
+    );
+    assert_eq!(
+        r.apply(1, Action::Discard(tile("9p"))),
+        Err(RoundError::Kuikae)
+    );
+    r.apply(1, Action::Discard(tile("3p"))).unwrap();
+}
+
+#[test]
+fn riichi() {
+    let mut r = round(
+        ["123m456m789m1122p", QUIET[0], QUIET[1], QUIET[2]],
+        "5z1s2s3s4z",
+    );
+
+    assert_eq!(
+        r.apply(0, Action::Riichi(tile("1m"))),
+        Err(RoundError::NotTenpai)
+    );
+    r.apply(0, Action::Riichi(tile("5z"))).unwrap();
+
+    assert_eq!(r.scores()[0], 24000);
+    assert_eq!(r.riichi_sticks(), 1);
+    assert!(r.player(0).is_riichi() && r.player(0).double_riichi);
+    assert!(r.events().contains(&Event::RiichiAccepted { seat: 0 }));
+
+    for (seat, t) in [(1, "1s"), (2, "2s"), (3, "3s")] {
+        r.apply(seat, Action::Discard(tile(t))).unwrap();
+        for s in r.waiting_for() {
+            if let Phase::Calls { .. } = r.phase() {
+                r.apply(s, Action::Pass).unwrap();
+            }
+        }
+    }
+
+    assert_eq!(r.player(0).drawn, Some(tile("4z")));
+    assert_eq!(
+        r.apply(0, Action::Discard(tile("1m"))),
+        Err(RoundError::RiichiLocked)
+    );
+    assert_eq!(
+        r.apply(0, Action::Riichi(tile("4z"))),
+        Err(RoundError::AlreadyRiichi)
+    );
+    r.apply(0, Action::Discard(tile("4z"))).unwrap();
+    assert!(r.player(0).discards[1].tsumogiri);
+}
+
+#[test]
+fn ankan_reveals_dora_and_draws_rinshan() {
+    let mut r = round(["1111m456m789m112p", QUIET[0], QUIET[1], QUIET[2]], "5z");
+
+    r.apply(0, Action::Ankan(0)).unwrap();
+
+    assert_eq!(r.player(0).melds[0].kind, MeldKind::Ankan);
+    assert_eq!(r.wall().dora_indicators().len(), 2);
+    assert_eq!(r.wall().kan_count(), 1);
+    assert!(matches!(
+        r.phase(),
+        Phase::Turn {
+            seat: 0,
+            rinshan: true,
+            ..
+        }
+    ));
+    assert_eq!(r.player(0).concealed.len(), 11);
+    assert!(!r.is_uninterrupted());
+}
+
+#[test]
+fn kyuushu_kyuuhai() {
+    let mut r = round(["19m1p19s1234z234m5p", QUIET[0], QUIET[1], QUIET[2]], "5z");
+
+    r.apply(0, Action::KyuushuKyuuhai).unwrap();
+    assert_eq!(
+        r.result(),
+        Some(&RoundEnd::Abortive(AbortiveDraw::KyuushuKyuuhai {
+            seat: 0
+        }))
+    );
+
+    let mut r = round(["123m456m789m1122p", QUIET[0], QUIET[1], QUIET[2]], "5z");
+    assert_eq!(
+        r.apply(0, Action::KyuushuKyuuhai),
+        Err(RoundError::NotKyuushuKyuuhai)
+    );
+}
+
+#[test]
+fn tsumogiri_until_exhaustive_draw() {
+    let rules = Rules {
+        abortive_draws: false,
+        ..Rules::default()
+    };
+    let setup = RoundSetup {
+        round_wind: 27,
+        dealer: 2,
+        honba: 0,
+        riichi_sticks: 0,
+        scores: vec![25000; 4],
+    };
+    let mut r = Round::new(rules, Wall::new(WallConfig::default(), 7), setup);
+
+    assert_eq!(r.waiting_for(), vec![2]);
+
+    while !r.is_over() {
+        match r.phase().clone() {
+            Phase::Turn { seat, .. } => {
+                let t = r.player(seat).drawn.unwrap();
+                r.apply(seat, Action::Discard(t)).unwrap();
+            }
+            _ => {
+                for s in r.waiting_for() {
+                    r.apply(s, Action::Pass).unwrap();
+                }
+            }
+        }
+    }
+
+    let Some(RoundEnd::ExhaustiveDraw { tenpai, .. }) = r.result() else {
+        panic!("{:?}", r.phase());
+    };
+    assert_eq!(tenpai.len(), 4);
+    assert_eq!(r.wall().remaining(), 0);
+    assert_eq!(
+        r.events()
+            .iter()
+            .filter(|e| matches!(e, Event::Draw { .. }))
+            .count(),
+        70
+    );
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "serde")]
+
+use shanten_count::shanten::{Hand, TenpaiRule, TenpaiStatus};
+use std::str::FromStr;
+
+#[test]
+fn hand_round_trip() {
+    let hand = Hand::from_str("123m456p789s1122z").unwrap();
+    let json = serde_json::to_string(&hand).unwrap();
+
+    assert_eq!(serde_json::from_str::<Hand>(&json).unwrap(), hand);
+}
+
+#[test]
+fn tenpai_status_round_trip() {
+    let status = Hand::from_str("123m456p789s1112z")
+        .unwrap()
+        .is_tenpai(TenpaiRule::DenyKaraten, &[], &[0; 34]);
+    let json = serde_json::to_string(&status).unwrap();
+
+    assert_eq!(serde_json::from_str::<TenpaiStatus>(&json).unwrap(), status);
+}
@@ -0,0 +1,105 @@
+use shanten_count::shanten::{SuuhaiHand, SuuhaiTable};
+use shanten_count::table::{HAND_COUNT, TARGET_SIZES};
+
+/// Most tiles of the hand so far usable in one partial shape, by the runs
+/// started two and one positions back, the mentsu placed and the pair
+/// placed; `None` where no shape gets there.
+type Overlaps = [[[[Option<u8>; 2]; 5]; 5]; 5];
+
+/// Visits every hand of at most 14 tiles with at most 4 of each number, and
+/// checks each entry of the table against the shape overlapping it most: a
+/// partial shape of `k` tiles is `k` minus that many tiles away.
+fn check_hands(table: &SuuhaiTable, counts: &mut [u8; 9], i: usize, overlaps: &Overlaps) -> usize {
+    if i == 9 {
+        let count = counts.iter().sum::<u8>();
+
+        for k in TARGET_SIZES {
+            let (mentsu, pair) = (k as usize / 3, k as usize % 3 / 2);
+            let expected = match overlaps[0][0][mentsu][pair] {
+                // The search only covers hands that could hold k tiles' worth
+                // of groups.
+                Some(overlap) if count * 3 >= k => Some(k - overlap),
+                _ => None,
+            };
+
+            assert_eq!(table.get(counts, k), expected, "{:?} {}", counts, k);
+        }
+
+        return 1;
+    }
+
+    let mut visited = 0;
+
+    for c in 0..=4 {
+        if counts[..i].iter().sum::<u8>() + c > 14 {
+            break;
+        }
+
+        counts[i] = c;
+
+        let mut next = [[[[None; 2]; 5]; 5]; 5];
+
+        for (a, by_b) in overlaps.iter().enumerate() {
+            for (b, by_mentsu) in by_b.iter().enumerate().take(5 - a) {
+                for (mentsu, by_pair) in by_mentsu.iter().enumerate() {
+                    for (pair, &overlap) in by_pair.iter().enumerate() {
+                        let Some(overlap) = overlap else {
+                            continue;
+                        };
+
+                        // Koutsu, runs starting here and the pair at `i`.
+                        for x in 0..=4 - mentsu {
+                            for y in 0..=4 - mentsu - x {
+                                for z in 0..=1 - pair {
+                                    let used = a + b + 3 * x + y + 2 * z;
+
+                                    if used > 4 || (y > 0 && i > 6) {
+                                        continue;
+                                    }
+
+                                    let slot = &mut next[b][y][mentsu + x + y][pair + z];
+                                    let overlap = overlap + c.min(used as u8);
+                                    *slot = (*slot).max(Some(overlap));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        visited += check_hands(table, counts, i + 1, &next);
+    }
+
+    counts[i] = 0;
+
+    return visited;
+}
+
+#[test]
+fn every_entry_is_the_distance_to_the_nearest_shape() {
+    let mut overlaps = [[[[None; 2]; 5]; 5]; 5];
+    overlaps[0][0][0][0] = Some(0);
+
+    let visited = check_hands(&SuuhaiTable::new(), &mut [0; 9], 0, &overlaps);
+
+    assert_eq!(visited, HAND_COUNT);
+    assert_eq!(HAND_COUNT, 405350);
+}
+
+#[test]
+fn partly_agari_patterns_are_at_distance_zero() {
+    let table = SuuhaiHand::calc_shanten_to_all_partly_pattern();
+
+    for pattern in SuuhaiHand::all_partly_agari_pattern() {
+        assert_eq!(pattern.dist_with(&table, pattern.count()), 0);
+    }
+}
+
+#[cfg(feature = "precomputed-table")]
+#[test]
+fn precomputed_table_matches_runtime_table() {
+    use shanten_count::shanten::{PRECOMPUTED_SUUHAI_TABLE, SuuhaiTable};
+
+    assert!(PRECOMPUTED_SUUHAI_TABLE.as_bytes() == SuuhaiTable::new().as_bytes());
+}
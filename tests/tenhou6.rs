@@ -0,0 +1,323 @@
+#![cfg(feature = "tenhou6")]
+
+use serde_json::json;
+use shanten_count::agent::{Agent, GreedyAgent, RandomAgent, play_game};
+use shanten_count::game::GameConfig;
+use shanten_count::meld::{Meld, MeldKind};
+use shanten_count::round::{RoundEnd, Rules};
+use shanten_count::tenhou6::{
+    Cut, Take, TenhouError, TenhouLog, TenhouRound, meld_string, parse_meld, tile_code,
+    tile_from_code,
+};
+use shanten_count::tile::Tile;
+
+mod common;
+
+use common::tile;
+
+/// East 1 and 2 of a game, written by hand in the shape of a log saved from
+/// the tenhou.net/6 viewer, with the keys such a log carries beside `log`.
+/// It is not a recorded game, but every call and win is legal and the
+/// outcomes were scored by hand. East 1 has a chi, a pon from across, a
+/// daiminkan from the left and a dealer riichi tsumo; East 2 has a pon from
+/// the right that becomes a kakan, an ankan and a ron.
+const VIEWER_LOG: &str = include_str!("data/tenhou6/viewer.json");
+
+#[test]
+fn tile_codes() {
+    for (code, t) in [
+        (11, "1m"),
+        (19, "9m"),
+        (21, "1p"),
+        (35, "5s"),
+        (41, "1z"),
+        (47, "7z"),
+        (51, "0m"),
+        (52, "0p"),
+        (53, "0s"),
+    ] {
+        assert_eq!(tile_from_code(code), Some(tile(t)));
+        assert_eq!(tile_code(tile(t)), code);
+    }
+
+    for code in [0, 10, 20, 40, 48, 50, 54, 60] {
+        assert_eq!(tile_from_code(code), None);
+    }
+
+    for kind in 0..34 {
+        assert_eq!(
+            tile_from_code(tile_code(Tile::new(kind))),
+            Some(Tile::new(kind))
+        );
+    }
+}
+
+fn meld(kind: MeldKind, tiles: &str, called: Option<&str>, from: usize) -> Meld {
+    let mut tiles = shanten_count::tile::parse_tiles(tiles).unwrap();
+    tiles.sort();
+
+    Meld {
+        kind,
+        tiles,
+        called: called.map(tile),
+        from,
+    }
+}
+
+#[test]
+fn meld_strings() {
+    for (s, m, added) in [
+        ("c275226", meld(MeldKind::Chi, "067p", Some("7p"), 3), None),
+        ("p474747", meld(MeldKind::Pon, "777z", Some("7z"), 3), None),
+        ("47p4747", meld(MeldKind::Pon, "777z", Some("7z"), 2), None),
+        ("1515p51", meld(MeldKind::Pon, "055m", Some("0m"), 1), None),
+        (
+            "m39393939",
+            meld(MeldKind::Daiminkan, "9999s", Some("9s"), 3),
+            None,
+        ),
+        (
+            "39m393939",
+            meld(MeldKind::Daiminkan, "9999s", Some("9s"), 2),
+            None,
+        ),
+        (
+            "393939m39",
+            meld(MeldKind::Daiminkan, "9999s", Some("9s"), 1),
+            None,
+        ),
+        ("424242a42", meld(MeldKind::Ankan, "2222z", None, 0), None),
+        (
+            "25k522525",
+            meld(MeldKind::Kakan, "0555p", Some("5p"), 2),
+            Some(tile("0p")),
+        ),
+    ] {
+        let parsed = parse_meld(s).unwrap();
+
+        assert_eq!(parsed, (m.clone(), added), "{}", s);
+        assert_eq!(meld_string(&m, added), s);
+    }
+
+    for s in [
+        "c272826x",
+        "p4747",
+        "474747p",
+        "12p1213",
+        "c414243",
+        "3939m3939",
+        "39393939m",
+        "",
+    ] {
+        assert!(parse_meld(s).is_err(), "{}", s);
+    }
+}
+
+/// Self-play matches, random agents in seats 1 and 2 so that there are calls.
+fn games() -> Vec<shanten_count::game::Game> {
+    (0..3)
+        .map(|seed| {
+            let mut agents: Vec<Box<dyn Agent>> = vec![
+                Box::new(GreedyAgent),
+                Box::new(RandomAgent::new(seed)),
+                Box::new(RandomAgent::new(seed + 100)),
+                Box::new(GreedyAgent),
+            ];
+
+            play_game(GameConfig::default(), &mut agents, seed).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn self_play_round_trip() {
+    let mut calls = [0; 4];
+    let mut wins = 0;
+
+    for game in games() {
+        let log = TenhouLog::from_game(&["A", "B", "C", "D"], &game);
+        let json = log.to_json();
+        let parsed = TenhouLog::parse(&json).unwrap();
+
+        assert_eq!(parsed, log);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&parsed.to_json()).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        );
+
+        let rounds = parsed.to_rounds(Rules::default()).unwrap();
+        assert_eq!(rounds.len(), game.history().len());
+
+        for (replayed, record) in rounds.iter().zip(game.history()) {
+            assert_eq!(replayed.events(), record.round.events());
+            assert_eq!(replayed.result(), record.round.result());
+            assert_eq!(replayed.initial_hands(), record.round.initial_hands());
+        }
+
+        for round in log.rounds.iter() {
+            for take in round.takes.iter().flatten() {
+                if let Take::Call(m) = take {
+                    calls[m.kind as usize] += 1;
+                }
+            }
+            for cut in round.cuts.iter().flatten() {
+                match cut {
+                    Cut::Kakan { .. } => calls[3] += 1,
+                    Cut::Ankan(_) => calls[3] += 1,
+                    _ => {}
+                }
+            }
+            wins += round.outcome.agari.len();
+        }
+    }
+
+    assert!(calls.iter().all(|&c| c > 0), "{:?}", calls);
+    assert!(wins > 5);
+}
+
+#[test]
+fn agari_details() {
+    let game = games().remove(0);
+    let log = TenhouLog::from_game(&["A", "B", "C", "D"], &game);
+
+    for (round, record) in log.rounds.iter().zip(game.history()) {
+        let deltas = round
+            .outcome
+            .agari
+            .iter()
+            .fold(round.outcome.deltas.clone(), |mut acc, a| {
+                acc.resize(4, 0);
+                for (d, delta) in acc.iter_mut().zip(a.deltas.iter()) {
+                    *d += delta;
+                }
+                acc
+            });
+
+        if matches!(record.end, RoundEnd::Abortive(_)) {
+            assert!(deltas.iter().all(|&d| d == 0));
+        } else {
+            assert_eq!(deltas, record.settlement.deltas);
+        }
+
+        for a in round.outcome.agari.iter() {
+            assert!(
+                a.text.ends_with('点') || a.text.ends_with('∀'),
+                "{}",
+                a.text
+            );
+            assert!(
+                a.yaku
+                    .iter()
+                    .all(|y| y.ends_with("飜)") || y.ends_with("(役満)"))
+            );
+        }
+    }
+}
+
+#[test]
+fn import_errors() {
+    assert!(matches!(TenhouLog::parse("{"), Err(TenhouError::Json(_))));
+    assert!(matches!(
+        TenhouLog::parse(r#"{"log":[[[0,0,0]]]}"#),
+        Err(TenhouError::Invalid(_))
+    ));
+
+    let game = games().remove(0);
+    let mut log = TenhouLog::from_game(&["A", "B", "C", "D"], &game);
+
+    // Columns that do not match the four players and a kyoku past North 4.
+    for field in ["scores", "takes", "cuts", "kyoku 16"] {
+        let mut round = log.rounds[0].clone();
+
+        match field {
+            "scores" => round.scores.truncate(3),
+            "takes" => round.takes.truncate(3),
+            "cuts" => round.cuts.push(vec![]),
+            _ => round.kyoku = 16,
+        }
+
+        assert_eq!(
+            round.to_round(Rules::default(), true).map(|_| ()),
+            Err(TenhouError::Invalid(field.into()))
+        );
+    }
+
+    // The same from JSON: a short score list parses but does not replay.
+    let mut value = serde_json::from_str::<serde_json::Value>(&log.to_json()).unwrap();
+    value["log"][0][1].as_array_mut().unwrap().pop();
+    let short = TenhouLog::parse(&value.to_string()).unwrap();
+    assert_eq!(
+        short.to_rounds(Rules::default()).map(|_| ()),
+        Err(TenhouError::Invalid("scores".into()))
+    );
+
+    for head in [json!([16, 0, 0]), json!([-1, 0, 0]), json!([0, -1, 0])] {
+        let mut value = value.clone();
+        value["log"][0][0] = head;
+        assert!(matches!(
+            TenhouLog::parse(&value.to_string()),
+            Err(TenhouError::Invalid(_))
+        ));
+    }
+
+    // A fifth copy of the first tile of the dealer's hand.
+    let round = &mut log.rounds[0];
+    let extra = round.hands[0][0];
+    round.dora_indicators = vec![extra; 5];
+    assert_eq!(
+        round.to_round(Rules::default(), true).map(|_| ()),
+        Err(TenhouError::InconsistentTiles)
+    );
+}
+
+#[test]
+fn viewer_log_replays() {
+    let log = TenhouLog::parse(VIEWER_LOG).unwrap();
+
+    assert_eq!(log.names, vec!["A", "B", "C", "D"]);
+    assert_eq!(log.rule, "般南喰赤");
+    assert!(log.aka);
+
+    let rounds = log.to_rounds(Rules::default()).unwrap();
+
+    // Each marker position gives the meld and the seat it came from.
+    let melds = |round: usize, seat: usize| {
+        rounds[round]
+            .player(seat)
+            .melds
+            .iter()
+            .map(|m| (m.kind, m.from))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(melds(0, 1), vec![(MeldKind::Chi, 3)]);
+    assert_eq!(melds(0, 2), vec![(MeldKind::Pon, 2)]);
+    assert_eq!(melds(0, 3), vec![(MeldKind::Daiminkan, 3)]);
+    assert_eq!(melds(1, 0), vec![(MeldKind::Ankan, 0)]);
+    assert_eq!(melds(1, 2), vec![(MeldKind::Kakan, 1)]);
+    assert_eq!(rounds[1].wall().dora_indicators().len(), 3);
+
+    for (replayed, logged) in rounds.iter().zip(log.rounds.iter()) {
+        let mut recorded = TenhouRound::from_round(replayed);
+
+        assert_eq!(recorded.takes, logged.takes);
+        assert_eq!(recorded.cuts, logged.cuts);
+        assert_eq!(recorded.dora_indicators, logged.dora_indicators);
+
+        // The viewer lists 門前清自摸和 before 立直, the crate after.
+        for agari in recorded.outcome.agari.iter_mut() {
+            agari.yaku.sort();
+        }
+        let mut expected = logged.outcome.clone();
+        for agari in expected.agari.iter_mut() {
+            agari.yaku.sort();
+        }
+        assert_eq!(recorded.outcome, expected);
+    }
+
+    // The dealer's riichi stick goes back to it with the tsumo.
+    let first = &log.rounds[0];
+    let scores = (0..4)
+        .map(|s| first.scores[s] + first.outcome.agari[0].deltas[s] - if s == 0 { 1000 } else { 0 })
+        .collect::<Vec<_>>();
+    assert_eq!(log.rounds[1].scores, scores);
+}
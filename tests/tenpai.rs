@@ -0,0 +1,78 @@
+#![cfg(any(feature = "std", feature = "precomputed-table"))]
+
+use shanten_count::meld::{Meld, MeldKind};
+use shanten_count::shanten::{Hand, TenpaiReason, TenpaiRule};
+use shanten_count::tile::parse_tiles;
+
+/// The concealed hand `s` with a pon of `pon` (if any) called from the left,
+/// and `count` copies of tile `kind` seen elsewhere.
+fn status(
+    s: &str,
+    pon: &str,
+    (kind, count): (usize, u8),
+    rule: TenpaiRule,
+) -> (bool, TenpaiReason, Vec<usize>) {
+    let hand = s.parse::<Hand>().unwrap();
+    let melds = if pon.is_empty() {
+        vec![]
+    } else {
+        let tiles = parse_tiles(pon).unwrap();
+
+        vec![Meld {
+            kind: MeldKind::Pon,
+            called: Some(tiles[0]),
+            tiles,
+            from: 3,
+        }]
+    };
+    let mut counts = [0; 34];
+    counts[kind] = count;
+
+    let status = hand.is_tenpai(rule, &melds, &counts);
+
+    (status.tenpai, status.reason, status.waits)
+}
+
+#[test]
+fn reasons_under_each_rule() {
+    use TenpaiReason::*;
+    use TenpaiRule::*;
+
+    let cases = [
+        ("147m258p369s1357m", "", (0, 0), Noten, vec![]),
+        // Waiting only on a fifth copy is not tenpai.
+        ("1111m234p567p789s", "", (0, 0), Noten, vec![]),
+        ("123m456p1112349s", "", (0, 0), Waiting, vec![26]),
+        ("123m456p1112349s", "", (26, 2), Waiting, vec![26]),
+        // The last 1m is in the hand and the rest in the player's own pon.
+        ("1m234p567p789s", "111m", (0, 0), JunKaraten, vec![0]),
+        ("123m456p1112349s", "", (26, 3), Karaten, vec![26]),
+    ];
+
+    for (hand, pon, visible, reason, waits) in cases {
+        for (rule, tenpai) in [
+            (AllowKaraten, reason != Noten),
+            (DenyJunKaraten, matches!(reason, Waiting | Karaten)),
+            (DenyKaraten, reason == Waiting),
+        ] {
+            assert_eq!(
+                status(hand, pon, visible, rule),
+                (tenpai, reason, waits.clone()),
+                "{} {} {:?} {:?}",
+                hand,
+                pon,
+                visible,
+                rule
+            );
+        }
+    }
+}
+
+#[test]
+fn waits_exclude_a_fifth_copy() {
+    let hand = "1111222m567p789s".parse::<Hand>().unwrap();
+
+    // 1m completes the shape only as a fifth copy.
+    assert_eq!(hand.waits(), vec![2]);
+    assert_eq!(hand.shanten(), 0);
+}
@@ -0,0 +1,102 @@
+use shanten_count::shanten::Hand;
+use shanten_count::tile::{Tile, dora_from_indicator, parse_tiles, tiles_to_string};
+use shanten_count::wall::{Wall, WallConfig};
+use std::str::FromStr;
+
+#[test]
+fn same_seed_same_wall() {
+    let a = Wall::new(WallConfig::default(), 42);
+    let b = Wall::new(WallConfig::default(), 42);
+    let c = Wall::new(WallConfig::default(), 43);
+
+    assert_eq!(a.tiles(), b.tiles());
+    assert_ne!(a.tiles(), c.tiles());
+}
+
+#[test]
+fn tile_sets() {
+    let tiles = WallConfig::default().tiles();
+    assert_eq!(tiles.len(), 136);
+    assert_eq!(tiles.iter().filter(|t| t.is_red()).count(), 3);
+
+    let sanma = WallConfig {
+        sanma: true,
+        red_fives: [0, 2, 2],
+    };
+    let tiles = sanma.tiles();
+    assert_eq!(tiles.len(), 108);
+    assert_eq!(tiles.iter().filter(|t| t.is_red()).count(), 4);
+    assert!(tiles.iter().all(|t| !(1..8).contains(&t.kind())));
+}
+
+#[test]
+fn deal_and_draw_until_exhausted() {
+    let mut wall = Wall::new(WallConfig::default(), 1);
+    let hands = wall.deal();
+
+    assert_eq!(hands.len(), 4);
+    assert!(hands.iter().all(|h| h.count() == 13));
+    assert_eq!(wall.remaining(), 136 - 14 - 52);
+
+    assert!(wall.draw_rinshan().is_some());
+    assert!(wall.reveal_dora().is_some());
+    assert_eq!(wall.remaining(), 136 - 14 - 52 - 1);
+    assert_eq!(wall.dora_indicators().len(), 2);
+    assert_eq!(wall.ura_dora_indicators().len(), 2);
+
+    let mut drawn = 0;
+    while wall.draw().is_some() {
+        drawn += 1;
+    }
+
+    assert_eq!(drawn, 136 - 14 - 52 - 1);
+    assert!(wall.draw_rinshan().is_none());
+}
+
+#[test]
+fn sanma_deals_three_hands() {
+    let config = WallConfig {
+        sanma: true,
+        red_fives: [0, 1, 1],
+    };
+    let mut wall = Wall::new(config, 7);
+
+    assert_eq!(wall.deal().len(), 3);
+    assert_eq!(wall.remaining(), 108 - 14 - 39);
+}
+
+#[test]
+fn fixed_arrangement() {
+    let config = WallConfig::default();
+    let mut tiles = config.tiles();
+    tiles.reverse();
+
+    let mut wall = Wall::from_tiles(config, tiles.clone()).unwrap();
+    assert_eq!(wall.dora_indicators(), &tiles[136 - 10..136 - 9]);
+    assert_eq!(wall.draw(), Some(tiles[0]));
+
+    tiles.pop();
+    tiles.push(Tile::new(33));
+    assert!(Wall::from_tiles(config, tiles).is_none());
+}
+
+#[test]
+fn dora_indicators() {
+    assert_eq!(dora_from_indicator(8, false), 0);
+    assert_eq!(dora_from_indicator(0, true), 8);
+    assert_eq!(dora_from_indicator(30, false), 27);
+    assert_eq!(dora_from_indicator(33, false), 31);
+}
+
+#[test]
+fn mpsz_tiles() {
+    let tiles = parse_tiles("120m5p77z").unwrap();
+
+    assert_eq!(tiles_to_string(&tiles), "120m5p77z");
+    assert_eq!(
+        tiles.iter().copied().collect::<Hand>(),
+        Hand::from_str("125m5p77z").unwrap()
+    );
+    assert_eq!(Tile::from_str("0s"), Ok(Tile::red(22)));
+    assert!(parse_tiles("8z").is_err());
+}
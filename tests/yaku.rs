@@ -0,0 +1,143 @@
+use shanten_count::meld::{Meld, MeldKind};
+use shanten_count::round::Win;
+use shanten_count::score::{self, Limit};
+use shanten_count::tile::{Tile, parse_tiles};
+use shanten_count::yaku::{Wait, Yaku, evaluate};
+use std::str::FromStr;
+
+/// A non-dealer win in the east round as south.
+fn win(concealed: &str, melds: Vec<Meld>, tile: &str, tsumo: bool) -> Win {
+    Win {
+        seat: 1,
+        from: if tsumo { None } else { Some(0) },
+        tile: Tile::from_str(tile).unwrap(),
+        concealed: parse_tiles(concealed).unwrap(),
+        melds,
+        round_wind: 27,
+        seat_wind: 28,
+        riichi: false,
+        double_riichi: false,
+        ippatsu: false,
+        haitei: false,
+        houtei: false,
+        rinshan: false,
+        chankan: false,
+        tenhou: false,
+        chiihou: false,
+        dora: vec![],
+        ura_dora: vec![],
+    }
+}
+
+fn meld(kind: MeldKind, tiles: &str) -> Meld {
+    let tiles = parse_tiles(tiles).unwrap();
+
+    Meld {
+        kind,
+        called: Some(tiles[0]),
+        tiles,
+        from: 3,
+    }
+}
+
+#[test]
+fn point_tables() {
+    assert_eq!(score::base_points(1, 30), 240);
+    assert_eq!(score::ron_payment(240, false), 1000);
+    assert_eq!(score::ron_payment(score::base_points(4, 30), true), 11600);
+    assert_eq!(
+        score::tsumo_payments(score::base_points(3, 30), false),
+        (1000, 2000)
+    );
+    assert_eq!(
+        score::tsumo_payments(score::base_points(2, 40), true),
+        (1300, 1300)
+    );
+    assert_eq!(score::limit(4, 40), Some(Limit::Mangan));
+    assert_eq!(score::limit(3, 60), None);
+    assert_eq!(score::limit(7, 30), Some(Limit::Haneman));
+    assert_eq!(score::limit(13, 30), Some(Limit::KazoeYakuman));
+    assert_eq!(Limit::Yakuman(2).base_points(), 16000);
+}
+
+#[test]
+fn pinfu_tsumo() {
+    let agari = evaluate(&win("234m567p345s678s88s", vec![], "8s", true)).unwrap();
+
+    assert_eq!(
+        agari.yaku,
+        vec![Yaku::MenzenTsumo, Yaku::Pinfu, Yaku::Tanyao]
+    );
+    assert_eq!((agari.han, agari.fu), (3, 20));
+    assert_eq!(agari.wait, Wait::Ryanmen);
+    assert_eq!(
+        score::tsumo_payments(agari.base_points(), false),
+        (700, 1300)
+    );
+}
+
+#[test]
+fn chiitoitsu() {
+    let mut w = win("11m22m33p44p55s66s77z", vec![], "7z", false);
+    w.dora = vec![1];
+    let agari = evaluate(&w).unwrap();
+
+    assert_eq!(agari.yaku, vec![Yaku::Chiitoitsu]);
+    assert_eq!((agari.han, agari.fu, agari.dora), (4, 25, 2));
+}
+
+#[test]
+fn open_yakuhai_is_rounded_to_30_fu() {
+    let melds = vec![meld(MeldKind::Pon, "555z")];
+    let agari = evaluate(&win("234m567p345s88s", melds, "8s", false)).unwrap();
+
+    assert_eq!(agari.yaku, vec![Yaku::Haku]);
+    assert_eq!((agari.han, agari.fu), (1, 30));
+    assert_eq!(score::ron_payment(agari.base_points(), false), 1000);
+}
+
+#[test]
+fn no_yaku() {
+    let melds = vec![meld(MeldKind::Chi, "123m")];
+
+    assert_eq!(evaluate(&win("456p789s234s55m", melds, "5m", false)), None);
+    assert_eq!(
+        evaluate(&win("123m456p789s234s55m", vec![], "5m", false)),
+        None
+    );
+}
+
+#[test]
+fn shanpon_ron_is_not_concealed() {
+    let ron = evaluate(&win("111m222p333s444z55z", vec![], "4z", false)).unwrap();
+
+    assert_eq!(ron.yaku, vec![Yaku::Toitoi, Yaku::Sanankou]);
+    assert_eq!(ron.fu, 60);
+    assert_eq!(ron.limit(), Some(Limit::Mangan));
+
+    let tsumo = evaluate(&win("111m222p333s444z55z", vec![], "4z", true)).unwrap();
+
+    assert_eq!(tsumo.yaku, vec![Yaku::Suuankou]);
+    assert_eq!(tsumo.limit(), Some(Limit::Yakuman(1)));
+}
+
+#[test]
+fn yakuman() {
+    let kokushi = evaluate(&win("19m19p19s1234567z1m", vec![], "1m", false)).unwrap();
+    assert_eq!(kokushi.yaku, vec![Yaku::Kokushimusou]);
+
+    let daisangen = vec![meld(MeldKind::Pon, "555z"), meld(MeldKind::Pon, "666z")];
+    let agari = evaluate(&win("777z123m99p", daisangen, "9p", false)).unwrap();
+    assert_eq!(agari.yaku, vec![Yaku::Daisangen]);
+
+    let chuuren = evaluate(&win("11123456789995m", vec![], "5m", true)).unwrap();
+    assert_eq!(chuuren.yaku, vec![Yaku::Chuurenpoutou]);
+}
+
+#[test]
+fn best_interpretation() {
+    // Also seven pairs, but ryanpeikou scores more.
+    let agari = evaluate(&win("112233m445566p99s", vec![], "6p", false)).unwrap();
+    assert_eq!(agari.yaku, vec![Yaku::Pinfu, Yaku::Ryanpeikou]);
+    assert_eq!((agari.han, agari.fu), (4, 30));
+}